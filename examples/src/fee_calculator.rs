@@ -0,0 +1,29 @@
+use near_workspaces::types::{FeeAction, NearToken};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let (alice, bob) = (
+        worker.dev_create_account().await?,
+        worker.dev_create_account().await?,
+    );
+
+    // NOTE: this API is under the "experimental" flag and no guarantees are given.
+    let fees = worker.fee_calculator().await?;
+
+    let balance = alice.view_account().await?.balance;
+    let amount = NearToken::from_near(1);
+    alice.transfer_near(bob.id(), amount).await?.into_result()?;
+
+    // The exact amount alice got charged can be calculated upfront:
+    let expected = balance
+        .saturating_sub(amount)
+        .saturating_sub(fees.cost_of(&[FeeAction::Transfer], false));
+    assert_eq!(alice.view_account().await?.balance, expected);
+    println!(
+        "Transferring NEAR costs {}",
+        fees.cost_of(&[FeeAction::Transfer], false)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+//! Assertion helpers for the checks that come up over and over again in contract tests,
+//! where getting the expected values right by hand is error prone. Failed assertions panic
+//! like [`assert!`] does, while errors talking to the network are returned as usual.
+
+use crate::result::{ExecutionFinalResult, Result};
+use crate::types::{AccountId, NearToken};
+use crate::Account;
+
+/// Delete `account` with the funds going to `beneficiary_id`, and assert that the
+/// beneficiary received the balance of the deleted account, minus the tokens burnt on fees
+/// of the deletion. The beneficiary's balance is allowed to be off from the expected one by
+/// up to `tolerance`, which covers gas refunds and fees that can't be known upfront.
+///
+/// Returns the execution details of deleting the account.
+pub async fn assert_delete_account_refund(
+    account: Account,
+    beneficiary_id: &AccountId,
+    tolerance: NearToken,
+) -> Result<ExecutionFinalResult> {
+    let worker = account.worker().clone();
+    let balance = account.view_account().await?.balance;
+    let before = worker.view_account(beneficiary_id).await?.balance;
+
+    let account_id = account.id().clone();
+    let result = account.delete_account(beneficiary_id).await?;
+    let burnt = result
+        .outcomes()
+        .iter()
+        .fold(NearToken::from_yoctonear(0), |burnt, outcome| {
+            burnt.saturating_add(outcome.tokens_burnt)
+        });
+
+    let after = worker.view_account(beneficiary_id).await?.balance;
+    let received = after.saturating_sub(before);
+    let expected = balance.saturating_sub(burnt);
+    let off_by = if received > expected {
+        received.saturating_sub(expected)
+    } else {
+        expected.saturating_sub(received)
+    };
+    assert!(
+        off_by <= tolerance,
+        "beneficiary {} received {} from deleting {}, while {} was expected (tolerance {})",
+        beneficiary_id,
+        received,
+        account_id,
+        expected,
+        tolerance,
+    );
+
+    Ok(result)
+}
@@ -0,0 +1,172 @@
+//! Blocking facade over [`Worker`](crate::Worker), for scripts and tooling that would rather
+//! not be written in async code, such as small deployment scripts or `build.rs` files.
+//!
+//! A blocking [`Worker`] carries its own tokio runtime, which it drives on every call. The
+//! most common operations have blocking counterparts on [`Worker`], while everything else
+//! is reachable through [`Worker::block_on`]:
+//! ```no_run
+//! use near_workspaces::blocking;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let worker = blocking::sandbox()?;
+//! let contract = worker.dev_deploy(&std::fs::read("res/status_message.wasm")?)?;
+//!
+//! let outcome = worker.block_on(
+//!     contract
+//!         .call("set_status")
+//!         .args_json(serde_json::json!({ "message": "hello" }))
+//!         .transact(),
+//! )?;
+//! assert!(outcome.is_success());
+//!
+//! let status: String = worker
+//!     .view(contract.id(), "get_status", serde_json::json!({ "account_id": contract.id() }))?
+//!     .json()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Note that blocking workers cannot be used from within an async context, as the runtime
+//! they carry cannot be started from within another runtime.
+
+use std::future::IntoFuture;
+use std::sync::Arc;
+
+use tokio::runtime::Runtime;
+
+use crate::error::ErrorKind;
+use crate::network::{Mainnet, MockNetwork, Sandbox, Testnet};
+use crate::result::ViewResultDetails;
+use crate::types::{AccountDetails, NearToken};
+use crate::{Account, AccountId, Contract, DevNetwork, Network, Result};
+
+/// Spin up a new sandbox instance, and grab a blocking [`Worker`] that interacts with it.
+pub fn sandbox() -> Result<Worker<Sandbox>> {
+    Worker::connect(crate::sandbox())
+}
+
+/// Connect to the testnet network, and grab a blocking [`Worker`] that can interact with it.
+pub fn testnet() -> Result<Worker<Testnet>> {
+    Worker::connect(crate::testnet())
+}
+
+/// Connect to the mainnet network, and grab a blocking [`Worker`] that can interact with it.
+pub fn mainnet() -> Result<Worker<Mainnet>> {
+    Worker::connect(crate::mainnet())
+}
+
+/// Spin up a mock network answering requests with registered fixtures, and grab a blocking
+/// [`Worker`] that interacts with it.
+pub fn mock() -> Result<Worker<MockNetwork>> {
+    Worker::connect(crate::mock())
+}
+
+/// Blocking counterpart of [`crate::Worker`], driving the async one on a runtime of its own.
+pub struct Worker<T: ?Sized> {
+    // Declared before the runtime, such that background tasks of the worker are gone by
+    // the time the runtime shuts down.
+    worker: crate::Worker<T>,
+    runtime: Arc<Runtime>,
+}
+
+impl<T: ?Sized> Clone for Worker<T> {
+    fn clone(&self) -> Self {
+        Self {
+            worker: self.worker.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<T> Worker<T> {
+    /// Create a blocking worker out of anything resolving to an async worker, such as a
+    /// network builder with options specified on it:
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// let worker = near_workspaces::blocking::Worker::connect(
+    ///     near_workspaces::testnet().rpc_addr("https://rpc.testnet.example.com"),
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn connect<F>(worker: F) -> Result<Self>
+    where
+        F: IntoFuture<Output = Result<crate::Worker<T>>>,
+    {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| ErrorKind::Io.full("failed to create runtime for blocking worker", e))?;
+        let worker = runtime.block_on(worker.into_future())?;
+        Ok(Self {
+            worker,
+            runtime: Arc::new(runtime),
+        })
+    }
+}
+
+impl<T: ?Sized> Worker<T> {
+    /// Run `future` to completion on the runtime of this worker, blocking until it is done.
+    /// This is how any of the async operations not having a blocking counterpart, such as
+    /// the ones of [`Account`] and [`Contract`], are used.
+    pub fn block_on<F: IntoFuture>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future.into_future())
+    }
+
+    /// The async worker this drives, for passing on to code expecting one. Futures created
+    /// from it should be run with [`Worker::block_on`].
+    pub fn as_async(&self) -> &crate::Worker<T> {
+        &self.worker
+    }
+}
+
+impl<T> Worker<T>
+where
+    T: Network + 'static,
+{
+    /// Blocking counterpart of [`crate::Worker::view_account`].
+    pub fn view_account(&self, account_id: &AccountId) -> Result<AccountDetails> {
+        self.block_on(self.worker.view_account(account_id))
+    }
+
+    /// Blocking counterpart of [`crate::Worker::view`], serializing `args` to JSON.
+    pub fn view(
+        &self,
+        contract_id: &AccountId,
+        function: &str,
+        args: impl serde::Serialize,
+    ) -> Result<ViewResultDetails> {
+        self.block_on(self.worker.view(contract_id, function).args_json(args))
+    }
+
+    /// Blocking counterpart of [`crate::Worker::gas_price`].
+    pub fn gas_price(&self) -> Result<NearToken> {
+        self.block_on(self.worker.gas_price())
+    }
+}
+
+impl<T> Worker<T>
+where
+    T: DevNetwork + 'static,
+{
+    /// Blocking counterpart of [`crate::Worker::dev_create_account`].
+    pub fn dev_create_account(&self) -> Result<Account> {
+        self.block_on(self.worker.dev_create_account())
+    }
+
+    /// Blocking counterpart of [`crate::Worker::dev_deploy`].
+    pub fn dev_deploy(&self, wasm: &[u8]) -> Result<Contract> {
+        self.block_on(self.worker.dev_deploy(wasm))
+    }
+}
+
+impl<T: ?Sized> std::fmt::Debug for Worker<T>
+where
+    crate::Worker<T>: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Worker")
+            .field("worker", &self.worker)
+            .finish()
+    }
+}
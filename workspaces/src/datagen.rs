@@ -0,0 +1,254 @@
+//! Generators of realistic test data, such as token metadata following the NEP standards,
+//! corpora of account IDs, and skewed balance distributions, for populating worlds that load
+//! and fuzz tests run against. Every generator takes the random number generator to draw
+//! from, so a seeded one reproduces the same data on every run:
+//!
+//! ```
+//! use near_workspaces::datagen;
+//! use near_workspaces::types::NearToken;
+//! use rand::{rngs::StdRng, SeedableRng};
+//!
+//! let mut rng = StdRng::seed_from_u64(42);
+//! let metadata = datagen::ft_metadata(&mut rng);
+//! let holders = datagen::account_ids(&mut rng, &"token.test.near".parse().unwrap(), 100);
+//! let balances = datagen::balances(&mut rng, holders.len(), NearToken::from_near(1_000_000));
+//! assert_eq!(holders.len(), balances.len());
+//! # let _ = metadata;
+//! ```
+
+use std::collections::BTreeSet;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::types::NearToken;
+use crate::AccountId;
+
+const ADJECTIVES: &[&str] = &[
+    "amber", "brave", "calm", "cosmic", "crimson", "golden", "hidden", "lucky", "lunar", "mellow",
+    "nimble", "quiet", "rapid", "silver", "solar", "swift", "wild", "velvet",
+];
+
+const NOUNS: &[&str] = &[
+    "badger", "bridge", "comet", "falcon", "forest", "harbor", "lantern", "meadow", "otter",
+    "pixel", "river", "rocket", "summit", "tiger", "vault", "willow", "wolf", "zephyr",
+];
+
+const FIRST_NAMES: &[&str] = &[
+    "alice", "bob", "carol", "dave", "erin", "frank", "grace", "heidi", "ivan", "judy", "mallory",
+    "niaj", "olivia", "peggy", "rupert", "sybil", "trent", "victor", "walter",
+];
+
+const TOKEN_SUFFIXES: &[&str] = &["Coin", "Token", "Cash", "Dollar", "Credits", "Points"];
+
+const DECIMALS: &[u8] = &[6, 8, 18, 18, 18, 24, 24];
+
+/// Metadata of a fungible token, as returned by `ft_metadata` of [NEP-148] contracts.
+///
+/// [NEP-148]: https://nomicon.io/Standards/Tokens/FungibleToken/Metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FungibleTokenMetadata {
+    pub spec: String,
+    pub name: String,
+    pub symbol: String,
+    pub icon: Option<String>,
+    pub reference: Option<String>,
+    pub reference_hash: Option<String>,
+    pub decimals: u8,
+}
+
+/// Metadata of a non-fungible token contract, as returned by `nft_metadata` of [NEP-177]
+/// contracts.
+///
+/// [NEP-177]: https://nomicon.io/Standards/Tokens/NonFungibleToken/Metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftContractMetadata {
+    pub spec: String,
+    pub name: String,
+    pub symbol: String,
+    pub icon: Option<String>,
+    pub base_uri: Option<String>,
+    pub reference: Option<String>,
+    pub reference_hash: Option<String>,
+}
+
+/// Metadata of a single non-fungible token, following [NEP-177].
+///
+/// [NEP-177]: https://nomicon.io/Standards/Tokens/NonFungibleToken/Metadata
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftTokenMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub media: Option<String>,
+    pub media_hash: Option<String>,
+    pub copies: Option<u64>,
+    pub issued_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub starts_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub extra: Option<String>,
+    pub reference: Option<String>,
+    pub reference_hash: Option<String>,
+}
+
+/// Generate the metadata of a fungible token, with a made up name and symbol, an SVG icon and
+/// one of the number of decimals commonly used on NEAR, 24 and 18 being the most frequent.
+pub fn ft_metadata<R: Rng + ?Sized>(rng: &mut R) -> FungibleTokenMetadata {
+    let (name, symbol) = token_name(rng);
+    let suffix = TOKEN_SUFFIXES.choose(rng).unwrap();
+    FungibleTokenMetadata {
+        spec: "ft-1.0.0".into(),
+        name: format!("{name} {suffix}"),
+        symbol,
+        icon: Some(svg_icon(rng)),
+        reference: None,
+        reference_hash: None,
+        decimals: *DECIMALS.choose(rng).unwrap(),
+    }
+}
+
+/// Generate the metadata of a non-fungible token contract, with a made up name and symbol, an
+/// SVG icon and an IPFS gateway as the base URI of its media.
+pub fn nft_contract_metadata<R: Rng + ?Sized>(rng: &mut R) -> NftContractMetadata {
+    let (name, symbol) = token_name(rng);
+    NftContractMetadata {
+        spec: "nft-1.0.0".into(),
+        name: format!("{name} Collection"),
+        symbol,
+        icon: Some(svg_icon(rng)),
+        base_uri: Some("https://ipfs.io/ipfs".into()),
+        reference: None,
+        reference_hash: None,
+    }
+}
+
+/// Generate the metadata of the non-fungible token numbered `index` within its collection, with
+/// a title, a description and media relative to the base URI of the collection.
+pub fn nft_token_metadata<R: Rng + ?Sized>(rng: &mut R, index: u64) -> NftTokenMetadata {
+    let adjective = ADJECTIVES.choose(rng).unwrap();
+    let noun = NOUNS.choose(rng).unwrap();
+    let cid = (0..46)
+        .map(|_| char::from(rng.sample(rand::distributions::Alphanumeric)))
+        .collect::<String>();
+    NftTokenMetadata {
+        title: Some(format!(
+            "{} {} #{}",
+            capitalize(adjective),
+            capitalize(noun),
+            index
+        )),
+        description: Some(format!(
+            "A {adjective} {noun}, number {index} of the collection."
+        )),
+        media: Some(format!("{cid}/{index}.png")),
+        copies: Some(1),
+        issued_at: Some(
+            rng.gen_range(1_600_000_000_000u64..1_800_000_000_000)
+                .to_string(),
+        ),
+        ..Default::default()
+    }
+}
+
+/// Generate `count` distinct subaccount IDs of `parent`, mixing the shapes real users pick,
+/// such as plain names, names with digits and words joined by dashes or underscores. IDs that
+/// would not fit within the 64 characters limit of account IDs get shortened.
+pub fn account_ids<R: Rng + ?Sized>(
+    rng: &mut R,
+    parent: &AccountId,
+    count: usize,
+) -> Vec<AccountId> {
+    let max_len = 63usize.saturating_sub(parent.len()).max(8);
+    let mut seen = BTreeSet::new();
+    let mut ids = Vec::with_capacity(count);
+    while ids.len() < count {
+        let mut name = account_name(rng);
+        name.truncate(max_len);
+        if seen.contains(&name) {
+            // Popular names get taken quickly, so fall back to numbered ones like users do:
+            name.truncate(max_len - 6);
+            name = format!("{}{:06}", name, rng.gen_range(0..1_000_000));
+        }
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+        if let Ok(id) = format!("{name}.{parent}").parse() {
+            ids.push(id);
+        }
+    }
+    ids
+}
+
+/// Split `total` into `count` balances following a Pareto distribution, such that a few
+/// accounts hold most of the supply and a long tail holds little, like the holders of a real
+/// token. The balances add up to exactly `total`, in no particular order.
+pub fn balances<R: Rng + ?Sized>(rng: &mut R, count: usize, total: NearToken) -> Vec<NearToken> {
+    // The shape parameter behind the 80/20 rule:
+    const ALPHA: f64 = 1.16;
+
+    if count == 0 {
+        return Vec::new();
+    }
+    let weights = (0..count)
+        .map(|_| (1.0 - rng.gen::<f64>()).powf(-1.0 / ALPHA))
+        .collect::<Vec<_>>();
+    let sum = weights.iter().sum::<f64>();
+
+    let total = total.as_yoctonear();
+    let mut amounts = weights
+        .iter()
+        .map(|weight| (total as f64 * (weight / sum)) as u128)
+        .map(|amount| amount.min(total))
+        .collect::<Vec<_>>();
+    // Rounding errors go to the largest holder, keeping the total exact:
+    let allotted = amounts
+        .iter()
+        .fold(0u128, |sum, amount| sum.saturating_add(*amount));
+    let largest = (0..count).max_by_key(|i| amounts[*i]).unwrap();
+    if allotted <= total {
+        amounts[largest] += total - allotted;
+    } else {
+        amounts[largest] -= (allotted - total).min(amounts[largest]);
+    }
+    amounts.into_iter().map(NearToken::from_yoctonear).collect()
+}
+
+fn token_name<R: Rng + ?Sized>(rng: &mut R) -> (String, String) {
+    let adjective = ADJECTIVES.choose(rng).unwrap();
+    let noun = NOUNS.choose(rng).unwrap();
+    let name = format!("{} {}", capitalize(adjective), capitalize(noun));
+    let mut symbol = format!("{}{}", &adjective[..1], &noun[..rng.gen_range(1..=3)]);
+    symbol.make_ascii_uppercase();
+    (name, symbol)
+}
+
+fn account_name<R: Rng + ?Sized>(rng: &mut R) -> String {
+    let first = FIRST_NAMES.choose(rng).unwrap();
+    let adjective = ADJECTIVES.choose(rng).unwrap();
+    let noun = NOUNS.choose(rng).unwrap();
+    match rng.gen_range(0..6) {
+        0 => first.to_string(),
+        1 => format!("{first}{}", rng.gen_range(1..100)),
+        2 => format!("{first}{}", rng.gen_range(1970..2010)),
+        3 => format!("{adjective}-{noun}"),
+        4 => format!("{first}_{noun}"),
+        _ => format!("{adjective}{noun}{}", rng.gen_range(0..10_000)),
+    }
+}
+
+fn svg_icon<R: Rng + ?Sized>(rng: &mut R) -> String {
+    let color = rng.gen_range(0..0x100_0000u32);
+    format!(
+        "data:image/svg+xml,%3Csvg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 32 32'%3E\
+         %3Ccircle cx='16' cy='16' r='16' fill='%23{color:06x}'/%3E%3C/svg%3E"
+    )
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
@@ -185,6 +185,14 @@ impl RpcErrorCode {
     {
         Error::custom(ErrorKind::Rpc(self), error)
     }
+
+    pub(crate) fn full<T, E>(self, msg: T, error: E) -> Error
+    where
+        T: Into<Cow<'static, str>>,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Error::full(ErrorKind::Rpc(self), msg, error)
+    }
 }
 
 impl From<RpcErrorCode> for Error {
@@ -27,6 +27,9 @@ pub enum ErrorKind {
     /// An error from converting data.
     #[error("DataConversion")]
     DataConversion,
+    /// An operation did not complete within the time it was given.
+    #[error("Timeout")]
+    Timeout,
     /// An error that cannot be categorized into the other error kinds.
     #[error("Other")]
     Other,
@@ -82,6 +85,10 @@ pub enum SandboxErrorCode {
     PatchStateFailure,
     #[error("Sandbox failed to fast forward")]
     FastForwardFailure,
+    #[error("Sandbox failed to snapshot state")]
+    SnapshotFailure,
+    #[error("Sandbox failed to restore state")]
+    RestoreFailure,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
@@ -101,4 +108,8 @@ pub enum RpcErrorCode {
     QueryFailure,
     #[error("incorrect variant retrieved while querying (maybe a bug in RPC code?)")]
     QueryReturnedInvalidData,
+    #[error("the queried data exceeds the size limits of the rpc node")]
+    QueryResultTooLarge,
+    #[error("the rpc node does not track the shard the queried data lives in")]
+    UnavailableShard,
 }
@@ -2,6 +2,32 @@
 //!
 //! A library for automating workflows and writing tests for NEAR smart contracts.
 //! This software is not final, and will likely change.
+//!
+//! ## Async runtime
+//!
+//! Workspaces is built on top of [tokio](https://tokio.rs), as the HTTP client talking to
+//! the RPC node requires it, so workers need to be created and used within a tokio runtime.
+//! Both the multi-threaded and the current-thread runtime work, so the runtime set up by
+//! `#[tokio::test]` is enough. Some networks run tasks in the background, such as the server
+//! answering requests of [`mock`] or capturing the logs of [`sandbox`], which get spawned on
+//! the runtime the worker was created in, so a worker should not outlive its runtime.
+//!
+//! To use workspaces from another executor, such as async-std or smol, run the test itself
+//! on a tokio runtime, or through a compatibility layer such as
+//! [async-compat](https://docs.rs/async-compat). Creating a worker outside of a tokio
+//! runtime fails with an error instead of panicking:
+//! ```
+//! fn test() -> anyhow::Result<()> {
+//!     let runtime = tokio::runtime::Builder::new_current_thread()
+//!         .enable_all()
+//!         .build()?;
+//!     runtime.block_on(async {
+//!         let worker = near_workspaces::sandbox().await?;
+//!         // ...
+//!         Ok(())
+//!     })
+//! }
+//! ```
 
 // We want to enable all clippy lints, but some of them generate false positives.
 #![allow(clippy::missing_const_for_fn, clippy::redundant_pub_crate)]
@@ -15,10 +41,14 @@ pub use cargo_near_build;
 
 mod worker;
 
+pub mod assertions;
+pub mod blocking;
+pub mod datagen;
 pub mod error;
 pub mod network;
 pub mod operations;
 pub mod prelude;
+pub mod registry;
 pub mod result;
 pub mod rpc;
 pub mod types;
@@ -29,15 +59,15 @@ pub use near_abi_client;
 
 pub use network::pick_unused_port;
 pub use network::variants::{DevNetwork, Network};
-pub use result::Result;
+pub use result::{compare, Result};
 pub use types::account::{Account, AccountDetailsPatch, Contract};
 pub use types::block::Block;
 pub use types::chunk::Chunk;
 pub use types::{AccessKey, AccountId, BlockHeight, CryptoHash, InMemorySigner};
 pub use worker::{
-    betanet, mainnet, mainnet_archival, sandbox, sandbox_with_version, testnet, testnet_archival,
-    with_betanet, with_mainnet, with_mainnet_archival, with_sandbox, with_testnet,
-    with_testnet_archival, Worker,
+    betanet, mainnet, mainnet_archival, mock, sandbox, sandbox_attach, sandbox_with_version,
+    testnet, testnet_archival, with_betanet, with_mainnet, with_mainnet_archival, with_sandbox,
+    with_testnet, with_testnet_archival, Worker,
 };
 
 #[cfg(feature = "unstable")]
@@ -1,50 +1,127 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use near_gas::NearGas;
 use url::Url;
 
-use crate::network::builder::{FromNetworkBuilder, NetworkBuilder};
-use crate::network::{Info, NetworkClient, NetworkInfo};
-use crate::rpc::client::Client;
+use near_primitives::views::ExecutionStatusView;
 
-use std::path::PathBuf;
+use crate::network::builder::{FromNetworkBuilder, NetworkBuilder};
+use crate::network::{Info, NetworkClient, NetworkInfo, RootAccountSubaccountCreator};
+use crate::result::{Execution, ExecutionDetails, ExecutionFinalResult, ExecutionOutcome, Result};
+use crate::rpc::{client::Client, tool};
+use crate::types::{AccountId, InMemorySigner, NearToken, SecretKey};
+use crate::{Account, Contract, CryptoHash, Network, Worker};
 
 /// URL to the betanet RPC node provided by near.org.
 pub const RPC_URL: &str = "https://rpc.betanet.near.org";
 
+/// URL to the helper contract used to create named accounts provided by near.org.
+pub const HELPER_URL: &str = "https://helper.betanet.near.org";
+
 /// Betanet related configuration for interacting with betanet.
 ///
 /// Look at [`workspaces::betanet`] for how to spin up a [`Worker`] that can be
-/// used to interact with betanet. Note that betanet account creation
-/// is not currently supported, and these calls into creating a betanet
-/// worker is meant for retrieving data and/or making queries only.
-/// Also, note that betanet can be unstable and does not provide an
-/// archival endpoint similar to that of mainnet.
+/// used to run tests in betanet. Accounts are created as subaccounts of the
+/// `betanet` root account through the helper service, similar to testnet.
+/// Note that betanet can be unstable and does not provide an archival endpoint
+/// similar to that of mainnet.
 ///
 /// [`workspaces::betanet`]: crate::betanet
-/// [`workspaces::betanet_archival`]: crate::betanet_archival
 /// [`Worker`]: crate::Worker
 pub struct Betanet {
     client: Client,
     info: Info,
 }
 
-#[async_trait::async_trait]
+#[async_trait]
 impl FromNetworkBuilder for Betanet {
-    async fn from_builder<'a>(build: NetworkBuilder<'a, Self>) -> crate::result::Result<Self> {
-        let rpc_url = build.rpc_addr.unwrap_or_else(|| RPC_URL.into());
-        let client = Client::new(&rpc_url, build.api_key)?;
+    async fn from_builder<'a>(build: NetworkBuilder<'a, Self>) -> Result<Self> {
+        let rpc_url = build.rpc_addr.clone().unwrap_or_else(|| RPC_URL.into());
+        let client = build.client(&rpc_url).await?;
         client.wait_for_rpc().await?;
 
         Ok(Self {
             client,
             info: Info {
                 name: build.name.into(),
-                root_id: "near".parse().unwrap(),
-                keystore_path: PathBuf::from(".near-credentials/betanet/"),
+                root_id: AccountId::from_str("betanet").unwrap(),
+                keystore_path: build.keystore_path_or(".near-credentials/betanet/"),
                 rpc_url: Url::parse(&rpc_url).expect("url is hardcoded"),
             },
         })
     }
 }
 
+impl std::fmt::Debug for Betanet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Betanet")
+            .field("root_id", &self.info.root_id)
+            .field("rpc_url", &self.info.rpc_url)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl RootAccountSubaccountCreator for Betanet {
+    fn root_account_id(&self) -> Result<AccountId> {
+        Ok(self.info().root_id.clone())
+    }
+
+    async fn create_root_account_subaccount(
+        &self,
+        worker: Worker<dyn Network>,
+        subaccount_prefix: AccountId,
+        sk: SecretKey,
+    ) -> Result<Execution<Account>> {
+        let id = self.compute_subaccount_id(subaccount_prefix)?;
+        let url = Url::parse(HELPER_URL).unwrap();
+        // only registrar can create tla on betanet, so must concatenate random created id with .betanet
+        tool::url_create_account(url, id.clone(), sk.public_key()).await?;
+        let signer = InMemorySigner::from_secret_key(id, sk);
+
+        Ok(Execution {
+            result: Account::new(signer, worker),
+            details: ExecutionFinalResult {
+                // We technically have not burnt any gas ourselves since someone else paid to
+                // create the account for us in betanet when we used the Helper contract.
+                total_gas_burnt: NearGas::from_gas(0),
+
+                status: near_primitives::views::FinalExecutionStatus::SuccessValue(Vec::new()),
+                details: ExecutionDetails {
+                    transaction: ExecutionOutcome {
+                        transaction_hash: CryptoHash::default(),
+                        block_hash: CryptoHash::default(),
+                        logs: Vec::new(),
+                        receipt_ids: Vec::new(),
+                        gas_burnt: NearGas::from_gas(0),
+                        tokens_burnt: NearToken::from_near(0),
+                        executor_id: "betanet".parse().unwrap(),
+                        status: ExecutionStatusView::SuccessValue(Vec::new()),
+                    },
+                    receipts: Vec::new(),
+                },
+                method_names: Vec::new(),
+            },
+        })
+    }
+
+    async fn create_root_account_subaccount_and_deploy(
+        &self,
+        worker: Worker<dyn Network>,
+        subaccount_prefix: AccountId,
+        sk: SecretKey,
+        wasm: &[u8],
+    ) -> Result<Execution<Contract>> {
+        let account = self
+            .create_root_account_subaccount(worker, subaccount_prefix.clone(), sk)
+            .await?;
+        let account = account.into_result()?;
+
+        account.deploy(wasm).await
+    }
+}
+
 impl NetworkClient for Betanet {
     fn client(&self) -> &Client {
         &self.client
@@ -1,9 +1,18 @@
 use std::future::{Future, IntoFuture};
 use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::network::Sandbox;
+use crate::rpc::cassette::Cassette;
+use crate::rpc::client::{Client, HttpOptions};
+use crate::rpc::middleware::Layer;
+use crate::rpc::retry::RetryPolicy;
+use crate::rpc::tool;
 use crate::{Network, Worker};
 
+use super::config::GenesisConfig;
 use super::server::ValidatorKey;
 
 pub(crate) type BoxFuture<'a, T> = std::pin::Pin<Box<dyn Future<Output = T> + Send + 'a>>;
@@ -28,8 +37,18 @@ pub(crate) trait FromNetworkBuilder: Sized {
 pub struct NetworkBuilder<'a, T> {
     pub(crate) name: &'a str,
     pub(crate) rpc_addr: Option<String>,
+    pub(crate) fallback_rpc_addrs: Vec<String>,
     pub(crate) validator_key: Option<ValidatorKey>,
     pub(crate) api_key: Option<String>,
+    pub(crate) genesis: Option<GenesisConfig>,
+    pub(crate) cassette: Option<Cassette>,
+    pub(crate) layers: Vec<Arc<dyn Layer>>,
+    pub(crate) keystore_path: Option<PathBuf>,
+    pub(crate) version: Option<String>,
+    pub(crate) capture_logs: bool,
+    pub(crate) keep_files: bool,
+    pub(crate) retry_policy: Option<RetryPolicy>,
+    pub(crate) http: HttpOptions,
     _network: PhantomData<T>,
 }
 
@@ -42,6 +61,7 @@ where
 
     fn into_future(self) -> Self::IntoFuture {
         let fut = async {
+            tool::ensure_tokio_runtime()?;
             let network = FromNetworkBuilder::from_builder(self).await?;
             Ok(Worker::new(network))
         };
@@ -54,8 +74,18 @@ impl<'a, T> NetworkBuilder<'a, T> {
         Self {
             name,
             rpc_addr: None,
+            fallback_rpc_addrs: Vec::new(),
             validator_key: None,
             api_key: None,
+            genesis: None,
+            cassette: None,
+            layers: Vec::new(),
+            keystore_path: None,
+            version: None,
+            capture_logs: false,
+            keep_files: false,
+            retry_policy: None,
+            http: HttpOptions::default(),
             _network: PhantomData,
         }
     }
@@ -72,6 +102,40 @@ impl<'a, T> NetworkBuilder<'a, T> {
         self
     }
 
+    /// Adds an RPC node to fail over to when the one of this network, or any fallback added
+    /// before this one, can't be reached, times out or fails internally. Requests go to the
+    /// first healthy node, with nodes that failed being skipped for a while, which keeps long
+    /// running suites going through an outage of a single node.
+    pub fn fallback_rpc_addr(mut self, addr: &str) -> Self {
+        self.fallback_rpc_addrs.push(addr.into());
+        self
+    }
+
+    /// Sets the RPC nodes of this network, with the first one being used as the [`rpc_addr`]
+    /// and the rest as [fallbacks].
+    ///
+    /// ```
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let worker = near_workspaces::testnet()
+    ///     .rpc_addrs(&[
+    ///         "https://rpc.testnet.near.org",
+    ///         "https://test.rpc.fastnear.com",
+    ///     ])
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`rpc_addr`]: NetworkBuilder::rpc_addr
+    /// [fallbacks]: NetworkBuilder::fallback_rpc_addr
+    pub fn rpc_addrs(mut self, addrs: &[&str]) -> Self {
+        if let Some((addr, fallbacks)) = addrs.split_first() {
+            self.rpc_addr = Some(addr.to_string());
+            self.fallback_rpc_addrs = fallbacks.iter().map(|addr| addr.to_string()).collect();
+        }
+        self
+    }
+
     /// Sets the API key for this network. Useful for setting the API key to an RPC
     /// server that requires it.
     ///
@@ -81,6 +145,133 @@ impl<'a, T> NetworkBuilder<'a, T> {
         self.api_key = Some(api_key.into());
         self
     }
+
+    /// Sets an HTTP header sent along with every RPC request made through this network, such
+    /// as for authenticating with hosted RPC providers that don't use the `x-api-key` header
+    /// of [`api_key`]. Can be called multiple times to send multiple headers.
+    ///
+    /// ```
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let worker = near_workspaces::mainnet()
+    ///     .rpc_addr("https://rpc.mainnet.example.com")
+    ///     .header("Authorization", "Bearer my-token")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`api_key`]: NetworkBuilder::api_key
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.http.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Record every RPC request made through this network, along with the response to it,
+    /// into the cassette file at `path`. The file can be [`replay`]ed later on to rerun the
+    /// same test deterministically and offline, which is useful for tests depending on
+    /// testnet or mainnet. An existing file at `path` gets overwritten.
+    ///
+    /// Note that only JSON-RPC requests are recorded, so requests to other services such as
+    /// the testnet helper used to create dev accounts still go out to the network. Cassettes
+    /// are not supported by sandbox.
+    ///
+    /// [`replay`]: NetworkBuilder::replay
+    pub fn record(mut self, path: impl AsRef<Path>) -> Self {
+        self.cassette = Some(Cassette::Record(path.as_ref().into()));
+        self
+    }
+
+    /// Answer every RPC request made through this network from the cassette file at `path`
+    /// that was previously created with [`record`], instead of contacting the RPC node.
+    /// Responses are replayed in the order they were recorded in.
+    ///
+    /// [`record`]: NetworkBuilder::record
+    pub fn replay(mut self, path: impl AsRef<Path>) -> Self {
+        self.cassette = Some(Cassette::Replay(path.as_ref().into()));
+        self
+    }
+
+    /// Add a middleware [`Layer`] that every RPC request made through this network passes
+    /// through. Layers run in the order they were added in. Look at the [`middleware`] module
+    /// for more details.
+    ///
+    /// [`middleware`]: crate::rpc::middleware
+    pub fn layer(mut self, layer: impl Layer + 'static) -> Self {
+        self.layers.push(Arc::new(layer));
+        self
+    }
+
+    /// Sets the directory credentials of this network are kept in, instead of the default
+    /// `.near-credentials/<network>/` relative to the current directory. This is exposed as
+    /// [`Info::keystore_path`], and is useful for keeping the credentials of separate test
+    /// processes apart.
+    ///
+    /// [`Info::keystore_path`]: crate::network::Info::keystore_path
+    pub fn keystore_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.keystore_path = Some(path.as_ref().into());
+        self
+    }
+
+    /// Sets the policy for retrying RPC requests that failed, instead of the default one
+    /// retrying up to 4 times. Look at [`RetryPolicy`] for more info.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Sets how long to wait for the connection to the RPC node to be established, after
+    /// which requests fail with an [`ErrorKind::Timeout`] error. By default, this waits
+    /// indefinitely.
+    ///
+    /// [`ErrorKind::Timeout`]: crate::error::ErrorKind::Timeout
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.http.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets how long to wait for each RPC request to complete, after which it fails with an
+    /// [`ErrorKind::Timeout`] error, such that a hung RPC node does not stall tests. Note
+    /// that this also bounds requests waiting on transactions to be executed, so it should
+    /// be well above the time the slowest transaction takes. By default, this waits
+    /// indefinitely.
+    ///
+    /// [`ErrorKind::Timeout`]: crate::error::ErrorKind::Timeout
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.http.request_timeout = Some(timeout);
+        self
+    }
+
+    /// The keystore directory specified with [`keystore_path`], or `default` otherwise.
+    ///
+    /// [`keystore_path`]: NetworkBuilder::keystore_path
+    pub(crate) fn keystore_path_or(&self, default: &str) -> PathBuf {
+        self.keystore_path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(default))
+    }
+
+    /// Create the client to talk to the RPC node at `rpc_url` through, which goes by way of
+    /// the middleware layers and cassette if any were specified, failing over to the
+    /// fallback RPC nodes.
+    pub(crate) async fn client(&self, rpc_url: &str) -> crate::result::Result<Client> {
+        let mut layers = self.layers.clone();
+        if let Some(cassette) = &self.cassette {
+            // The cassette comes last, such that the other layers also run when replaying.
+            layers.push(cassette.layer()?);
+        }
+
+        let mut client = if layers.is_empty() && self.fallback_rpc_addrs.is_empty() {
+            Client::new(rpc_url, self.api_key.clone(), &self.http)?
+        } else {
+            let mut rpc_addrs = vec![rpc_url.to_string()];
+            rpc_addrs.extend(self.fallback_rpc_addrs.iter().cloned());
+            Client::with_layers(&rpc_addrs, self.api_key.clone(), &self.http, layers).await?
+        };
+        if let Some(policy) = &self.retry_policy {
+            client.retry_policy = policy.clone();
+        }
+        Ok(client)
+    }
 }
 
 // So far, only Sandbox makes use of validator_key.
@@ -94,4 +285,43 @@ impl NetworkBuilder<'_, Sandbox> {
         self.validator_key = Some(validator_key);
         self
     }
+
+    /// Specify the [`GenesisConfig`] to start up the sandbox node with, such as initial accounts
+    /// and balances, the epoch length or gas prices. This is only applicable to sandboxes spawned
+    /// by workspaces, since the genesis of an already running node cannot be changed.
+    pub fn genesis(mut self, genesis: GenesisConfig) -> Self {
+        self.genesis = Some(genesis);
+        self
+    }
+
+    /// Specify the version of the sandbox node to download and run, such as `"1.35.0"`,
+    /// instead of the default one this crate comes with. This is useful for testing against
+    /// the exact protocol version running on mainnet. This is only applicable to sandboxes
+    /// spawned by workspaces.
+    pub fn version(mut self, version: &str) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Capture the output of the sandbox node instead of letting it go straight to the
+    /// output of the test. Captured lines are forwarded to `tracing` under the `near-sandbox`
+    /// target, and the latest ones are kept around to be viewed with [`Worker::sandbox_logs`],
+    /// such as for printing them next to a failing assertion. The node only logs errors
+    /// unless `NEAR_ENABLE_SANDBOX_LOG=1` is set. This is only applicable to sandboxes
+    /// spawned by workspaces.
+    ///
+    /// [`Worker::sandbox_logs`]: crate::Worker::sandbox_logs
+    pub fn capture_logs(mut self, capture: bool) -> Self {
+        self.capture_logs = capture;
+        self
+    }
+
+    /// Leave the home directory of the sandbox node on disk once it shuts down, instead of
+    /// removing it, and print out where it is. The directory holds the chain data, config and
+    /// keys of the node, which is useful for post-mortem debugging of failed tests. This is
+    /// only applicable to sandboxes spawned by workspaces.
+    pub fn keep_files(mut self, keep: bool) -> Self {
+        self.keep_files = keep;
+        self
+    }
 }
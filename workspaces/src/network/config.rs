@@ -18,6 +18,7 @@ use std::str::FromStr;
 use serde_json::Value;
 
 use crate::error::ErrorKind;
+use crate::types::{AccountId, Gas, NearToken, PublicKey};
 use crate::Result;
 
 /// Overwrite the $home_dir/config.json file over a set of entries. `value` will be used per (key, value) pair
@@ -142,6 +143,150 @@ fn overwrite_genesis(home_dir: impl AsRef<Path>) -> Result<()> {
     Ok(())
 }
 
+/// Genesis parameters to start up a sandbox node with, which is useful for contracts that are
+/// sensitive to protocol parameters such as the epoch length or gas prices. Anything left unset
+/// keeps the default value that the sandbox was initialized with.
+///
+/// ```
+/// use near_workspaces::network::GenesisConfig;
+/// use near_workspaces::types::{Gas, NearToken};
+///
+/// # async fn run() -> anyhow::Result<()> {
+/// let genesis = GenesisConfig::new()
+///     .epoch_length(100)
+///     .min_gas_price(NearToken::from_yoctonear(500_000_000))
+///     .gas_limit(Gas::from_tgas(500_000));
+/// let worker = near_workspaces::sandbox().genesis(genesis).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GenesisConfig {
+    epoch_length: Option<u64>,
+    min_gas_price: Option<NearToken>,
+    max_gas_price: Option<NearToken>,
+    gas_limit: Option<Gas>,
+    accounts: Vec<(AccountId, PublicKey, NearToken)>,
+}
+
+impl GenesisConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of blocks in an epoch.
+    pub fn epoch_length(mut self, epoch_length: u64) -> Self {
+        self.epoch_length = Some(epoch_length);
+        self
+    }
+
+    /// Minimum gas price the network can go down to. This is also the starting gas price.
+    pub fn min_gas_price(mut self, price: NearToken) -> Self {
+        self.min_gas_price = Some(price);
+        self
+    }
+
+    /// Maximum gas price the network can go up to.
+    pub fn max_gas_price(mut self, price: NearToken) -> Self {
+        self.max_gas_price = Some(price);
+        self
+    }
+
+    /// Maximum amount of gas that can be used per chunk.
+    pub fn gas_limit(mut self, gas: Gas) -> Self {
+        self.gas_limit = Some(gas);
+        self
+    }
+
+    /// Add an account that exists from genesis onwards, with a full access key associated
+    /// to `public_key` and an initial `balance`.
+    pub fn add_account(mut self, id: AccountId, public_key: PublicKey, balance: NearToken) -> Self {
+        self.accounts.push((id, public_key, balance));
+        self
+    }
+}
+
+/// Apply the parameters of a user supplied [`GenesisConfig`] to the $home_dir/genesis.json file.
+pub(crate) fn set_sandbox_genesis_config(
+    home_dir: impl AsRef<Path>,
+    genesis: &GenesisConfig,
+) -> Result<()> {
+    let home_dir = home_dir.as_ref();
+    let config_file =
+        File::open(home_dir.join("genesis.json")).map_err(|err| ErrorKind::Io.custom(err))?;
+    let config = BufReader::new(config_file);
+    let mut config: Value =
+        serde_json::from_reader(config).map_err(|err| ErrorKind::DataConversion.custom(err))?;
+
+    let mut patch = serde_json::Map::new();
+    if let Some(epoch_length) = genesis.epoch_length {
+        patch.insert("epoch_length".into(), epoch_length.into());
+    }
+    if let Some(price) = genesis.min_gas_price {
+        patch.insert(
+            "min_gas_price".into(),
+            price.as_yoctonear().to_string().into(),
+        );
+    }
+    if let Some(price) = genesis.max_gas_price {
+        patch.insert(
+            "max_gas_price".into(),
+            price.as_yoctonear().to_string().into(),
+        );
+    }
+    if let Some(gas) = genesis.gas_limit {
+        patch.insert("gas_limit".into(), gas.as_gas().into());
+    }
+    json_patch::merge(&mut config, &Value::Object(patch));
+
+    let config = config.as_object_mut().expect("expected to be object");
+    let mut total_supply = u128::from_str(
+        config
+            .get("total_supply")
+            .and_then(Value::as_str)
+            .unwrap_or_default(),
+    )
+    .unwrap_or_default();
+    let records = config
+        .get_mut("records")
+        .and_then(Value::as_array_mut)
+        .expect("expected records to be an array");
+    for (id, public_key, balance) in &genesis.accounts {
+        total_supply += balance.as_yoctonear();
+        records.push(serde_json::json!({
+            "Account": {
+                "account_id": id,
+                "account": {
+                    "amount": balance.as_yoctonear().to_string(),
+                    "locked": "0",
+                    "code_hash": "11111111111111111111111111111111",
+                    "storage_usage": 182
+                }
+            }
+        }));
+        records.push(serde_json::json!({
+            "AccessKey": {
+                "account_id": id,
+                "public_key": public_key.to_string(),
+                "access_key": {
+                    "nonce": 0,
+                    "permission": "FullAccess"
+                }
+            }
+        }));
+    }
+    config.insert(
+        "total_supply".to_string(),
+        Value::String(total_supply.to_string()),
+    );
+
+    let config_file =
+        File::create(home_dir.join("genesis.json")).map_err(|err| ErrorKind::Io.custom(err))?;
+    serde_json::to_writer(config_file, &config).map_err(|err| ErrorKind::Io.custom(err))?;
+
+    Ok(())
+}
+
 pub fn set_sandbox_genesis(home_dir: impl AsRef<Path>) -> Result<()> {
     overwrite_genesis(&home_dir)?;
     let registrar_key = r#"{"account_id":"registrar","public_key":"ed25519:5BGSaf6YjVm7565VzWQHNxoyEjwr3jUpRJSGjREvU9dB","private_key":"ed25519:3tgdk2wPraJzT4nsTuf86UX41xgPNk3MHnq8epARMdBNs29AFEztAuaQ7iHddDfXG9F2RzV1XNQYgJyAyoW51UBB"}"#;
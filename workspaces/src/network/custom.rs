@@ -1,7 +1,6 @@
 use crate::network::{Info, NetworkClient, NetworkInfo};
 use crate::result::Result;
 use crate::rpc::client::Client;
-use std::path::PathBuf;
 
 use super::builder::{FromNetworkBuilder, NetworkBuilder};
 
@@ -16,8 +15,9 @@ impl FromNetworkBuilder for Custom {
     async fn from_builder<'a>(build: NetworkBuilder<'a, Self>) -> Result<Self> {
         let rpc_url = build
             .rpc_addr
+            .clone()
             .expect("rpc address should be provided for custom network");
-        let client = Client::new(&rpc_url, build.api_key)?;
+        let client = build.client(&rpc_url).await?;
         client.wait_for_rpc().await?;
 
         Ok(Self {
@@ -25,7 +25,7 @@ impl FromNetworkBuilder for Custom {
             info: Info {
                 name: build.name.into(),
                 root_id: "near".parse().unwrap(),
-                keystore_path: PathBuf::from(".near-credentials/mainnet/"),
+                keystore_path: build.keystore_path_or(".near-credentials/mainnet/"),
                 rpc_url: url::Url::parse(&rpc_url).expect("custom provided url should be valid"),
             },
         })
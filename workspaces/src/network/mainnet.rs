@@ -1,7 +1,6 @@
 use crate::network::{Info, NetworkClient, NetworkInfo};
 use crate::result::Result;
 use crate::rpc::client::Client;
-use std::path::PathBuf;
 
 use super::builder::{FromNetworkBuilder, NetworkBuilder};
 
@@ -30,8 +29,8 @@ pub struct Mainnet {
 #[async_trait::async_trait]
 impl FromNetworkBuilder for Mainnet {
     async fn from_builder<'a>(build: NetworkBuilder<'a, Self>) -> Result<Self> {
-        let rpc_url = build.rpc_addr.unwrap_or_else(|| RPC_URL.into());
-        let client = Client::new(&rpc_url, build.api_key)?;
+        let rpc_url = build.rpc_addr.clone().unwrap_or_else(|| RPC_URL.into());
+        let client = build.client(&rpc_url).await?;
         client.wait_for_rpc().await?;
 
         Ok(Self {
@@ -39,7 +38,7 @@ impl FromNetworkBuilder for Mainnet {
             info: Info {
                 name: build.name.into(),
                 root_id: "near".parse().unwrap(),
-                keystore_path: PathBuf::from(".near-credentials/mainnet/"),
+                keystore_path: build.keystore_path_or(".near-credentials/mainnet/"),
                 rpc_url: url::Url::parse(&rpc_url).expect("url is hardcoded"),
             },
         })
@@ -0,0 +1,149 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde_json::Value;
+use url::Url;
+
+use crate::network::builder::{FromNetworkBuilder, NetworkBuilder};
+use crate::network::{Info, NetworkClient, NetworkInfo};
+use crate::result::Result;
+use crate::rpc::client::Client;
+use crate::rpc::local::{error_response, Handler, LocalServer};
+
+/// Offline network that serves canned JSON-RPC responses from in-memory fixtures instead
+/// of talking to an actual RPC node. This is useful for unit testing tooling built on top
+/// of workspaces in environments without network access or a sandbox binary.
+///
+/// Look at [`workspaces::mock`] for how to create a [`Worker`] for it, and at
+/// [`Worker::respond`] for how to register fixtures. Requests without a matching fixture
+/// are answered with an error.
+///
+/// [`workspaces::mock`]: crate::mock
+/// [`Worker`]: crate::Worker
+/// [`Worker::respond`]: crate::Worker::respond
+pub struct MockNetwork {
+    client: Client,
+    info: Info,
+    fixtures: Arc<Mutex<Vec<Fixture>>>,
+    _server: LocalServer,
+}
+
+struct Fixture {
+    method: String,
+    params: Option<Value>,
+    response: std::result::Result<Value, Value>,
+}
+
+impl Fixture {
+    fn matches(&self, method: &str, params: &Value) -> bool {
+        self.method == method
+            && self
+                .params
+                .as_ref()
+                .is_none_or(|expected| contains(params, expected))
+    }
+}
+
+/// Whether `value` contains everything in `expected`. Objects only need to contain the
+/// keys of `expected`, while every other value has to be equal.
+fn contains(value: &Value, expected: &Value) -> bool {
+    match (value, expected) {
+        (Value::Object(value), Value::Object(expected)) => expected
+            .iter()
+            .all(|(key, expected)| value.get(key).is_some_and(|v| contains(v, expected))),
+        _ => value == expected,
+    }
+}
+
+impl MockNetwork {
+    pub(crate) fn add_fixture(
+        &self,
+        method: &str,
+        params: Option<Value>,
+        response: std::result::Result<Value, Value>,
+    ) {
+        self.fixtures.lock().unwrap().push(Fixture {
+            method: method.into(),
+            params,
+            response,
+        });
+    }
+}
+
+#[async_trait]
+impl FromNetworkBuilder for MockNetwork {
+    async fn from_builder<'a>(build: NetworkBuilder<'a, Self>) -> Result<Self> {
+        let fixtures: Arc<Mutex<Vec<Fixture>>> = Arc::default();
+        let handler: Handler = {
+            let fixtures = fixtures.clone();
+            Arc::new(move |request: Value| {
+                let id = request.get("id").cloned().unwrap_or_default();
+                let method = request
+                    .get("method")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                let params = request.get("params").cloned().unwrap_or_default();
+
+                // Fixtures registered last take precedence, so that tests can override them.
+                let fixtures = fixtures.lock().unwrap();
+                let response = match fixtures.iter().rev().find(|f| f.matches(method, &params)) {
+                    Some(Fixture {
+                        response: Ok(result),
+                        ..
+                    }) => serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    Some(Fixture {
+                        response: Err(error),
+                        ..
+                    }) => serde_json::json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+                    None => error_response(
+                        id,
+                        "REQUEST_VALIDATION_ERROR",
+                        serde_json::json!({
+                            "name": "METHOD_NOT_FOUND",
+                            "info": { "method_name": method },
+                        }),
+                    ),
+                };
+
+                Box::pin(async move { response })
+            })
+        };
+
+        let server = LocalServer::start(handler).await?;
+        let rpc_url = server.rpc_addr();
+        let client = build.client(&rpc_url).await?;
+
+        Ok(Self {
+            client,
+            info: Info {
+                name: build.name.into(),
+                root_id: "near".parse().unwrap(),
+                keystore_path: build.keystore_path_or(".near-credentials/mock/"),
+                rpc_url: Url::parse(&rpc_url).expect("local rpc url is valid"),
+            },
+            fixtures,
+            _server: server,
+        })
+    }
+}
+
+impl std::fmt::Debug for MockNetwork {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockNetwork")
+            .field("rpc_url", &self.info.rpc_url)
+            .field("fixtures", &self.fixtures.lock().unwrap().len())
+            .finish()
+    }
+}
+
+impl NetworkClient for MockNetwork {
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+impl NetworkInfo for MockNetwork {
+    fn info(&self) -> &Info {
+        &self.info
+    }
+}
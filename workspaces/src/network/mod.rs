@@ -1,6 +1,6 @@
 //! All builtin network types and traits.
 //!
-//! Currently the builtin network types are [`Mainnet`], [`Testnet`], and [`Sandbox`].
+//! Currently the builtin network types are [`Mainnet`], [`Testnet`], [`Betanet`], and [`Sandbox`].
 
 mod config;
 mod info;
@@ -13,6 +13,7 @@ pub(crate) mod variants;
 pub mod betanet;
 pub mod custom;
 pub mod mainnet;
+pub mod mock;
 pub mod testnet;
 
 pub(crate) use variants::DEV_ACCOUNT_SEED;
@@ -21,10 +22,11 @@ pub use self::betanet::Betanet;
 pub use self::custom::Custom;
 pub use self::info::Info;
 pub use self::mainnet::Mainnet;
+pub use self::mock::MockNetwork;
 pub use self::sandbox::Sandbox;
-pub use self::server::{pick_unused_port, ValidatorKey};
+pub use self::server::{pick_unused_port, SnapshotId, ValidatorKey};
 pub use self::testnet::Testnet;
 pub use self::variants::{
     NetworkClient, NetworkInfo, RootAccountSubaccountCreator, TopLevelAccountCreator,
 };
-pub use config::set_sandbox_genesis;
+pub use config::{set_sandbox_genesis, GenesisConfig};
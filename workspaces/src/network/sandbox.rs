@@ -1,5 +1,4 @@
 use std::convert::TryFrom;
-use std::path::PathBuf;
 
 use async_trait::async_trait;
 use near_jsonrpc_client::methods::sandbox_fast_forward::RpcSandboxFastForwardRequest;
@@ -10,8 +9,8 @@ use near_sandbox_utils as sandbox;
 use super::builder::{FromNetworkBuilder, NetworkBuilder};
 use super::server::ValidatorKey;
 use super::{NetworkClient, NetworkInfo, RootAccountSubaccountCreator, TopLevelAccountCreator};
-use crate::error::SandboxErrorCode;
-use crate::network::server::SandboxServer;
+use crate::error::{ErrorKind, SandboxErrorCode};
+use crate::network::server::{SandboxServer, SnapshotId};
 use crate::network::Info;
 use crate::result::{Execution, ExecutionFinalResult, Result};
 use crate::rpc::client::Client;
@@ -52,17 +51,71 @@ impl Sandbox {
         }
     }
 
+    /// The accounts in the genesis records of this sandbox. Sandboxes connected to through a
+    /// known validator key have no home directory to read the records from, so have none.
+    pub(crate) fn genesis_account_ids(&self) -> Result<Vec<AccountId>> {
+        let ValidatorKey::HomeDir(home_dir) = &self.server.validator_key else {
+            return Ok(Vec::new());
+        };
+
+        let genesis = std::fs::read(home_dir.join("genesis.json"))
+            .map_err(|e| ErrorKind::Io.full("failed to read genesis.json of sandbox", e))?;
+        let genesis: serde_json::Value =
+            serde_json::from_slice(&genesis).map_err(|e| ErrorKind::DataConversion.custom(e))?;
+
+        let records = genesis["records"].as_array().into_iter().flatten();
+        Ok(records
+            .filter_map(|record| record["Account"]["account_id"].as_str())
+            .filter_map(|account_id| account_id.parse().ok())
+            .collect())
+    }
+
     pub(crate) async fn from_builder_with_version<'a>(
         build: NetworkBuilder<'a, Self>,
         version: &str,
     ) -> Result<Self> {
+        if build.cassette.is_some() {
+            return Err(SandboxErrorCode::InitFailure
+                .message("Cassettes cannot be recorded or replayed with sandbox."));
+        }
+
         // Check the conditions of the provided rpc_url and validator_key
-        let mut server = match (build.rpc_addr, build.validator_key) {
+        let mut server = match (build.rpc_addr.clone(), build.validator_key.clone()) {
             // Connect to a provided sandbox:
-            (Some(rpc_url), Some(validator_key)) => SandboxServer::new(rpc_url, validator_key)?,
+            (Some(rpc_url), Some(validator_key)) => {
+                if build.genesis.is_some() {
+                    return Err(SandboxErrorCode::InitFailure.message(
+                        "Custom genesis can only be set for a sandbox spawned by workspaces.",
+                    ));
+                }
+                if build.version.is_some() {
+                    return Err(SandboxErrorCode::InitFailure.message(
+                        "Custom version can only be set for a sandbox spawned by workspaces.",
+                    ));
+                }
+                if build.capture_logs {
+                    return Err(SandboxErrorCode::InitFailure.message(
+                        "Logs can only be captured for a sandbox spawned by workspaces.",
+                    ));
+                }
+                if build.keep_files {
+                    return Err(SandboxErrorCode::InitFailure
+                        .message("Files can only be kept for a sandbox spawned by workspaces."));
+                }
+                SandboxServer::new(rpc_url, validator_key)?
+            }
 
             // Spawn a new sandbox since rpc_url and home_dir weren't specified:
-            (None, None) => SandboxServer::run_new_with_version(version).await?,
+            (None, None) => {
+                let mut server = SandboxServer::run_new_with_version(
+                    version,
+                    build.genesis.as_ref(),
+                    build.capture_logs,
+                )
+                .await?;
+                server.keep_files = build.keep_files;
+                server
+            }
 
             // Missing inputted parameters for sandbox:
             (Some(rpc_url), None) => {
@@ -77,7 +130,7 @@ impl Sandbox {
             }
         };
 
-        let client = Client::new(&server.rpc_addr(), build.api_key)?;
+        let client = build.client(&server.rpc_addr()).await?;
         client.wait_for_rpc().await?;
 
         // Server locks some ports on startup due to potential port collision, so we need
@@ -91,7 +144,7 @@ impl Sandbox {
         let info = Info {
             name: build.name.into(),
             root_id,
-            keystore_path: PathBuf::from(".near-credentials/sandbox/"),
+            keystore_path: build.keystore_path_or(".near-credentials/sandbox/"),
             rpc_url: url::Url::parse(&server.rpc_addr()).expect("url is hardcoded"),
         };
 
@@ -119,7 +172,11 @@ impl std::fmt::Debug for Sandbox {
 #[async_trait]
 impl FromNetworkBuilder for Sandbox {
     async fn from_builder<'a>(build: NetworkBuilder<'a, Self>) -> Result<Self> {
-        Self::from_builder_with_version(build, sandbox::DEFAULT_NEAR_SANDBOX_VERSION).await
+        let version = build
+            .version
+            .clone()
+            .unwrap_or_else(|| sandbox::DEFAULT_NEAR_SANDBOX_VERSION.to_string());
+        Self::from_builder_with_version(build, &version).await
     }
 }
 
@@ -256,6 +313,21 @@ impl Sandbox {
         Ok(())
     }
 
+    pub(crate) async fn snapshot(&self) -> Result<SnapshotId> {
+        let id = self.server.snapshot().await?;
+        self.client().wait_for_rpc().await?;
+        Ok(id)
+    }
+
+    pub(crate) async fn restore(&self, id: SnapshotId) -> Result<()> {
+        self.server.restore(id).await?;
+        self.client().wait_for_rpc().await?;
+
+        // Access key nonces have been rolled back along with the rest of the chain state.
+        self.client().access_key_nonces.write().await.clear();
+        Ok(())
+    }
+
     pub(crate) async fn fast_forward(&self, delta_height: u64) -> Result<()> {
         // NOTE: RpcSandboxFastForwardResponse is an empty struct with no fields, so don't do anything with it:
         self.client()
@@ -1,8 +1,13 @@
+use std::collections::VecDeque;
 use std::fs::File;
 use std::net::{Ipv4Addr, SocketAddrV4};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 use crate::error::{ErrorKind, SandboxErrorCode};
+use crate::network::GenesisConfig;
 use crate::result::Result;
 use crate::types::SecretKey;
 
@@ -11,7 +16,9 @@ use fs2::FileExt;
 use near_account_id::AccountId;
 use reqwest::Url;
 use tempfile::TempDir;
-use tokio::process::Child;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
 
 use tracing::info;
 
@@ -82,13 +89,65 @@ pub enum ValidatorKey {
     Known(AccountId, SecretKey),
 }
 
+/// Number of the latest lines of output kept around for sandboxes capturing their logs.
+const MAX_SANDBOX_LOG_LINES: usize = 10_000;
+
+/// Output of a sandbox node, captured while the node is running.
+#[derive(Clone, Default)]
+pub(crate) struct SandboxLogs(Arc<std::sync::Mutex<VecDeque<String>>>);
+
+impl SandboxLogs {
+    pub(crate) fn lines(&self) -> Vec<String> {
+        self.0
+            .lock()
+            .map(|lines| lines.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Forward every line read from `output` into `tracing`, keeping the latest ones around.
+    fn capture(&self, output: impl AsyncBufRead + Unpin + Send + 'static) {
+        let logs = self.clone();
+        tokio::spawn(async move {
+            let mut output = output.lines();
+            while let Ok(Some(line)) = output.next_line().await {
+                info!(target: "near-sandbox", "{}", line);
+                if let Ok(mut lines) = logs.0.lock() {
+                    if lines.len() == MAX_SANDBOX_LOG_LINES {
+                        lines.pop_front();
+                    }
+                    lines.push_back(line);
+                }
+            }
+        });
+    }
+}
+
+/// Identifier of a snapshot of the sandbox chain state, taken with [`Worker::snapshot`]
+/// and rolled back to with [`Worker::restore`].
+///
+/// [`Worker::snapshot`]: crate::Worker::snapshot
+/// [`Worker::restore`]: crate::Worker::restore
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SnapshotId(u64);
+
+impl std::fmt::Display for SnapshotId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 pub struct SandboxServer {
     pub(crate) validator_key: ValidatorKey,
     rpc_addr: Url,
     net_port: Option<u16>,
     rpc_port_lock: Option<File>,
     net_port_lock: Option<File>,
-    process: Option<Child>,
+    process: Mutex<Option<Child>>,
+    version: Option<String>,
+    next_snapshot: AtomicU64,
+    pub(crate) logs: Option<SandboxLogs>,
+    /// Whether to leave the home directory of a spawned node on disk after shutting it down.
+    pub(crate) keep_files: bool,
 }
 
 impl SandboxServer {
@@ -104,26 +163,37 @@ impl SandboxServer {
             net_port: None,
             rpc_port_lock: None,
             net_port_lock: None,
-            process: None,
+            process: Mutex::new(None),
+            version: None,
+            next_snapshot: AtomicU64::new(0),
+            logs: None,
+            keep_files: false,
         })
     }
 
     /// Run a new SandboxServer, spawning the sandbox node in the process.
     #[allow(dead_code)]
     pub(crate) async fn run_new() -> Result<Self> {
-        Self::run_new_with_version(sandbox::DEFAULT_NEAR_SANDBOX_VERSION).await
+        Self::run_new_with_version(sandbox::DEFAULT_NEAR_SANDBOX_VERSION, None, false).await
     }
 
-    pub(crate) async fn run_new_with_version(version: &str) -> Result<Self> {
+    pub(crate) async fn run_new_with_version(
+        version: &str,
+        genesis: Option<&GenesisConfig>,
+        capture_logs: bool,
+    ) -> Result<Self> {
         // Suppress logs for the sandbox binary by default:
         suppress_sandbox_logs_if_required();
 
-        let home_dir = init_home_dir_with_version(version).await?.into_path();
+        let home_dir = init_home_dir_with_version(version).await?.keep();
         // Configure `$home_dir/config.json` to our liking. Sandbox requires extra settings
         // for the best user experience, and being able to offer patching large state payloads.
         crate::network::config::set_sandbox_configs(&home_dir)?;
         // Configure `$home_dir/genesis.json` to our liking.
         crate::network::config::set_sandbox_genesis(&home_dir)?;
+        if let Some(genesis) = genesis {
+            crate::network::config::set_sandbox_genesis_config(&home_dir, genesis)?;
+        }
 
         // Try running the server with the follow provided rpc_ports and net_ports
         let (rpc_port, rpc_port_lock) = acquire_unused_port().await?;
@@ -134,23 +204,8 @@ impl SandboxServer {
         let net_addr = rpc_socket(net_port);
 
         info!(target: "workspaces", "Starting up sandbox at localhost:{}", rpc_port);
-
-        let options = &[
-            "--home",
-            home_dir
-                .as_os_str()
-                .to_str()
-                .expect("home_dir is valid utf8"),
-            "run",
-            "--rpc-addr",
-            &rpc_addr,
-            "--network-addr",
-            &net_addr,
-        ];
-
-        let child = sandbox::run_with_options_with_version(options, version)
-            .map_err(|e| SandboxErrorCode::RunFailure.custom(e))?;
-
+        let logs = capture_logs.then(SandboxLogs::default);
+        let child = spawn_sandbox(&home_dir, &rpc_addr, &net_addr, version, logs.as_ref())?;
         info!(target: "workspaces", "Started up sandbox at localhost:{} with pid={:?}", rpc_port, child.id());
 
         let rpc_addr: Url = format!("http://{rpc_addr}")
@@ -163,10 +218,102 @@ impl SandboxServer {
             net_port: Some(net_port),
             rpc_port_lock: Some(rpc_port_lock),
             net_port_lock: Some(net_port_lock),
-            process: Some(child),
+            process: Mutex::new(Some(child)),
+            version: Some(version.to_string()),
+            next_snapshot: AtomicU64::new(0),
+            logs,
+            keep_files: false,
         })
     }
 
+    /// Stop the sandbox node, copy its data directory into `$home_dir/snapshots/<id>` and
+    /// start the node back up on the same ports.
+    pub(crate) async fn snapshot(&self) -> Result<SnapshotId> {
+        let home_dir = self.owned_home_dir("snapshot")?;
+        let id = SnapshotId(self.next_snapshot.fetch_add(1, Ordering::SeqCst));
+
+        let mut process = self.process.lock().await;
+        stop_sandbox(&mut process).await?;
+        let copied = copy_dir_all(&home_dir.join("data"), &snapshot_dir(home_dir, id))
+            .map_err(|e| SandboxErrorCode::SnapshotFailure.full("failed to copy sandbox data", e));
+        *process = Some(self.respawn(home_dir)?);
+        copied?;
+
+        Ok(id)
+    }
+
+    /// Stop the sandbox node, replace its data directory with the one captured by the
+    /// snapshot `id` and start the node back up on the same ports.
+    pub(crate) async fn restore(&self, id: SnapshotId) -> Result<()> {
+        let home_dir = self.owned_home_dir("restore")?;
+        let snapshot = snapshot_dir(home_dir, id);
+        if !snapshot.exists() {
+            return Err(SandboxErrorCode::RestoreFailure
+                .message(format!("snapshot {id} does not exist for this sandbox")));
+        }
+
+        let mut process = self.process.lock().await;
+        stop_sandbox(&mut process).await?;
+        let data_dir = home_dir.join("data");
+        let copied = std::fs::remove_dir_all(&data_dir)
+            .and_then(|_| copy_dir_all(&snapshot, &data_dir))
+            .map_err(|e| {
+                SandboxErrorCode::RestoreFailure.full("failed to restore sandbox data", e)
+            });
+        *process = Some(self.respawn(home_dir)?);
+        copied
+    }
+
+    /// The home directory of a sandbox node that was spawned by this server. Sandboxes we
+    /// only connected to cannot be stopped and restarted by us.
+    fn owned_home_dir(&self, op: &str) -> Result<&Path> {
+        match (&self.validator_key, &self.version) {
+            (ValidatorKey::HomeDir(home_dir), Some(_)) => Ok(home_dir),
+            _ => Err(SandboxErrorCode::SnapshotFailure.message(format!(
+                "cannot {op} a sandbox that was not spawned by workspaces"
+            ))),
+        }
+    }
+
+    /// Stop the sandbox node if it was spawned by this server, and remove its home directory
+    /// unless the files are to be kept. Sandboxes we only connected to are left untouched.
+    pub(crate) async fn shutdown(&self) -> Result<()> {
+        let Ok(home_dir) = self.owned_home_dir("shut down") else {
+            return Ok(());
+        };
+
+        let mut process = self.process.lock().await;
+        if process.is_none() {
+            return Ok(());
+        }
+        stop_sandbox(&mut process).await?;
+        self.cleanup_home_dir(home_dir);
+        Ok(())
+    }
+
+    fn cleanup_home_dir(&self, home_dir: &Path) {
+        if self.keep_files {
+            println!("Sandbox files kept at {}", home_dir.display());
+        } else if let Err(err) = std::fs::remove_dir_all(home_dir) {
+            tracing::warn!(
+                target: "workspaces",
+                "Failed to remove sandbox home directory {:?}: {}",
+                home_dir,
+                err
+            );
+        }
+    }
+
+    fn respawn(&self, home_dir: &Path) -> Result<Child> {
+        let version = self
+            .version
+            .as_deref()
+            .unwrap_or(sandbox::DEFAULT_NEAR_SANDBOX_VERSION);
+        let rpc_addr = rpc_socket(self.rpc_port().expect("spawned sandbox has an rpc port"));
+        let net_addr = rpc_socket(self.net_port.expect("spawned sandbox has a net port"));
+        spawn_sandbox(home_dir, &rpc_addr, &net_addr, version, self.logs.as_ref())
+    }
+
     /// Unlock port lockfiles that were used to avoid port contention when starting up
     /// the sandbox node.
     pub(crate) fn unlock_lockfiles(&mut self) -> Result<()> {
@@ -208,7 +355,7 @@ impl SandboxServer {
 
 impl Drop for SandboxServer {
     fn drop(&mut self) {
-        if let Some(mut child) = self.process.take() {
+        if let Some(mut child) = self.process.get_mut().take() {
             info!(
                 target: "workspaces",
                 "Cleaning up sandbox: pid={:?}",
@@ -217,8 +364,99 @@ impl Drop for SandboxServer {
 
             child.start_kill().expect("failed to kill sandbox");
             let _ = child.try_wait();
+
+            if let Ok(home_dir) = self.owned_home_dir("clean up") {
+                self.cleanup_home_dir(home_dir);
+            }
+        }
+    }
+}
+
+fn spawn_sandbox(
+    home_dir: &Path,
+    rpc_addr: &str,
+    net_addr: &str,
+    version: &str,
+    logs: Option<&SandboxLogs>,
+) -> Result<Child> {
+    let options = &[
+        "--home",
+        home_dir
+            .as_os_str()
+            .to_str()
+            .expect("home_dir is valid utf8"),
+        "run",
+        "--rpc-addr",
+        rpc_addr,
+        "--network-addr",
+        net_addr,
+    ];
+
+    let Some(logs) = logs else {
+        return sandbox::run_with_options_with_version(options, version)
+            .map_err(|e| SandboxErrorCode::RunFailure.custom(e));
+    };
+
+    // Same as what near-sandbox-utils does for spawning the node, except for capturing the
+    // output of the node instead of it going straight to the output of the test.
+    let bin_path = sandbox::ensure_sandbox_bin_with_version(version)
+        .map_err(|e| SandboxErrorCode::RunFailure.custom(e))?;
+    let mut command = Command::new(&bin_path);
+    command
+        .args(options)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Ok(val) = std::env::var("NEAR_SANDBOX_LOG") {
+        command.env("RUST_LOG", val);
+    }
+    if let Ok(val) = std::env::var("NEAR_SANDBOX_LOG_STYLE") {
+        command.env("RUST_LOG_STYLE", val);
+    }
+
+    let mut child = command.spawn().map_err(|e| {
+        SandboxErrorCode::RunFailure.full(
+            format!("failed to run sandbox using '{}'", bin_path.display()),
+            e,
+        )
+    })?;
+    if let Some(stdout) = child.stdout.take() {
+        logs.capture(BufReader::new(stdout));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        logs.capture(BufReader::new(stderr));
+    }
+
+    Ok(child)
+}
+
+async fn stop_sandbox(process: &mut Option<Child>) -> Result<()> {
+    if let Some(mut child) = process.take() {
+        info!(target: "workspaces", "Stopping sandbox: pid={:?}", child.id());
+        child
+            .kill()
+            .await
+            .map_err(|e| SandboxErrorCode::RunFailure.full("failed to stop sandbox", e))?;
+    }
+
+    Ok(())
+}
+
+fn snapshot_dir(home_dir: &Path, id: SnapshotId) -> PathBuf {
+    home_dir.join("snapshots").join(id.to_string())
+}
+
+fn copy_dir_all(src: &Path, dst: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dst)?;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            copy_dir_all(&entry.path(), &dst.join(entry.file_name()))?;
+        } else {
+            std::fs::copy(entry.path(), dst.join(entry.file_name()))?;
         }
     }
+
+    Ok(())
 }
 
 /// Turn off neard-sandbox logs by default. Users can turn them back on with
@@ -1,4 +1,3 @@
-use std::path::PathBuf;
 use std::str::FromStr;
 
 use async_trait::async_trait;
@@ -40,8 +39,8 @@ pub struct Testnet {
 #[async_trait]
 impl FromNetworkBuilder for Testnet {
     async fn from_builder<'a>(build: NetworkBuilder<'a, Self>) -> Result<Self> {
-        let rpc_url = build.rpc_addr.unwrap_or_else(|| RPC_URL.into());
-        let client = Client::new(&rpc_url, build.api_key)?;
+        let rpc_url = build.rpc_addr.clone().unwrap_or_else(|| RPC_URL.into());
+        let client = build.client(&rpc_url).await?;
         client.wait_for_rpc().await?;
 
         Ok(Self {
@@ -49,7 +48,7 @@ impl FromNetworkBuilder for Testnet {
             info: Info {
                 name: build.name.into(),
                 root_id: AccountId::from_str("testnet").unwrap(),
-                keystore_path: PathBuf::from(".near-credentials/testnet/"),
+                keystore_path: build.keystore_path_or(".near-credentials/testnet/"),
                 rpc_url: Url::parse(&rpc_url).expect("url is hardcoded"),
             },
         })
@@ -105,6 +104,7 @@ impl RootAccountSubaccountCreator for Testnet {
                     },
                     receipts: Vec::new(),
                 },
+                method_names: Vec::new(),
             },
         })
     }
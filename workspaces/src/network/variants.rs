@@ -82,9 +82,35 @@ pub trait TopLevelAccountCreator {
 impl<T> Worker<T> {
     pub fn generate_dev_account_credentials(&self) -> (AccountId, SecretKey) {
         let id = crate::rpc::tool::random_account_id();
-        let sk = SecretKey::from_seed(KeyType::ED25519, DEV_ACCOUNT_SEED);
+        let sk = SecretKey::from_seed(self.dev_key_type, DEV_ACCOUNT_SEED);
         (id, sk)
     }
+
+    /// The [`KeyType`] of the keys of dev accounts created by this worker, being
+    /// [`KeyType::ED25519`] unless changed with [`Worker::set_dev_key_type`].
+    pub fn dev_key_type(&self) -> KeyType {
+        self.dev_key_type
+    }
+
+    /// Use keys of `key_type` for the dev accounts created by this worker from now on, along
+    /// with the subaccounts created without a key of their own and imported contracts. This
+    /// is how contracts and wallets relying on secp256k1 keys get tested:
+    ///
+    /// ```
+    /// use near_workspaces::types::KeyType;
+    ///
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let mut worker = near_workspaces::sandbox().await?;
+    /// worker.set_dev_key_type(KeyType::SECP256K1);
+    ///
+    /// let alice = worker.dev_create_account().await?;
+    /// assert_eq!(alice.secret_key().key_type(), KeyType::SECP256K1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_dev_key_type(&mut self, key_type: KeyType) {
+        self.dev_key_type = key_type;
+    }
 }
 
 impl<T> Worker<T>
@@ -97,9 +123,10 @@ where
             .workspace
             .create_tla(self.clone().coerce(), id, sk)
             .await?;
+        self.client().declare_signer(res.result.id());
 
         for callback in self.tx_callbacks.iter() {
-            callback(res.details.total_gas_burnt)?;
+            callback(&res.details)?;
         }
 
         Ok(res)
@@ -116,9 +143,10 @@ where
             .workspace
             .create_tla_and_deploy(self.clone().coerce(), id, sk, wasm)
             .await?;
+        self.client().declare_signer(res.result.id());
 
         for callback in self.tx_callbacks.iter() {
-            callback(res.details.total_gas_burnt)?;
+            callback(&res.details)?;
         }
 
         Ok(res)
@@ -154,9 +182,10 @@ where
             .workspace
             .create_root_account_subaccount(self.clone().coerce(), subaccount_prefix, sk)
             .await?;
+        self.client().declare_signer(res.result.id());
 
         for callback in self.tx_callbacks.iter() {
-            callback(res.details.total_gas_burnt)?;
+            callback(&res.details)?;
         }
 
         Ok(res)
@@ -177,9 +206,10 @@ where
                 wasm,
             )
             .await?;
+        self.client().declare_signer(res.result.id());
 
         for callback in self.tx_callbacks.iter() {
-            callback(res.details.total_gas_burnt)?;
+            callback(&res.details)?;
         }
 
         Ok(res)
@@ -203,6 +233,47 @@ where
             .await?;
         Ok(contract.into_result()?)
     }
+
+    /// Downloads the wasm code at `url` and deploys it like [`dev_deploy`], such that tests can
+    /// target released contract artifacts without having to vendor them. The downloaded code
+    /// has to match the hex encoded `sha256` checksum, as to pin the exact artifact being
+    /// tested against. Besides HTTP(S) URLs, `ipfs://<cid>` URLs are supported as well.
+    ///
+    /// [`dev_deploy`]: Worker::dev_deploy
+    pub async fn dev_deploy_from_url(&self, url: &str, sha256: &str) -> Result<Contract> {
+        let wasm = crate::rpc::tool::download_wasm(url, sha256).await?;
+        self.dev_deploy(&wasm).await
+    }
+
+    /// Creates the account `id` and deploys provided wasm code into it, such that the contract
+    /// gets a predictable name. This is useful for contracts that reference the account IDs of
+    /// their collaborators. The account must be a direct subaccount of the network's
+    /// [root account](RootAccountSubaccountCreator::root_account_id), such as `ft.test.near`
+    /// on sandbox or `ft.testnet` on testnet.
+    pub async fn dev_deploy_to(&self, wasm: &[u8], id: &str) -> Result<Contract> {
+        let id: AccountId = id
+            .parse()
+            .map_err(|e| ErrorKind::DataConversion.custom(e))?;
+        let root_id = self.workspace.root_account_id()?;
+        let prefix = id
+            .as_str()
+            .strip_suffix(root_id.as_str())
+            .and_then(|prefix| prefix.strip_suffix('.'))
+            .ok_or_else(|| {
+                ErrorKind::DataConversion.message(format!(
+                    "{} is not a subaccount of the root account {}",
+                    id, root_id
+                ))
+            })?;
+        let prefix =
+            AccountId::from_str(prefix).map_err(|e| ErrorKind::DataConversion.custom(e))?;
+
+        let (_, sk) = self.generate_dev_account_credentials();
+        let contract = self
+            .create_root_account_subaccount_and_deploy(prefix, sk, wasm)
+            .await?;
+        Ok(contract.into_result()?)
+    }
 }
 
 /// Network trait specifies the functionality of a network type such as mainnet, testnet or any
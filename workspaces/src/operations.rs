@@ -1,14 +1,19 @@
 //! All operation types that are generated/used when making transactions or view calls.
 
 use crate::error::{ErrorKind, RpcErrorCode};
-use crate::result::{Execution, ExecutionFinalResult, Result, ViewResultDetails};
+use crate::result::{
+    Execution, ExecutionFailure, ExecutionFinalResult, ExecutionOutcome, ExecutionSuccess, Result,
+    ViewResultDetails,
+};
 use crate::rpc::client::{
-    send_batch_tx_and_retry, send_batch_tx_async_and_retry, DEFAULT_CALL_DEPOSIT,
-    DEFAULT_CALL_FN_GAS,
+    send_batch_tx, send_batch_tx_and_retry, send_batch_tx_async_and_retry, sign_delegate_action,
+    DEFAULT_CALL_DEPOSIT, DEFAULT_CALL_FN_GAS,
 };
 use crate::rpc::query::{Query, ViewFunction};
+use crate::rpc::BoxFuture;
 use crate::types::{
-    AccessKey, AccountId, Gas, InMemorySigner, KeyType, NearToken, PublicKey, SecretKey,
+    AccessKey, AccountId, BlockHeight, Event, Finality, Gas, InMemorySigner, NearToken, PublicKey,
+    SecretKey, SignedDelegateAction,
 };
 use crate::worker::Worker;
 use crate::{Account, CryptoHash, Network};
@@ -31,6 +36,56 @@ use std::task::Poll;
 
 const MAX_GAS: NearGas = NearGas::from_tgas(300);
 
+/// Encoding of the arguments of function calls into the bytes handed to the contract, used
+/// through `args_with` on the call builders. [`Json`] and [`Borsh`] come built in, which is
+/// what `args_json` and `args_borsh` use, while other encodings such as MessagePack or CBOR
+/// can be added by implementing this trait:
+///
+/// ```
+/// use near_workspaces::error::{Error, ErrorKind};
+/// use near_workspaces::operations::ArgsEncoder;
+///
+/// /// JSON prefixed with the version of the arguments' schema.
+/// struct VersionedJson(u8);
+///
+/// impl<T: serde::Serialize> ArgsEncoder<T> for VersionedJson {
+///     fn encode(&self, args: &T) -> near_workspaces::Result<Vec<u8>> {
+///         let mut bytes = vec![self.0];
+///         serde_json::to_writer(&mut bytes, args)
+///             .map_err(|e| Error::custom(ErrorKind::DataConversion, e))?;
+///         Ok(bytes)
+///     }
+/// }
+///
+/// let bytes = VersionedJson(2).encode(&serde_json::json!({ "id": 1 }))?;
+/// assert_eq!(bytes, b"\x02{\"id\":1}");
+/// # Ok::<(), near_workspaces::error::Error>(())
+/// ```
+pub trait ArgsEncoder<T: ?Sized> {
+    /// Encode `args` into the bytes handed to the contract.
+    fn encode(&self, args: &T) -> Result<Vec<u8>>;
+}
+
+/// Encodes arguments as JSON, being what most contracts expect.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json;
+
+impl<T: serde::Serialize + ?Sized> ArgsEncoder<T> for Json {
+    fn encode(&self, args: &T) -> Result<Vec<u8>> {
+        serde_json::to_vec(args).map_err(|e| ErrorKind::DataConversion.custom(e))
+    }
+}
+
+/// Encodes arguments with borsh.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Borsh;
+
+impl<T: borsh::BorshSerialize + ?Sized> ArgsEncoder<T> for Borsh {
+    fn encode(&self, args: &T) -> Result<Vec<u8>> {
+        borsh::to_vec(args).map_err(|e| ErrorKind::DataConversion.custom(e))
+    }
+}
+
 /// A set of arguments we can provide to a transaction, containing
 /// the function name, arguments, the amount of gas to use and deposit.
 #[derive(Debug)]
@@ -67,21 +122,21 @@ impl Function {
     /// Similar to `args`, specify an argument that is JSON serializable and can be
     /// accepted by the equivalent contract. Recommend to use something like
     /// `serde_json::json!` macro to easily serialize the arguments.
-    pub fn args_json<U: serde::Serialize>(mut self, args: U) -> Self {
-        match serde_json::to_vec(&args) {
-            Ok(args) => self.args = Ok(args),
-            Err(e) => self.args = Err(ErrorKind::DataConversion.custom(e)),
-        }
-        self
+    pub fn args_json<U: serde::Serialize>(self, args: U) -> Self {
+        self.args_with(Json, args)
     }
 
     /// Similar to `args`, specify an argument that is borsh serializable and can be
     /// accepted by the equivalent contract.
-    pub fn args_borsh<U: borsh::BorshSerialize>(mut self, args: U) -> Self {
-        match borsh::to_vec(&args) {
-            Ok(args) => self.args = Ok(args),
-            Err(e) => self.args = Err(ErrorKind::DataConversion.custom(e)),
-        }
+    pub fn args_borsh<U: borsh::BorshSerialize>(self, args: U) -> Self {
+        self.args_with(Borsh, args)
+    }
+
+    /// Similar to `args`, specify an argument that gets encoded by `encoder`, such as for
+    /// contracts expecting an encoding other than JSON or borsh. Look at [`ArgsEncoder`] for
+    /// more info.
+    pub fn args_with<E: ArgsEncoder<U>, U>(mut self, encoder: E, args: U) -> Self {
+        self.args = encoder.encode(&args);
         self
     }
 
@@ -242,6 +297,35 @@ impl Transaction {
         self
     }
 
+    /// Relay a [`SignedDelegateAction`] signed by another account, such that its actions get
+    /// executed on its behalf while the gas is paid for by the signer of this transaction.
+    /// The `receiver_id` of this transaction has to be the sender of the delegate action.
+    ///
+    /// Look at [`Transaction::delegate`] for how to create one.
+    pub fn signed_delegate(mut self, action: SignedDelegateAction) -> Self {
+        if let Ok(actions) = &mut self.actions {
+            actions.push(action.into());
+        }
+        self
+    }
+
+    /// Sign the actions of this transaction as a delegate action ([NEP-366]) instead of
+    /// sending them, so that another account can relay them to the network and pay for the
+    /// gas with [`Account::relay`]. The delegate action stays valid for the next `valid_for`
+    /// blocks.
+    ///
+    /// [NEP-366]: https://github.com/near/NEPs/blob/master/neps/nep-0366.md
+    pub async fn delegate(self, valid_for: BlockHeight) -> Result<SignedDelegateAction> {
+        sign_delegate_action(
+            self.worker.client(),
+            &self.signer,
+            &self.receiver_id,
+            self.actions?,
+            valid_for,
+        )
+        .await
+    }
+
     async fn transact_raw(self) -> Result<FinalExecutionOutcomeView> {
         let view = send_batch_tx_and_retry(
             self.worker.client(),
@@ -252,15 +336,9 @@ impl Transaction {
         .await?;
 
         if !self.worker.tx_callbacks.is_empty() {
-            let total_gas_burnt = view.transaction_outcome.outcome.gas_burnt
-                + view
-                    .receipts_outcome
-                    .iter()
-                    .map(|t| t.outcome.gas_burnt)
-                    .sum::<u64>();
-
+            let result = ExecutionFinalResult::from_view(view.clone());
             for callback in self.worker.tx_callbacks {
-                callback(Gas::from_gas(total_gas_burnt))?;
+                callback(&result)?;
             }
         }
 
@@ -272,7 +350,6 @@ impl Transaction {
         self.transact_raw()
             .await
             .map(ExecutionFinalResult::from_view)
-            .map_err(crate::error::Error::from)
     }
 
     /// Send the transaction to the network to be processed. This will be done asynchronously
@@ -288,6 +365,12 @@ impl Transaction {
     }
 }
 
+impl From<CallTransaction> for Transaction {
+    fn from(call: CallTransaction) -> Self {
+        Self::new(call.worker, call.signer, call.contract_id).call(call.function)
+    }
+}
+
 /// Similar to a [`Transaction`], but more specific to making a call into a contract.
 /// Note, only one call can be made per `CallTransaction`.
 pub struct CallTransaction {
@@ -295,6 +378,8 @@ pub struct CallTransaction {
     signer: InMemorySigner,
     contract_id: AccountId,
     function: Function,
+    finality: Option<Finality>,
+    retries: bool,
 }
 
 impl CallTransaction {
@@ -309,6 +394,8 @@ impl CallTransaction {
             signer,
             contract_id,
             function: Function::new(function),
+            finality: None,
+            retries: true,
         }
     }
 
@@ -335,6 +422,13 @@ impl CallTransaction {
         self
     }
 
+    /// Similar to `args`, specify an argument that gets encoded by `encoder`. Look at
+    /// [`ArgsEncoder`] for more info.
+    pub fn args_with<E: ArgsEncoder<U>, U>(mut self, encoder: E, args: U) -> Self {
+        self.function = self.function.args_with(encoder, args);
+        self
+    }
+
     /// Specify the amount of tokens to be deposited where `deposit` is the amount of
     /// tokens in yocto near.
     pub fn deposit(mut self, deposit: NearToken) -> Self {
@@ -342,8 +436,8 @@ impl CallTransaction {
         self
     }
 
-    /// Specify the amount of gas to be used where `gas` is the amount of gas in yocto near.
-    pub fn gas(mut self, gas: NearGas) -> Self {
+    /// Specify the amount of gas to be used, such as `Gas::from_tgas(50)`.
+    pub fn gas(mut self, gas: Gas) -> Self {
         self.function = self.function.gas(gas);
         self
     }
@@ -353,31 +447,90 @@ impl CallTransaction {
         self.gas(MAX_GAS)
     }
 
+    /// Specify the [`Finality`] this call requires. When transacting, this waits until the
+    /// outcome of the transaction reaches the given finality before returning, rather than
+    /// returning as soon as it got executed. When viewing, this queries the latest block
+    /// with the given finality.
+    pub fn finality(mut self, finality: Finality) -> Self {
+        self.finality = Some(finality);
+        self
+    }
+
+    /// Fail right away if sending the transaction or view fails, instead of retrying it a
+    /// couple of times with an exponential backoff. This keeps the feedback of tests
+    /// expecting a failure fast.
+    pub fn no_retry(mut self) -> Self {
+        self.retries = false;
+        self
+    }
+
     /// Finally, send the transaction to the network. This will consume the `CallTransaction`
     /// object and return us the execution details, along with any errors if the transaction
     /// failed in any process along the way.
     pub async fn transact(self) -> Result<ExecutionFinalResult> {
-        let txn = self
-            .worker
-            .client()
-            .call(
-                &self.signer,
-                &self.contract_id,
-                self.function.name.to_string(),
-                self.function.args?,
-                self.function.gas.as_gas(),
-                self.function.deposit,
-            )
-            .await
-            .map(ExecutionFinalResult::from_view)
-            .map_err(crate::error::Error::from)?;
+        let client = self.worker.client();
+        let mut view = send_batch_tx(
+            client,
+            &self.signer,
+            &self.contract_id,
+            vec![FunctionCallAction {
+                args: self.function.args?,
+                method_name: self.function.name,
+                gas: self.function.gas.as_gas(),
+                deposit: self.function.deposit.as_yoctonear(),
+            }
+            .into()],
+            self.retries,
+        )
+        .await?;
+
+        let wait_until = match self.finality {
+            None | Some(Finality::Optimistic) => None,
+            Some(Finality::DoomSlug) => Some(TxExecutionStatus::Executed),
+            Some(Finality::Final) => Some(TxExecutionStatus::Final),
+        };
+        if let Some(wait_until) = wait_until {
+            let resp = client
+                .tx_async_status(
+                    &self.signer.account_id,
+                    view.transaction_outcome.id,
+                    wait_until,
+                )
+                .await
+                .map_err(|e| RpcErrorCode::QueryFailure.custom(e))?;
+            if let Some(outcome) = resp.final_execution_outcome {
+                view = outcome.into_outcome();
+            }
+        }
+        let txn = ExecutionFinalResult::from_view(view);
 
         for callback in self.worker.tx_callbacks.iter() {
-            callback(txn.total_gas_burnt)?;
+            callback(&txn)?;
         }
         Ok(txn)
     }
 
+    /// Send the transaction to the network just like [`transact`](Self::transact), but for
+    /// negative-path tests expecting the call to fail. Retries are disabled as with
+    /// [`no_retry`](Self::no_retry), and the failure is returned as is to be inspected, such
+    /// as with [`ExecutionFailure::panic_message`]. Errors out if the call succeeded instead.
+    pub async fn expect_failure(self) -> Result<ExecutionFailure> {
+        let call = format!("{}.{}", self.contract_id, self.function.name);
+        match self.no_retry().transact().await?.into_result() {
+            Ok(_) => Err(ErrorKind::Execution.message(format!(
+                "expected the call to {} to fail, but it succeeded",
+                call
+            ))),
+            Err(failure) => Ok(failure),
+        }
+    }
+
+    /// Sign this call as a delegate action instead of sending it, just like
+    /// [`Transaction::delegate`].
+    pub async fn delegate(self, valid_for: BlockHeight) -> Result<SignedDelegateAction> {
+        Transaction::from(self).delegate(valid_for).await
+    }
+
     /// Send the transaction to the network to be processed. This will be done asynchronously
     /// without waiting for the transaction to complete. This returns us a [`TransactionStatus`]
     /// for which we can call into [`status`] and/or `.await` to retrieve info about whether
@@ -403,14 +556,20 @@ impl CallTransaction {
 
     /// Instead of transacting the transaction, call into the specified view function.
     pub async fn view(self) -> Result<ViewResultDetails> {
-        Query::new(
+        let mut query = Query::new(
             self.worker.client(),
             ViewFunction {
                 account_id: self.contract_id.clone(),
                 function: self.function,
             },
-        )
-        .await
+        );
+        if let Some(finality) = self.finality {
+            query = query.finality(finality);
+        }
+        if !self.retries {
+            query = query.no_retry();
+        }
+        query.await
     }
 }
 
@@ -424,6 +583,7 @@ pub struct CreateAccountTransaction<'a, 'b> {
 
     initial_balance: NearToken,
     secret_key: Option<SecretKey>,
+    access_keys: Vec<(PublicKey, AccessKey)>,
 }
 
 impl<'a, 'b> CreateAccountTransaction<'a, 'b> {
@@ -440,6 +600,7 @@ impl<'a, 'b> CreateAccountTransaction<'a, 'b> {
             new_account_id,
             initial_balance: NearToken::from_yoctonear(100000000000000000000000u128),
             secret_key: None,
+            access_keys: Vec::new(),
         }
     }
 
@@ -456,28 +617,77 @@ impl<'a, 'b> CreateAccountTransaction<'a, 'b> {
         self
     }
 
+    /// Add another key to the new account on top of the full access key from [`keys`], such
+    /// as a function call access key only allowed to call into certain methods of a contract:
+    /// ```no_run
+    /// # async fn run(account: near_workspaces::Account, contract_id: near_workspaces::AccountId) -> anyhow::Result<()> {
+    /// use near_workspaces::types::{AccessKey, KeyType, NearToken, SecretKey};
+    ///
+    /// let game_key = SecretKey::from_random(KeyType::ED25519);
+    /// let player = account
+    ///     .create_subaccount("player")
+    ///     .add_key(
+    ///         game_key.public_key(),
+    ///         AccessKey::function_call_access(
+    ///             &contract_id,
+    ///             &["make_move"],
+    ///             Some(NearToken::from_millinear(250)),
+    ///         ),
+    ///     )
+    ///     .transact()
+    ///     .await?
+    ///     .into_result()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`keys`]: CreateAccountTransaction::keys
+    pub fn add_key(mut self, pk: PublicKey, ak: AccessKey) -> Self {
+        self.access_keys.push((pk, ak));
+        self
+    }
+
     /// Send the transaction to the network. This will consume the `CreateAccountTransaction`
     /// and give us back the details of the execution and finally the new [`Account`] object.
     pub async fn transact(self) -> Result<Execution<Account>> {
+        let key_type = self.worker.dev_key_type;
         let sk = self
             .secret_key
-            .unwrap_or_else(|| SecretKey::from_seed(KeyType::ED25519, "subaccount.seed"));
+            .unwrap_or_else(|| SecretKey::from_seed(key_type, "subaccount.seed"));
         let id: AccountId = format!("{}.{}", self.new_account_id, self.parent_id)
             .try_into()
             .map_err(|e: ParseAccountError| ErrorKind::DataConversion.custom(e))?;
 
-        let outcome = self
-            .worker
-            .client()
-            .create_account(&self.signer, &id, sk.public_key(), self.initial_balance)
-            .await?;
+        let mut actions: Vec<Action> = vec![
+            CreateAccountAction {}.into(),
+            AddKeyAction {
+                public_key: sk.public_key().into(),
+                access_key: AccessKey::full_access().into(),
+            }
+            .into(),
+            TransferAction {
+                deposit: self.initial_balance.as_yoctonear(),
+            }
+            .into(),
+        ];
+        actions.extend(self.access_keys.into_iter().map(|(pk, ak)| {
+            AddKeyAction {
+                public_key: pk.into(),
+                access_key: ak.into(),
+            }
+            .into()
+        }));
+
+        let outcome =
+            send_batch_tx_and_retry(self.worker.client(), &self.signer, &id, actions).await?;
+        self.worker.client().declare_signer(&id);
 
         let signer = InMemorySigner::from_secret_key(id, sk);
         let account = Account::new(signer, self.worker.clone());
         let details = ExecutionFinalResult::from_view(outcome);
 
         for callback in self.worker.tx_callbacks.iter() {
-            callback(details.total_gas_burnt)?;
+            callback(&details)?;
         }
 
         Ok(Execution {
@@ -487,6 +697,187 @@ impl<'a, 'b> CreateAccountTransaction<'a, 'b> {
     }
 }
 
+/// A payload too large for a single function call, split across several calls to a method
+/// which accumulates it in the state of the contract, as oracles and other data-heavy
+/// contracts do. This is most commonly constructed from [`Account::chunked_call`] or
+/// [`Contract::chunked_call`](crate::Contract::chunked_call).
+///
+/// Every chunk gets sent as the raw input of its own call, in order, one after the other.
+/// Chunks are 1 MiB by default, which keeps every call well within the limits on the size of
+/// transactions and their arguments, and each call gets the maximum amount of gas attached.
+pub struct ChunkedCallTransaction {
+    worker: Worker<dyn Network>,
+    signer: InMemorySigner,
+    contract_id: AccountId,
+    method: String,
+    payload: Vec<u8>,
+    chunk_size: usize,
+    gas: NearGas,
+    deposit: NearToken,
+}
+
+impl ChunkedCallTransaction {
+    pub(crate) fn new(
+        worker: Worker<dyn Network>,
+        signer: InMemorySigner,
+        contract_id: AccountId,
+        method: &str,
+        payload: Vec<u8>,
+    ) -> Self {
+        Self {
+            worker,
+            signer,
+            contract_id,
+            method: method.into(),
+            payload,
+            chunk_size: 1 << 20,
+            gas: MAX_GAS,
+            deposit: DEFAULT_CALL_DEPOSIT,
+        }
+    }
+
+    /// Maximum number of bytes of the payload sent per call.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Gas attached to every call, being the maximum amount by default.
+    pub fn gas(mut self, gas: NearGas) -> Self {
+        self.gas = gas;
+        self
+    }
+
+    /// Deposit attached to every call, such as to pay for the storage taken up by each chunk.
+    pub fn deposit(mut self, deposit: NearToken) -> Self {
+        self.deposit = deposit;
+        self
+    }
+
+    /// Number of calls the payload gets split across.
+    pub fn num_chunks(&self) -> usize {
+        self.payload.len().div_ceil(self.chunk_size)
+    }
+
+    /// Send every chunk, returning the execution details of each call. Stops at the first
+    /// call that fails, returning its failure as the error.
+    pub async fn transact(self) -> Result<Vec<ExecutionSuccess>> {
+        let mut outcomes = Vec::with_capacity(self.num_chunks());
+        for chunk in self.payload.chunks(self.chunk_size) {
+            let outcome = Transaction::new(
+                self.worker.clone(),
+                self.signer.clone(),
+                self.contract_id.clone(),
+            )
+            .call(
+                Function::new(&self.method)
+                    .args(chunk.to_vec())
+                    .gas(self.gas)
+                    .deposit(self.deposit),
+            )
+            .transact()
+            .await?;
+            outcomes.push(outcome.into_result()?);
+        }
+        Ok(outcomes)
+    }
+}
+
+/// Upgrade of a contract through the common pattern of staging the new code in its state
+/// before applying it, as DAOs and other self-upgrading contracts do. This is most commonly
+/// constructed from [`Account::stage_upgrade`].
+///
+/// The code gets staged as a [`ChunkedCallTransaction`], calling the stage method once per
+/// chunk with the raw bytes of the chunk as its input, which the contract is expected to
+/// append to the code staged so far. The apply method gets called afterwards, which is
+/// expected to deploy the staged code to the contract.
+pub struct UpgradeTransaction {
+    stage: ChunkedCallTransaction,
+    apply_method: String,
+    apply_args: Result<Vec<u8>>,
+}
+
+impl UpgradeTransaction {
+    pub(crate) fn new(
+        worker: Worker<dyn Network>,
+        signer: InMemorySigner,
+        contract_id: AccountId,
+        code: Vec<u8>,
+    ) -> Self {
+        Self {
+            stage: ChunkedCallTransaction::new(worker, signer, contract_id, "stage_code", code),
+            apply_method: "apply_upgrade".into(),
+            apply_args: Ok(vec![]),
+        }
+    }
+
+    /// Method called with every chunk of the code, being `stage_code` by default.
+    pub fn stage_method(mut self, method: &str) -> Self {
+        self.stage.method = method.into();
+        self
+    }
+
+    /// Method called once all of the code got staged, being `apply_upgrade` by default.
+    pub fn apply_method(mut self, method: &str) -> Self {
+        self.apply_method = method.into();
+        self
+    }
+
+    /// Specify the arguments of the apply method, such as a migration to run along with the
+    /// upgrade, which get serialized to JSON.
+    pub fn apply_args_json<U: serde::Serialize>(mut self, args: U) -> Self {
+        self.apply_args =
+            serde_json::to_vec(&args).map_err(|e| ErrorKind::DataConversion.custom(e));
+        self
+    }
+
+    /// Maximum number of bytes of code uploaded per call, being 1 MiB by default.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.stage = self.stage.chunk_size(chunk_size);
+        self
+    }
+
+    /// Gas attached to every call, being the maximum amount by default.
+    pub fn gas(mut self, gas: NearGas) -> Self {
+        self.stage = self.stage.gas(gas);
+        self
+    }
+
+    /// Stage the code and apply it, returning the execution details of the apply call. Fails
+    /// if staging any chunk fails, or if the code of the contract does not match the staged
+    /// one after the apply call succeeded.
+    pub async fn transact(self) -> Result<ExecutionFinalResult> {
+        let Self {
+            stage,
+            apply_method,
+            apply_args,
+        } = self;
+        let apply_args = apply_args?;
+        let expected = CryptoHash::hash_bytes(&stage.payload);
+        let apply = Transaction::new(
+            stage.worker.clone(),
+            stage.signer.clone(),
+            stage.contract_id.clone(),
+        )
+        .call(Function::new(&apply_method).args(apply_args).gas(stage.gas));
+        let worker = stage.worker.clone();
+        let contract_id = stage.contract_id.clone();
+
+        stage.transact().await?;
+        let outcome = apply.transact().await?;
+        if outcome.is_success() {
+            let actual = worker.view_account(&contract_id).await?.code_hash;
+            if actual != expected {
+                return Err(ErrorKind::Execution.message(format!(
+                    "code hash of {} is {} after the upgrade, while {} was staged",
+                    contract_id, actual, expected
+                )));
+            }
+        }
+        Ok(outcome)
+    }
+}
+
 /// `TransactionStatus` object relating to an [`asynchronous transaction`] on the network.
 /// Used to query into the status of the Transaction for whether it has completed or not.
 ///
@@ -595,3 +986,143 @@ impl IntoFuture for TransactionStatus {
         Box::pin(async { self.wait().await })
     }
 }
+
+/// Waits on a [NEP-297] event matching a predicate to be emitted by any contract on the
+/// network. Construct this with [`Worker::wait_for_event`], then `.await` it to start
+/// watching the outcomes of blocks produced from that point onwards.
+///
+/// [NEP-297]: https://nomicon.io/Standards/EventsFormat
+#[must_use]
+pub struct WaitForEvent<'a> {
+    worker: Worker<dyn Network>,
+    standard: String,
+    event: String,
+    predicate: Box<dyn Fn(&Event) -> bool + Send + Sync + 'a>,
+    timeout: std::time::Duration,
+}
+
+impl<'a> WaitForEvent<'a> {
+    pub(crate) fn new(
+        worker: Worker<dyn Network>,
+        standard: &str,
+        event: &str,
+        predicate: impl Fn(&Event) -> bool + Send + Sync + 'a,
+    ) -> Self {
+        Self {
+            worker,
+            standard: standard.into(),
+            event: event.into(),
+            predicate: Box::new(predicate),
+            timeout: std::time::Duration::from_secs(60),
+        }
+    }
+
+    /// Maximum amount of time to wait for the event before erroring out with
+    /// [`ErrorKind::Timeout`]. Defaults to 60 seconds.
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn matches(&self, event: &Event) -> bool {
+        event.standard == self.standard && event.event == self.event && (self.predicate)(event)
+    }
+
+    async fn wait(self) -> Result<Event> {
+        let deadline = tokio::time::Instant::now() + self.timeout;
+        let client = self.worker.client();
+
+        // Transactions included in blocks since we started watching, which still have
+        // receipts left to execute.
+        let mut pending = Vec::new();
+        let mut last_hash = client.view_block(None).await?.header.hash;
+        loop {
+            // Walk back from the latest block to the last one we looked at, to not miss any
+            // blocks in between and to not trip over skipped heights.
+            let mut blocks = Vec::new();
+            let mut block = client.view_block(None).await?;
+            while block.header.hash != last_hash && block.header.height > 0 {
+                let prev_hash = block.header.prev_hash;
+                blocks.push(block);
+                block = client
+                    .view_block(Some(
+                        near_primitives::types::BlockId::Hash(prev_hash).into(),
+                    ))
+                    .await?;
+            }
+            if let Some(latest) = blocks.first() {
+                last_hash = latest.header.hash;
+            }
+
+            for block in blocks.into_iter().rev() {
+                for chunk in block.chunks {
+                    if chunk.height_included != block.header.height {
+                        continue;
+                    }
+
+                    let chunk = client
+                        .query(near_jsonrpc_client::methods::chunk::RpcChunkRequest {
+                            chunk_reference:
+                                near_jsonrpc_primitives::types::chunks::ChunkReference::ChunkHash {
+                                    chunk_id: chunk.chunk_hash,
+                                },
+                        })
+                        .await
+                        .map_err(|e| RpcErrorCode::QueryFailure.custom(e))?;
+                    pending.extend(
+                        chunk
+                            .transactions
+                            .into_iter()
+                            .map(|tx| (tx.signer_id, tx.hash)),
+                    );
+                }
+            }
+
+            let mut still_pending = Vec::new();
+            for (signer_id, hash) in pending {
+                let status = TransactionStatus::new(self.worker.clone(), signer_id, hash);
+                match status.status().await? {
+                    Poll::Ready(result) => {
+                        let found = result
+                            .outcomes()
+                            .into_iter()
+                            .flat_map(ExecutionOutcome::events)
+                            .find(|event| self.matches(event));
+                        if let Some(event) = found {
+                            return Ok(event);
+                        }
+                    }
+                    Poll::Pending => still_pending.push((status.sender_id, hash)),
+                }
+            }
+            pending = still_pending;
+
+            if tokio::time::Instant::now() >= deadline {
+                return Err(ErrorKind::Timeout.message(format!(
+                    "no `{}` event of standard `{}` was emitted within {:?}",
+                    self.event, self.standard, self.timeout
+                )));
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        }
+    }
+}
+
+impl fmt::Debug for WaitForEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaitForEvent")
+            .field("standard", &self.standard)
+            .field("event", &self.event)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl<'a> IntoFuture for WaitForEvent<'a> {
+    type Output = Result<Event>;
+    type IntoFuture = BoxFuture<'a, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.wait())
+    }
+}
@@ -1,3 +1,22 @@
-//! All traits that are essential to the ease of use of workspaces.
+//! All traits that are essential to the ease of use of workspaces, along with the types and
+//! helpers that come up in nearly every test. A single glob import gives the full API:
+//!
+//! ```
+//! use near_workspaces::prelude::*;
+//!
+//! async fn transfer(worker: &Worker<impl DevNetwork>, to: &AccountId) -> anyhow::Result<()> {
+//!     let account = worker.dev_create_account().await?;
+//!     account
+//!         .transfer_near(to, NearToken::from_near(1))
+//!         .await?
+//!         .into_result()?;
+//!     Ok(())
+//! }
+//! ```
 
-pub use crate::network::TopLevelAccountCreator;
+pub use crate::assertions::*;
+pub use crate::network::{
+    NetworkClient, NetworkInfo, RootAccountSubaccountCreator, TopLevelAccountCreator,
+};
+pub use crate::types::{Gas, NearToken};
+pub use crate::{Account, AccountId, Contract, DevNetwork, Network, Worker};
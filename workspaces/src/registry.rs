@@ -0,0 +1,188 @@
+//! Registry of the contracts used throughout a project's tests, such that their wasm
+//! artifacts are declared once by name and referred to by that name everywhere else.
+//!
+//! ```no_run
+//! use near_workspaces::registry::{ContractRegistry, WasmSource};
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let mainnet = near_workspaces::mainnet().await?;
+//! let registry = ContractRegistry::new()
+//!     .register("token", WasmSource::path("res/fungible_token.wasm"))
+//!     .register("wrap", WasmSource::account("wrap.near".parse()?, &mainnet))
+//!     .register(
+//!         "multisig",
+//!         WasmSource::url(
+//!             "https://example.com/multisig.wasm",
+//!             "af2b3a6e0c1c1c4c5d7e3b8f0a9d2c1b4e5f6a7b8c9d0e1f2a3b4c5d6e7f8a9b",
+//!         ),
+//!     );
+//!
+//! let worker = near_workspaces::sandbox().await?;
+//! let token = registry.deploy(&worker, "token").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::error::ErrorKind;
+use crate::{AccountId, Contract, DevNetwork, Network, Result, Worker};
+
+/// Where to get the wasm code of a contract registered in a [`ContractRegistry`] from.
+#[derive(Clone)]
+pub struct WasmSource(Source);
+
+#[derive(Clone)]
+enum Source {
+    Bytes(Arc<[u8]>),
+    Path(PathBuf),
+    Url {
+        url: String,
+        sha256: String,
+    },
+    Account(AccountId, Worker<dyn Network>),
+    #[cfg(feature = "unstable")]
+    Project(String),
+}
+
+impl WasmSource {
+    /// The given wasm code, as is.
+    pub fn bytes(wasm: impl Into<Vec<u8>>) -> Self {
+        Self(Source::Bytes(wasm.into().into()))
+    }
+
+    /// The wasm file at `path`.
+    pub fn path(path: impl Into<PathBuf>) -> Self {
+        Self(Source::Path(path.into()))
+    }
+
+    /// The wasm code downloaded from `url`, which has to match the hex encoded `sha256`
+    /// checksum. Look at [`Worker::dev_deploy_from_url`] for the URLs supported.
+    pub fn url(url: impl Into<String>, sha256: impl Into<String>) -> Self {
+        Self(Source::Url {
+            url: url.into(),
+            sha256: sha256.into(),
+        })
+    }
+
+    /// The code deployed to the account `id` on the network of `worker`, such as a contract
+    /// already live on mainnet.
+    pub fn account(id: AccountId, worker: &Worker<impl Network + 'static>) -> Self {
+        Self(Source::Account(id, worker.clone().coerce()))
+    }
+
+    /// The wasm code built out of the cargo project at `project_path`, using
+    /// [`compile_project`](crate::compile_project).
+    #[cfg(feature = "unstable")]
+    pub fn project(project_path: impl Into<String>) -> Self {
+        Self(Source::Project(project_path.into()))
+    }
+
+    async fn resolve(&self) -> Result<Vec<u8>> {
+        match &self.0 {
+            Source::Bytes(wasm) => Ok(wasm.to_vec()),
+            Source::Path(path) => tokio::fs::read(path).await.map_err(|e| {
+                ErrorKind::Io.full(format!("failed to read wasm file {}", path.display()), e)
+            }),
+            Source::Url { url, sha256 } => crate::rpc::tool::download_wasm(url, sha256).await,
+            Source::Account(id, worker) => worker.view_code(id).await,
+            #[cfg(feature = "unstable")]
+            Source::Project(project_path) => crate::compile_project(project_path).await,
+        }
+    }
+}
+
+impl fmt::Debug for WasmSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Source::Bytes(wasm) => write!(f, "Bytes({} bytes)", wasm.len()),
+            Source::Path(path) => f.debug_tuple("Path").field(path).finish(),
+            Source::Url { url, sha256 } => f
+                .debug_struct("Url")
+                .field("url", url)
+                .field("sha256", sha256)
+                .finish(),
+            Source::Account(id, _) => f.debug_tuple("Account").field(id).finish(),
+            #[cfg(feature = "unstable")]
+            Source::Project(project_path) => f.debug_tuple("Project").field(project_path).finish(),
+        }
+    }
+}
+
+/// Registry of named wasm artifacts, such that multi-contract projects declare where each of
+/// their contracts comes from in a single place. The code of each contract is resolved the
+/// first time it is needed and cached from then on, with clones of the registry sharing the
+/// same cache. Look at the [module docs](self) for an example.
+#[derive(Clone, Default)]
+pub struct ContractRegistry {
+    sources: HashMap<String, WasmSource>,
+    cache: Arc<Mutex<HashMap<String, Arc<[u8]>>>>,
+}
+
+impl ContractRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the contract `name`, with its wasm code coming from `source`. Registering a
+    /// name again replaces its previous source.
+    pub fn register(mut self, name: impl Into<String>, source: WasmSource) -> Self {
+        let name = name.into();
+        self.cache.lock().unwrap().remove(&name);
+        self.sources.insert(name, source);
+        self
+    }
+
+    /// The names of the registered contracts.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.sources.keys().map(String::as_str)
+    }
+
+    /// The wasm code of the contract `name`, resolving it from its source if this is the
+    /// first time it is needed.
+    pub async fn wasm(&self, name: &str) -> Result<Vec<u8>> {
+        if let Some(wasm) = self.cache.lock().unwrap().get(name) {
+            return Ok(wasm.to_vec());
+        }
+
+        let source = self.sources.get(name).ok_or_else(|| {
+            ErrorKind::DataConversion.message(format!("no contract named `{name}` is registered"))
+        })?;
+        let wasm = source.resolve().await.map_err(|e| {
+            ErrorKind::Io.full(format!("failed to resolve the wasm of `{name}`"), e)
+        })?;
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), wasm.as_slice().into());
+        Ok(wasm)
+    }
+
+    /// Deploy the contract `name` to a new dev account, like [`Worker::dev_deploy`].
+    pub async fn deploy<T>(&self, worker: &Worker<T>, name: &str) -> Result<Contract>
+    where
+        T: DevNetwork + 'static,
+    {
+        worker.dev_deploy(&self.wasm(name).await?).await
+    }
+
+    /// Deploy the contract `name` to the account `id`, like [`Worker::dev_deploy_to`].
+    pub async fn deploy_to<T>(&self, worker: &Worker<T>, name: &str, id: &str) -> Result<Contract>
+    where
+        T: DevNetwork + 'static,
+    {
+        worker.dev_deploy_to(&self.wasm(name).await?, id).await
+    }
+}
+
+impl fmt::Debug for ContractRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContractRegistry")
+            .field("sources", &self.sources)
+            .finish()
+    }
+}
@@ -7,17 +7,21 @@ use base64::{engine::general_purpose, Engine as _};
 use near_account_id::AccountId;
 use near_gas::NearGas;
 use near_primitives::borsh;
-use near_primitives::errors::TxExecutionError;
 use near_primitives::views::{
-    CallResult, ExecutionOutcomeWithIdView, ExecutionStatusView, FinalExecutionOutcomeView,
-    FinalExecutionStatus,
+    ActionView, CallResult, ExecutionOutcomeWithIdView, ExecutionStatusView,
+    FinalExecutionOutcomeView, FinalExecutionStatus,
 };
 
 use crate::error::ErrorKind;
-use crate::types::{CryptoHash, Gas, NearToken};
+use crate::types::{CryptoHash, Event, Gas, NearToken};
 
 pub type Result<T, E = crate::error::Error> = core::result::Result<T, E>;
 
+/// Errors a transaction can fail with, as found in an [`ExecutionFailure`].
+pub use near_primitives::errors::{
+    ActionError, ActionErrorKind, FunctionCallError, TxExecutionError,
+};
+
 /// Execution related info as a result of performing a successful transaction
 /// execution on the network.
 ///
@@ -121,6 +125,51 @@ impl ExecutionDetails {
             .map(String::as_str)
             .collect()
     }
+
+    /// Decode all the events of type `E` emitted by the transaction and its receipts.
+    pub fn events_of<E: crate::types::TypedEvent>(&self) -> Result<Vec<E>> {
+        let mut events = Vec::new();
+        for outcome in self.outcomes() {
+            for event in outcome.events() {
+                events.extend(event.decode::<E>()?);
+            }
+        }
+        Ok(events)
+    }
+
+    /// Render the gas burnt by each outcome in the folded stack format, where every
+    /// line is the `;` separated path of executor accounts from the transaction down
+    /// to a receipt, followed by the gas burnt at that point of the call tree.
+    pub fn folded_stacks(&self) -> String {
+        let mut lines = Vec::new();
+        let mut stack = Vec::new();
+        self.fold_outcome(&self.transaction, &mut stack, &mut lines);
+        lines.join("\n")
+    }
+
+    fn fold_outcome<'a>(
+        &'a self,
+        outcome: &'a ExecutionOutcome,
+        stack: &mut Vec<&'a str>,
+        lines: &mut Vec<String>,
+    ) {
+        stack.push(outcome.executor_id.as_str());
+        lines.push(format!(
+            "{} {}",
+            stack.join(";"),
+            outcome.gas_burnt.as_gas()
+        ));
+        for receipt_id in &outcome.receipt_ids {
+            if let Some(receipt) = self
+                .receipts
+                .iter()
+                .find(|receipt| &receipt.transaction_hash == receipt_id)
+            {
+                self.fold_outcome(receipt, stack, lines);
+            }
+        }
+        stack.pop();
+    }
 }
 
 /// The result after evaluating the status of an execution. This can be [`ExecutionSuccess`]
@@ -162,6 +211,8 @@ pub struct ExecutionFinalResult {
 
     pub(crate) status: FinalExecutionStatus,
     pub(crate) details: ExecutionDetails,
+    /// Names of the functions called by the transaction itself.
+    pub(crate) method_names: Vec<String>,
 }
 
 impl fmt::Debug for ExecutionFinalResult {
@@ -175,6 +226,58 @@ impl fmt::Debug for ExecutionFinalResult {
     }
 }
 
+/// Summary of the transaction meant to be read by people, with one line for the transaction
+/// and one for each receipt, followed by their logs:
+/// ```text
+/// alice.near -> market.near.buy: failed: Smart contract panicked: sold out, burnt 4.2 Tgas
+///   alice.near: succeeded, burnt 0.3 Tgas
+///   market.near: failed: Smart contract panicked: sold out, burnt 3.9 Tgas
+///     log: checking stock
+/// ```
+impl fmt::Display for ExecutionFinalResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let transaction = &self.details.transaction;
+        let receiver_id = self
+            .details
+            .receipts
+            .first()
+            .map_or(&transaction.executor_id, |receipt| &receipt.executor_id);
+        write!(f, "{} -> {}", transaction.executor_id, receiver_id)?;
+        if !self.method_names.is_empty() {
+            write!(f, ".{}", self.method_names.join(","))?;
+        }
+        let status = match &self.status {
+            FinalExecutionStatus::SuccessValue(value) => success_summary(value),
+            FinalExecutionStatus::Failure(err) => format!("failed: {}", err),
+            status => format!("{:?}", status),
+        };
+        writeln!(f, ": {}, burnt {}", status, self.total_gas_burnt)?;
+
+        for outcome in self.outcomes() {
+            writeln!(
+                f,
+                "  {}: {}, burnt {}",
+                outcome.executor_id,
+                outcome.status_summary(),
+                outcome.gas_burnt
+            )?;
+            for log in &outcome.logs {
+                writeln!(f, "    log: {}", log)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Summary of a successful execution, along with the value it returned if there is one.
+fn success_summary(value: &[u8]) -> String {
+    if value.is_empty() {
+        "succeeded".to_string()
+    } else {
+        format!("succeeded with {}", String::from_utf8_lossy(value))
+    }
+}
+
 impl ExecutionFinalResult {
     pub(crate) fn from_view(view: FinalExecutionOutcomeView) -> Self {
         let total_gas_burnt = view.transaction_outcome.outcome.gas_burnt
@@ -184,6 +287,16 @@ impl ExecutionFinalResult {
                 .map(|t| t.outcome.gas_burnt)
                 .sum::<u64>();
 
+        let method_names = view
+            .transaction
+            .actions
+            .into_iter()
+            .filter_map(|action| match action {
+                ActionView::FunctionCall { method_name, .. } => Some(method_name),
+                _ => None,
+            })
+            .collect();
+
         let transaction = view.transaction_outcome.into();
         let receipts = view
             .receipts_outcome
@@ -199,6 +312,7 @@ impl ExecutionFinalResult {
                 transaction,
                 receipts,
             },
+            method_names,
         }
     }
 
@@ -278,6 +392,23 @@ impl ExecutionFinalResult {
         matches!(self.status, FinalExecutionStatus::Failure(_))
     }
 
+    /// The error the transaction failed with, or `None` if it succeeded. Look at
+    /// [`ExecutionFailure`] for helpers to inspect it, obtained through
+    /// [`into_result`](Self::into_result).
+    pub fn failure(&self) -> Option<&TxExecutionError> {
+        match &self.status {
+            FinalExecutionStatus::Failure(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Checks whether the transaction failed with an error whose message contains `pat`,
+    /// such as the code of an expected contract panic.
+    pub fn failure_message_contains(&self, pat: &str) -> bool {
+        self.failure()
+            .is_some_and(|err| err.to_string().contains(pat))
+    }
+
     /// Returns just the transaction outcome.
     pub fn outcome(&self) -> &ExecutionOutcome {
         self.details.outcome()
@@ -310,6 +441,21 @@ impl ExecutionFinalResult {
     pub fn logs(&self) -> Vec<&str> {
         self.details.logs()
     }
+
+    /// Render the gas burnt across the call tree of this execution in the folded stack
+    /// format, which can be fed directly into flamegraph tooling such as `inferno` or
+    /// `flamegraph.pl`. See [`ExecutionOutcome::executor_id`] for the frame names used.
+    pub fn folded_stacks(&self) -> String {
+        self.details.folded_stacks()
+    }
+
+    /// Decode all the events of type `E` emitted by the transaction and its receipts, such
+    /// as the ones defined through the [`events!`] macro.
+    ///
+    /// [`events!`]: crate::events
+    pub fn events_of<E: crate::types::TypedEvent>(&self) -> Result<Vec<E>> {
+        self.details.events_of()
+    }
 }
 
 impl ExecutionSuccess {
@@ -336,6 +482,52 @@ impl ExecutionSuccess {
     }
 }
 
+impl ExecutionFailure {
+    /// The error the transaction failed with.
+    pub fn error(&self) -> &TxExecutionError {
+        &self.value
+    }
+
+    /// Consume this failure, returning the error the transaction failed with.
+    pub fn into_error(self) -> TxExecutionError {
+        self.value
+    }
+
+    /// The error of the action that failed, or `None` if the transaction itself was invalid.
+    pub fn action_error(&self) -> Option<&ActionError> {
+        match &self.value {
+            TxExecutionError::ActionError(err) => Some(err),
+            TxExecutionError::InvalidTxError(_) => None,
+        }
+    }
+
+    /// The error of the function call that failed, such as the contract panicking or running
+    /// out of gas, or `None` if the transaction failed for any other reason.
+    pub fn function_call_error(&self) -> Option<&FunctionCallError> {
+        match &self.action_error()?.kind {
+            ActionErrorKind::FunctionCallError(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// The message the contract panicked with, such as `ERR_NOT_OWNER` out of
+    /// `env::panic_str("ERR_NOT_OWNER")`, or `None` if the contract did not panic.
+    pub fn panic_message(&self) -> Option<&str> {
+        match self.function_call_error()? {
+            FunctionCallError::ExecutionError(msg) => {
+                Some(msg.strip_prefix("Smart contract panicked: ").unwrap_or(msg))
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks whether the message of the error the transaction failed with contains `pat`,
+    /// such as the code of an expected contract panic.
+    pub fn failure_message_contains(&self, pat: &str) -> bool {
+        self.value.to_string().contains(pat)
+    }
+}
+
 impl<T> ExecutionResult<T> {
     /// Returns just the transaction outcome.
     pub fn outcome(&self) -> &ExecutionOutcome {
@@ -369,6 +561,21 @@ impl<T> ExecutionResult<T> {
     pub fn logs(&self) -> Vec<&str> {
         self.details.logs()
     }
+
+    /// Render the gas burnt across the call tree of this execution in the folded stack
+    /// format, which can be fed directly into flamegraph tooling such as `inferno` or
+    /// `flamegraph.pl`. See [`ExecutionOutcome::executor_id`] for the frame names used.
+    pub fn folded_stacks(&self) -> String {
+        self.details.folded_stacks()
+    }
+
+    /// Decode all the events of type `E` emitted by the transaction and its receipts, such
+    /// as the ones defined through the [`events!`] macro.
+    ///
+    /// [`events!`]: crate::events
+    pub fn events_of<E: crate::types::TypedEvent>(&self) -> Result<Vec<E>> {
+        self.details.events_of()
+    }
 }
 
 /// The result from a call into a View function. This contains the contents or
@@ -455,6 +662,35 @@ impl ExecutionOutcome {
         )
     }
 
+    /// Grab all the [NEP-297] events emitted by this transaction or receipt, skipping over logs
+    /// that are not events.
+    ///
+    /// [NEP-297]: https://nomicon.io/Standards/EventsFormat
+    pub fn events(&self) -> Vec<Event> {
+        self.logs
+            .iter()
+            .filter_map(|log| {
+                Event::from_log(
+                    log,
+                    &self.executor_id,
+                    self.transaction_hash,
+                    self.block_hash,
+                )
+            })
+            .collect()
+    }
+
+    /// Whether this outcome succeeded, along with the value it returned, or the error it
+    /// failed with.
+    pub(crate) fn status_summary(&self) -> String {
+        match &self.status {
+            ExecutionStatusView::SuccessValue(value) => success_summary(value),
+            ExecutionStatusView::SuccessReceiptId(_) => "succeeded".to_string(),
+            ExecutionStatusView::Failure(err) => format!("failed: {}", err),
+            ExecutionStatusView::Unknown => "unknown".to_string(),
+        }
+    }
+
     /// Converts this [`ExecutionOutcome`] into a Result type to match against whether the
     /// particular outcome has failed or not.
     pub fn into_result(self) -> Result<ValueOrReceiptId> {
@@ -546,3 +782,147 @@ impl From<ExecutionOutcomeWithIdView> for ExecutionOutcome {
         }
     }
 }
+
+/// Differences between the outcomes of two runs of the same scenario, such as before and
+/// after upgrading a contract or the protocol. Look at [`compare`] for how to get one.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct OutcomeDiff {
+    /// Whether the final status differs, such as one of the runs failing or the runs
+    /// returning different values.
+    pub status_changed: bool,
+    /// Logs only found in the first run, leaving out the ones that are events.
+    pub logs_removed: Vec<String>,
+    /// Logs only found in the second run, leaving out the ones that are events.
+    pub logs_added: Vec<String>,
+    /// Events only emitted by the first run. Events are compared by their standard, version,
+    /// name and data, so the accounts and transactions they come from can differ.
+    pub events_removed: Vec<Event>,
+    /// Events only emitted by the second run.
+    pub events_added: Vec<Event>,
+    /// Total gas burnt by the first run.
+    pub gas_before: Gas,
+    /// Total gas burnt by the second run.
+    pub gas_after: Gas,
+}
+
+impl OutcomeDiff {
+    /// Whether the gas burnt by the second run is within `tolerance` of the first run, with
+    /// `tolerance` being relative, such as `0.05` allowing for a 5% difference.
+    pub fn gas_within(&self, tolerance: f64) -> bool {
+        let before = self.gas_before.as_gas() as f64;
+        let after = self.gas_after.as_gas() as f64;
+        (after - before).abs() <= before * tolerance
+    }
+
+    /// Whether both runs ended up the same, apart from their gas usage being allowed to
+    /// differ by `gas_tolerance`, as in [`OutcomeDiff::gas_within`].
+    pub fn is_equivalent(&self, gas_tolerance: f64) -> bool {
+        !self.status_changed
+            && self.logs_removed.is_empty()
+            && self.logs_added.is_empty()
+            && self.events_removed.is_empty()
+            && self.events_added.is_empty()
+            && self.gas_within(gas_tolerance)
+    }
+}
+
+impl fmt::Display for OutcomeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.status_changed {
+            writeln!(f, "status changed")?;
+        }
+        for log in &self.logs_removed {
+            writeln!(f, "- log: {}", log)?;
+        }
+        for log in &self.logs_added {
+            writeln!(f, "+ log: {}", log)?;
+        }
+        let events = self
+            .events_removed
+            .iter()
+            .map(|event| ("-", event))
+            .chain(self.events_added.iter().map(|event| ("+", event)));
+        for (sign, event) in events {
+            write!(f, "{} event: {} {}", sign, event.standard, event.event)?;
+            match &event.data {
+                Some(data) => writeln!(f, " {}", data)?,
+                None => writeln!(f)?,
+            }
+        }
+        writeln!(f, "gas: {} -> {}", self.gas_before, self.gas_after)
+    }
+}
+
+/// Compare the outcomes of two runs of the same scenario, such as for checking a golden run
+/// against a run with an upgraded contract or protocol version.
+///
+/// ```
+/// # fn check(before: &near_workspaces::result::ExecutionFinalResult, after: &near_workspaces::result::ExecutionFinalResult) {
+/// let diff = near_workspaces::compare(before, after);
+/// assert!(diff.is_equivalent(0.05), "{:#?}", diff);
+/// # }
+/// ```
+pub fn compare(before: &ExecutionFinalResult, after: &ExecutionFinalResult) -> OutcomeDiff {
+    let plain_logs = |result: &ExecutionFinalResult| {
+        result
+            .outcomes()
+            .into_iter()
+            .flat_map(|outcome| {
+                outcome.logs.iter().filter(move |log| {
+                    Event::from_log(
+                        log,
+                        &outcome.executor_id,
+                        outcome.transaction_hash,
+                        outcome.block_hash,
+                    )
+                    .is_none()
+                })
+            })
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    let events = |result: &ExecutionFinalResult| {
+        result
+            .outcomes()
+            .into_iter()
+            .flat_map(|outcome| outcome.events())
+            .collect::<Vec<_>>()
+    };
+    let same_event = |a: &Event, b: &Event| {
+        a.standard == b.standard && a.version == b.version && a.event == b.event && a.data == b.data
+    };
+
+    let (logs_removed, logs_added) =
+        multiset_diff(plain_logs(before), plain_logs(after), |a, b| a == b);
+    let (events_removed, events_added) = multiset_diff(events(before), events(after), same_event);
+
+    OutcomeDiff {
+        status_changed: before.status != after.status,
+        logs_removed,
+        logs_added,
+        events_removed,
+        events_added,
+        gas_before: before.total_gas_burnt,
+        gas_after: after.total_gas_burnt,
+    }
+}
+
+/// Split up two lists into the items only found in the first, and the ones only found in the
+/// second, with each item matching up with at most one item of the other list.
+fn multiset_diff<T>(
+    before: Vec<T>,
+    mut after: Vec<T>,
+    eq: impl Fn(&T, &T) -> bool,
+) -> (Vec<T>, Vec<T>) {
+    let mut removed = Vec::new();
+    for item in before {
+        match after.iter().position(|other| eq(&item, other)) {
+            Some(idx) => {
+                after.remove(idx);
+            }
+            None => removed.push(item),
+        }
+    }
+    (removed, after)
+}
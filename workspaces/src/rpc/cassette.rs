@@ -0,0 +1,159 @@
+//! Cassettes record the JSON-RPC interactions of a test run into a fixture file, such that
+//! they can be replayed deterministically and offline later on. Both modes are implemented
+//! as a [`Layer`] at the bottom of the middleware stack.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::ErrorKind;
+use crate::result::Result;
+use crate::rpc::local::error_response;
+use crate::rpc::middleware::{Layer, Next};
+use crate::rpc::BoxFuture;
+
+/// Whether a cassette file is being recorded into or replayed from.
+#[derive(Debug, Clone)]
+pub(crate) enum Cassette {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+/// A single recorded request along with the response the RPC node gave back for it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    method: String,
+    params: Value,
+    response: Value,
+}
+
+impl Cassette {
+    /// The layer recording or replaying this cassette.
+    pub(crate) fn layer(&self) -> Result<Arc<dyn Layer>> {
+        Ok(match self {
+            Self::Record(path) => Arc::new(Recorder::new(path.clone())?),
+            Self::Replay(path) => Arc::new(Replayer::load(path)?),
+        })
+    }
+}
+
+struct Recorder {
+    path: PathBuf,
+    interactions: tokio::sync::Mutex<Vec<Interaction>>,
+}
+
+impl Recorder {
+    fn new(path: PathBuf) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| ErrorKind::Io.full("failed to create cassette directory", e))?;
+        }
+
+        Ok(Self {
+            path,
+            interactions: Default::default(),
+        })
+    }
+}
+
+impl Layer for Recorder {
+    fn call<'a>(&'a self, request: Value, next: Next<'a>) -> BoxFuture<'a, Value> {
+        Box::pin(async move {
+            let method = request["method"].as_str().unwrap_or_default().to_string();
+            let params = request.get("params").cloned().unwrap_or_default();
+            let response = next.run(request).await;
+
+            // Ids are assigned by the client per request, so they are left out of the
+            // recording and filled back in when replaying.
+            let mut recorded = response.clone();
+            if let Some(recorded) = recorded.as_object_mut() {
+                recorded.remove("id");
+            }
+
+            let mut interactions = self.interactions.lock().await;
+            interactions.push(Interaction {
+                method,
+                params,
+                response: recorded,
+            });
+
+            // Write out the whole cassette every time, so that it is still complete when the
+            // test fails or panics midway through.
+            let write = serde_json::to_vec_pretty(&*interactions)
+                .map_err(std::io::Error::from)
+                .and_then(|bytes| std::fs::write(&self.path, bytes));
+            if let Err(err) = write {
+                tracing::error!(target: "workspaces", "failed to write cassette {:?}: {}", self.path, err);
+            }
+
+            response
+        })
+    }
+}
+
+struct Replayer {
+    interactions: Vec<Interaction>,
+    replayed: Mutex<Vec<bool>>,
+}
+
+impl Replayer {
+    fn load(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path).map_err(|e| {
+            ErrorKind::Io.full(format!("failed to read cassette {}", path.display()), e)
+        })?;
+        let interactions: Vec<Interaction> = serde_json::from_slice(&bytes)
+            .map_err(|e| ErrorKind::DataConversion.full("cassette file is malformed", e))?;
+
+        Ok(Self {
+            replayed: Mutex::new(vec![false; interactions.len()]),
+            interactions,
+        })
+    }
+
+    fn respond(&self, request: &Value) -> Value {
+        let id = request.get("id").cloned().unwrap_or_default();
+        let method = request["method"].as_str().unwrap_or_default();
+        let params = request.get("params").cloned().unwrap_or_default();
+
+        // Responses are replayed in the order they were recorded in. Requests that are not
+        // byte-for-byte the same as when recording, such as transactions signed by randomly
+        // generated keys, get the next response recorded for the same method instead.
+        let mut replayed = self.replayed.lock().unwrap();
+        let unreplayed = |exact: bool| {
+            self.interactions
+                .iter()
+                .zip(replayed.iter())
+                .position(|(interaction, replayed)| {
+                    !replayed
+                        && interaction.method == method
+                        && (!exact || interaction.params == params)
+                })
+        };
+
+        match unreplayed(true).or_else(|| unreplayed(false)) {
+            Some(i) => {
+                replayed[i] = true;
+                let mut response = self.interactions[i].response.clone();
+                response["id"] = id;
+                response
+            }
+            None => error_response(
+                id,
+                "REQUEST_VALIDATION_ERROR",
+                serde_json::json!({
+                    "name": "METHOD_NOT_FOUND",
+                    "info": { "method_name": method },
+                }),
+            ),
+        }
+    }
+}
+
+impl Layer for Replayer {
+    fn call<'a>(&'a self, request: Value, _next: Next<'a>) -> BoxFuture<'a, Value> {
+        let response = self.respond(&request);
+        Box::pin(async move { response })
+    }
+}
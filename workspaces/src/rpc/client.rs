@@ -1,12 +1,14 @@
-use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap};
+use std::convert::TryFrom;
 use std::fmt::Debug;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crate::types::NearToken;
 use near_gas::NearGas;
 use tokio::sync::RwLock;
-use tokio_retry::strategy::{jitter, ExponentialBackoff};
 use tokio_retry::Retry;
 
 use near_jsonrpc_client::errors::{JsonRpcError, JsonRpcServerError};
@@ -14,16 +16,17 @@ use near_jsonrpc_client::methods::tx::{RpcTransactionError, RpcTransactionRespon
 use near_jsonrpc_client::{methods, JsonRpcClient, MethodCallResult};
 use near_jsonrpc_primitives::types::query::QueryResponseKind;
 use near_primitives::account::{AccessKey, AccessKeyPermission};
+use near_primitives::action::delegate::{DelegateAction, NonDelegateAction, SignedDelegateAction};
 use near_primitives::errors::InvalidTxError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::transaction::{
     Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeployContractAction,
-    FunctionCallAction, SignedTransaction, TransferAction,
+    SignedTransaction, Transaction, TransactionV0, TransferAction,
 };
-use near_primitives::types::{BlockReference, Finality, Gas};
+use near_primitives::types::{BlockHeight, BlockHeightDelta, BlockId, BlockReference, Finality};
 use near_primitives::views::{
-    AccessKeyView, BlockView, FinalExecutionOutcomeView, QueryRequest, StatusResponse,
-    TxExecutionStatus,
+    AccessKeyView, BlockView, FinalExecutionOutcomeView, QueryRequest, StateChangeWithCauseView,
+    StateChangesRequestView, StatusResponse, TxExecutionStatus,
 };
 
 #[cfg(feature = "experimental")]
@@ -35,19 +38,31 @@ use {
     },
     near_primitives::{
         types::MaybeBlockId,
-        views::{validator_stake_view::ValidatorStakeView, ReceiptView, StateChangesRequestView},
+        views::{validator_stake_view::ValidatorStakeView, ReceiptView},
     },
 };
 
 use crate::error::{Error, ErrorKind, RpcErrorCode};
 use crate::operations::TransactionStatus;
-use crate::result::Result;
+use crate::result::{Result, ViewResultDetails};
+use crate::rpc::local::LocalServer;
+use crate::rpc::middleware::{self, Layer};
+use crate::rpc::query::ViewCacheKey;
+use crate::rpc::retry::RetryPolicy;
+use crate::rpc::tool;
 use crate::types::{AccountId, InMemorySigner, Nonce, PublicKey};
 use crate::{Network, Worker};
 
+/// The parts of the genesis config needed regardless of the `experimental` feature.
+#[derive(Debug, serde::Deserialize)]
+struct PartialGenesisConfig {
+    epoch_length: BlockHeightDelta,
+}
+
+impl methods::RpcHandlerResponse for PartialGenesisConfig {}
+
 pub(crate) const DEFAULT_CALL_FN_GAS: NearGas = NearGas::from_tgas(10);
 pub(crate) const DEFAULT_CALL_DEPOSIT: NearToken = NearToken::from_near(0);
-pub(crate) const DEFAULT_PRIORITY_FEE: u64 = 0;
 
 /// A client that wraps around [`JsonRpcClient`], and provides more capabilities such
 /// as retry w/ exponential backoff and utility functions for sending transactions.
@@ -56,11 +71,75 @@ pub struct Client {
     rpc_client: JsonRpcClient,
     /// AccessKey nonces to reference when sending transactions.
     pub(crate) access_key_nonces: RwLock<HashMap<(AccountId, near_crypto::PublicKey), AtomicU64>>,
+    /// Number of times a transaction had to be re-signed and broadcasted again.
+    pub(crate) rebroadcasts: AtomicU64,
+    /// Hashes and senders of transactions that were being sent when the future sending them
+    /// got dropped, so they might still end up being executed.
+    pub(crate) detached_txs: std::sync::Mutex<Vec<(CryptoHash, AccountId)>>,
+    /// Policy for retrying failed requests.
+    pub(crate) retry_policy: RetryPolicy,
+    /// Accounts that signed or received transactions sent through this client.
+    pub(crate) known_accounts: std::sync::Mutex<BTreeSet<AccountId>>,
+    /// Human readable labels of accounts, substituted for their ids in output meant to be
+    /// read by people. Look at [`Worker::label`].
+    pub(crate) labels: std::sync::RwLock<HashMap<AccountId, String>>,
+    /// Accounts allowed to sign transactions, or `None` if any account may. Look at
+    /// [`Worker::declare_signers`].
+    pub(crate) allowed_signers: std::sync::RwLock<Option<BTreeSet<AccountId>>>,
+    /// Results of view calls memoized through [`CachedView`], along with when they were cached.
+    ///
+    /// [`CachedView`]: crate::rpc::query::CachedView
+    pub(crate) view_cache: std::sync::Mutex<HashMap<ViewCacheKey, (Instant, ViewResultDetails)>>,
+    /// Server in between this client and the RPC node, such as one recording or replaying
+    /// a cassette. Kept here so it lives as long as the client does.
+    _local_server: Option<LocalServer>,
+}
+
+/// Options of the HTTP requests made to the RPC node.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HttpOptions {
+    /// Time to wait for the connection to the RPC node to be established, or indefinitely.
+    pub(crate) connect_timeout: Option<Duration>,
+    /// Time to wait for a request to complete, from sending it to receiving the response,
+    /// or indefinitely.
+    pub(crate) request_timeout: Option<Duration>,
+    /// Additional headers sent along with every request, such as for authenticating with
+    /// hosted RPC providers.
+    pub(crate) headers: Vec<(String, String)>,
+}
+
+impl HttpOptions {
+    /// HTTP client sending JSON requests with these options.
+    pub(crate) fn http_client(&self) -> Result<reqwest::Client> {
+        use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| ErrorKind::DataConversion.full(format!("invalid header {name}"), e))?;
+            let value = HeaderValue::from_str(value).map_err(|e| {
+                ErrorKind::DataConversion.full(format!("invalid value of header {name}"), e)
+            })?;
+            headers.append(name, value);
+        }
+
+        let mut builder = reqwest::Client::builder().default_headers(headers);
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.request_timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder
+            .build()
+            .map_err(|e| RpcErrorCode::ConnectionFailure.custom(e))
+    }
 }
 
 impl Client {
-    pub(crate) fn new(rpc_addr: &str, api_key: Option<String>) -> Result<Self> {
-        let connector = JsonRpcClient::new_client();
+    pub(crate) fn new(rpc_addr: &str, api_key: Option<String>, http: &HttpOptions) -> Result<Self> {
+        let connector = JsonRpcClient::with(http.http_client()?);
         let mut rpc_client = connector.connect(rpc_addr);
         if let Some(api_key) = api_key {
             let api_key = near_jsonrpc_client::auth::ApiKey::new(api_key)
@@ -72,14 +151,74 @@ impl Client {
             rpc_client,
             rpc_addr: rpc_addr.into(),
             access_key_nonces: RwLock::new(HashMap::new()),
+            rebroadcasts: AtomicU64::new(0),
+            detached_txs: Default::default(),
+            retry_policy: RetryPolicy::default(),
+            known_accounts: Default::default(),
+            labels: Default::default(),
+            allowed_signers: Default::default(),
+            view_cache: Default::default(),
+            _local_server: None,
         })
     }
 
+    /// Create a client that talks to the first healthy RPC node out of `rpc_addrs` through
+    /// a stack of middleware [`Layer`]s.
+    pub(crate) async fn with_layers(
+        rpc_addrs: &[String],
+        api_key: Option<String>,
+        http: &HttpOptions,
+        layers: Vec<Arc<dyn Layer>>,
+    ) -> Result<Self> {
+        let handler = middleware::stack(layers, rpc_addrs, api_key.clone(), http)?;
+        let server = LocalServer::start(handler).await?;
+        let mut client = Self::new(&server.rpc_addr(), api_key, http)?;
+        client._local_server = Some(server);
+        Ok(client)
+    }
+
+    /// Remember the accounts involved in a transaction, for [`Worker::summarize`].
+    pub(crate) fn record_accounts(&self, accounts: &[&AccountId]) {
+        if let Ok(mut known) = self.known_accounts.lock() {
+            known.extend(accounts.iter().map(|&account_id| account_id.clone()));
+        }
+    }
+
+    /// Allow `account_id` to sign transactions, if only declared accounts are allowed to.
+    pub(crate) fn declare_signer(&self, account_id: &AccountId) {
+        if let Ok(mut allowed) = self.allowed_signers.write() {
+            if let Some(allowed) = allowed.as_mut() {
+                allowed.insert(account_id.clone());
+            }
+        }
+    }
+
+    /// Make sure `signer_id` is allowed to sign transactions. Look at
+    /// [`Worker::declare_signers`].
+    pub(crate) fn check_signer(&self, signer_id: &AccountId) -> Result<()> {
+        let allowed = self.allowed_signers.read().map_or(true, |allowed| {
+            allowed
+                .as_ref()
+                .is_none_or(|allowed| allowed.contains(signer_id))
+        });
+        if allowed {
+            Ok(())
+        } else {
+            Err(ErrorKind::Other.message(format!(
+                "{} is not allowed to sign transactions, as it was not declared with \
+                Worker::declare_signers",
+                signer_id
+            )))
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
     pub(crate) async fn query_broadcast_tx(
         &self,
         method: &methods::broadcast_tx_commit::RpcBroadcastTxCommitRequest,
+        retries: bool,
     ) -> MethodCallResult<FinalExecutionOutcomeView, RpcTransactionError> {
-        retry(|| async {
+        self.retry_if(retries, || async {
             let result = self.rpc_client.call(method).await;
             match &result {
                 Ok(response) => {
@@ -116,30 +255,58 @@ impl Client {
         .await
     }
 
+    #[allow(clippy::result_large_err)]
     pub(crate) async fn query_nolog<M>(&self, method: M) -> MethodCallResult<M::Response, M::Error>
     where
         M: methods::RpcMethod + Send + Sync,
+        M::Error: Debug,
     {
-        retry(|| async { self.rpc_client.call(&method).await }).await
+        self.retry_policy
+            .retry(|| async { self.rpc_client.call(&method).await })
+            .await
     }
 
+    /// Run `task` according to the retry policy of this client if `retries` are enabled,
+    /// or only once otherwise.
+    pub(crate) async fn retry_if<R, E, T, F>(&self, retries: bool, mut task: F) -> T::Output
+    where
+        F: FnMut() -> T + Send,
+        T: core::future::Future<Output = core::result::Result<R, E>> + Send,
+        E: Debug,
+    {
+        if retries {
+            self.retry_policy.retry(task).await
+        } else {
+            task().await
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
     pub(crate) async fn query<M>(&self, method: M) -> MethodCallResult<M::Response, M::Error>
     where
         M: methods::RpcMethod + Debug + Send + Sync,
         M::Response: Debug + Send,
         M::Error: Debug + Send,
     {
-        retry(|| async {
-            let result = self.rpc_client.call(&method).await;
-            tracing::debug!(
-                target: "workspaces",
-                "Querying RPC with {:?} resulted in {:?}",
-                method,
-                result
-            );
+        self.retry_policy.retry(|| self.query_once(&method)).await
+    }
+
+    /// Same as [`Client::query`], but without retrying the query on failure.
+    #[allow(clippy::result_large_err)]
+    pub(crate) async fn query_once<M>(&self, method: &M) -> MethodCallResult<M::Response, M::Error>
+    where
+        M: methods::RpcMethod + Debug + Send + Sync,
+        M::Response: Debug + Send,
+        M::Error: Debug + Send,
+    {
+        let result = self.rpc_client.call(method).await;
+        tracing::debug!(
+            target: "workspaces",
+            "Querying RPC with {:?} resulted in {:?}",
+            method,
             result
-        })
-        .await
+        );
+        result
     }
 
     async fn send_tx_and_retry(
@@ -151,29 +318,6 @@ impl Client {
         send_batch_tx_and_retry(self, signer, receiver_id, vec![action]).await
     }
 
-    pub(crate) async fn call(
-        &self,
-        signer: &InMemorySigner,
-        contract_id: &AccountId,
-        method_name: String,
-        args: Vec<u8>,
-        gas: Gas,
-        deposit: NearToken,
-    ) -> Result<FinalExecutionOutcomeView> {
-        self.send_tx_and_retry(
-            signer,
-            contract_id,
-            FunctionCallAction {
-                args,
-                method_name,
-                gas,
-                deposit: deposit.as_yoctonear(),
-            }
-            .into(),
-        )
-        .await
-    }
-
     pub(crate) async fn view_block(&self, block_ref: Option<BlockReference>) -> Result<BlockView> {
         let block_reference = block_ref.unwrap_or_else(|| Finality::None.into());
         let block_view = self
@@ -184,6 +328,27 @@ impl Client {
         Ok(block_view)
     }
 
+    /// All the changes of the kind requested by `request` made in the block `block_hash`,
+    /// along with their causes, in the order they got made in.
+    pub(crate) async fn state_changes_in_block(
+        &self,
+        block_hash: crate::CryptoHash,
+        request: StateChangesRequestView,
+    ) -> Result<Vec<StateChangeWithCauseView>> {
+        let resp = self
+            .query(
+                &methods::EXPERIMENTAL_changes::RpcStateChangesInBlockByTypeRequest {
+                    block_reference: BlockReference::BlockId(BlockId::Hash(
+                        near_primitives::hash::CryptoHash(block_hash.0),
+                    )),
+                    state_changes_request: request,
+                },
+            )
+            .await
+            .map_err(|e| RpcErrorCode::QueryFailure.custom(e))?;
+        Ok(resp.changes)
+    }
+
     pub(crate) async fn deploy(
         &self,
         signer: &InMemorySigner,
@@ -222,7 +387,9 @@ impl Client {
         new_account_pk: PublicKey,
         amount: NearToken,
     ) -> Result<FinalExecutionOutcomeView> {
-        send_batch_tx_and_retry(
+        // Only used for creating dev accounts with the root account, on behalf of workspaces
+        // itself rather than the test, so exempt from `Worker::declare_signers`.
+        sign_and_send_batch_tx(
             self,
             signer,
             new_account_id,
@@ -241,6 +408,7 @@ impl Client {
                 }
                 .into(),
             ],
+            true,
         )
         .await
     }
@@ -253,7 +421,9 @@ impl Client {
         amount: NearToken,
         code: Vec<u8>,
     ) -> Result<FinalExecutionOutcomeView> {
-        send_batch_tx_and_retry(
+        // Only used for creating dev accounts with the root account, on behalf of workspaces
+        // itself rather than the test, so exempt from `Worker::declare_signers`.
+        sign_and_send_batch_tx(
             self,
             signer,
             new_account_id,
@@ -273,6 +443,7 @@ impl Client {
                 .into(),
                 DeployContractAction { code }.into(),
             ],
+            true,
         )
         .await
     }
@@ -301,6 +472,25 @@ impl Client {
         Ok(result)
     }
 
+    /// The number of blocks in an epoch, as set in the genesis config of the network. Only
+    /// the needed parts of the genesis config get parsed, such that this does not need the
+    /// `experimental` feature.
+    pub(crate) async fn epoch_length(&self) -> Result<BlockHeightDelta> {
+        let genesis = self
+            .query(methods::any::<
+                std::result::Result<
+                    PartialGenesisConfig,
+                    methods::EXPERIMENTAL_genesis_config::RpcGenesisConfigError,
+                >,
+            >(
+                "EXPERIMENTAL_genesis_config", serde_json::Value::Null
+            ))
+            .await
+            .map_err(|e| RpcErrorCode::QueryFailure.custom(e))?;
+        Ok(genesis.epoch_length)
+    }
+
+    #[allow(clippy::result_large_err)]
     pub(crate) async fn tx_async_status(
         &self,
         sender_id: &AccountId,
@@ -333,7 +523,7 @@ impl Client {
 
         let retry_strategy =
             std::iter::repeat_with(|| Duration::from_millis(500)).take(2 * timeout_secs);
-        Retry::spawn(retry_strategy, || async { self.status().await })
+        Retry::start(retry_strategy, || async { self.status().await })
             .await
             .map_err(|e| {
                 Error::full(
@@ -502,44 +692,56 @@ async fn fetch_tx_nonce(
         let (access_key, block_hash) =
             access_key(client, account_id.clone(), public_key.clone()).await?;
 
-        // case where multiple writers end up at the same lock acquisition point and tries
-        // to overwrite the cached value that a previous writer already wrote.
-        let nonce = client
-            .access_key_nonces
-            .write()
-            .await
-            .entry(cache_key.clone())
-            .or_insert_with(|| AtomicU64::new(access_key.nonce + 1))
-            .fetch_max(access_key.nonce + 1, Ordering::SeqCst)
-            .max(access_key.nonce + 1);
+        // Multiple writers can end up at the same lock acquisition point when sending their
+        // first transactions concurrently. Only the first one gets to use the nonce right after
+        // the one on chain, while the rest allocate the ones following it from the cache.
+        let mut nonces = client.access_key_nonces.write().await;
+        let nonce = match nonces.entry(cache_key.clone()) {
+            Entry::Occupied(cached) => {
+                let cached = cached.get();
+                cached.fetch_max(access_key.nonce, Ordering::SeqCst);
+                cached.fetch_add(1, Ordering::SeqCst) + 1
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(AtomicU64::new(access_key.nonce + 1));
+                access_key.nonce + 1
+            }
+        };
 
         Ok((block_hash, nonce))
     }
 }
 
-pub(crate) async fn retry<R, E, T, F>(task: F) -> T::Output
-where
-    F: FnMut() -> T + Send,
-    T: core::future::Future<Output = core::result::Result<R, E>> + Send,
-{
-    // Exponential backoff starting w/ 5ms for maximum retry of 4 times with the following delays:
-    //   5, 25, 125, 625 ms
-    let retry_strategy = ExponentialBackoff::from_millis(5).map(jitter).take(4);
-
-    Retry::spawn(retry_strategy, task).await
-}
-
+#[allow(clippy::result_large_err)]
 pub(crate) async fn send_tx(
     client: &Client,
     cache_key: &(AccountId, near_crypto::PublicKey),
     tx: SignedTransaction,
-) -> Result<FinalExecutionOutcomeView> {
-    let result = client
-        .query_broadcast_tx(&methods::broadcast_tx_commit::RpcBroadcastTxCommitRequest {
-            signed_transaction: tx,
-        })
+    retries: bool,
+) -> MethodCallResult<FinalExecutionOutcomeView, RpcTransactionError> {
+    let tx_hash = tx.get_hash();
+    let mut in_flight = InFlightTx {
+        client,
+        tx: Some((tx_hash, cache_key.0.clone())),
+    };
+    let mut result = client
+        .query_broadcast_tx(
+            &methods::broadcast_tx_commit::RpcBroadcastTxCommitRequest {
+                signed_transaction: tx,
+            },
+            retries,
+        )
         .await;
 
+    // The transaction could still end up being executed after timing out, so wait on its
+    // outcome instead of failing, which would have it re-signed with a different nonce.
+    if let Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+        RpcTransactionError::TimeoutError,
+    ))) = &result
+    {
+        result = wait_for_tx(client, &cache_key.0, tx_hash).await;
+    }
+
     // InvalidNonce, cached nonce is potentially very far behind, so invalidate it.
     if let Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
         RpcTransactionError::InvalidTransaction {
@@ -552,38 +754,195 @@ pub(crate) async fn send_tx(
         nonces.remove(cache_key);
     }
 
-    result.map_err(|e| RpcErrorCode::BroadcastTxFailure.custom(e))
+    in_flight.tx = None;
+    result
 }
 
+/// Guard over a transaction being sent, which records the transaction as detached in the
+/// client if the future sending it gets dropped before finishing.
+struct InFlightTx<'a> {
+    client: &'a Client,
+    tx: Option<(CryptoHash, AccountId)>,
+}
+
+impl Drop for InFlightTx<'_> {
+    fn drop(&mut self) {
+        if let Some(tx) = self.tx.take() {
+            tracing::warn!(
+                target: "workspaces",
+                "Transaction {} from {} was dropped while in flight, and might still get executed",
+                tx.0,
+                tx.1
+            );
+            if let Ok(mut detached) = self.client.detached_txs.lock() {
+                detached.push(tx);
+            }
+        }
+    }
+}
+
+/// Wait on the outcome of a transaction that timed out while being broadcasted. Returns an
+/// `UnknownTransaction` error if the network has no record of the transaction.
+#[allow(clippy::result_large_err)]
+async fn wait_for_tx(
+    client: &Client,
+    sender_id: &AccountId,
+    tx_hash: CryptoHash,
+) -> MethodCallResult<FinalExecutionOutcomeView, RpcTransactionError> {
+    loop {
+        let resp = client
+            .tx_async_status(sender_id, tx_hash, TxExecutionStatus::ExecutedOptimistic)
+            .await;
+
+        match resp {
+            Ok(RpcTransactionResponse {
+                final_execution_outcome: Some(outcome),
+                ..
+            }) => break Ok(outcome.into_outcome()),
+            Ok(_)
+            | Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+                RpcTransactionError::TimeoutError,
+            ))) => tokio::time::sleep(Duration::from_millis(300)).await,
+            Err(err) => break Err(err),
+        }
+    }
+}
+
+/// Maximum number of times the same transaction gets re-signed and broadcasted again after it
+/// expired or got lost.
+const MAX_REBROADCASTS: usize = 3;
+
 pub(crate) async fn send_batch_tx_and_retry(
     client: &Client,
     signer: &InMemorySigner,
     receiver_id: &AccountId,
     actions: Vec<Action>,
 ) -> Result<FinalExecutionOutcomeView> {
-    let inner = signer.inner();
-    let cache_key = (
-        signer.account_id.clone(),
-        signer.secret_key.public_key().into(),
-    );
-    retry(|| async {
-        let (block_hash, nonce) = fetch_tx_nonce(client, &cache_key).await?;
-        send_tx(
-            client,
-            &cache_key,
-            SignedTransaction::from_actions(
-                nonce,
-                signer.account_id.clone(),
-                receiver_id.clone(),
-                &inner,
-                actions.clone(),
-                block_hash,
-                DEFAULT_PRIORITY_FEE,
-            ),
-        )
+    send_batch_tx(client, signer, receiver_id, actions, true).await
+}
+
+/// Sign and send a transaction with the `actions`, retrying on failure if `retries` are
+/// enabled. Transactions that got lost are rebroadcasted regardless.
+pub(crate) async fn send_batch_tx(
+    client: &Client,
+    signer: &InMemorySigner,
+    receiver_id: &AccountId,
+    actions: Vec<Action>,
+    retries: bool,
+) -> Result<FinalExecutionOutcomeView> {
+    client.check_signer(&signer.account_id)?;
+    sign_and_send_batch_tx(client, signer, receiver_id, actions, retries).await
+}
+
+async fn sign_and_send_batch_tx(
+    client: &Client,
+    signer: &InMemorySigner,
+    receiver_id: &AccountId,
+    actions: Vec<Action>,
+    retries: bool,
+) -> Result<FinalExecutionOutcomeView> {
+    client.record_accounts(&[&signer.account_id, receiver_id]);
+    let cache_key = (signer.account_id.clone(), signer.public_key().into());
+    client
+        .retry_if(retries, || async {
+            let (mut block_hash, nonce) = fetch_tx_nonce(client, &cache_key).await?;
+            let mut rebroadcasts = 0;
+            loop {
+                let tx = signer
+                    .sign_transaction(Transaction::V0(TransactionV0 {
+                        nonce,
+                        signer_id: signer.account_id.clone(),
+                        public_key: cache_key.1.clone(),
+                        receiver_id: receiver_id.clone(),
+                        block_hash,
+                        actions: actions.clone(),
+                    }))
+                    .await?;
+
+                let err = match send_tx(client, &cache_key, tx, retries).await {
+                    Ok(outcome) => break Ok(outcome),
+                    Err(err) => err,
+                };
+
+                // The transaction either expired before making it into a block, or timed out and
+                // the network has no record of it. Re-sign it with a fresh block hash but the same
+                // nonce, so that at most one of the two transactions can ever be executed.
+                let lost = matches!(
+                    &err,
+                    JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+                        RpcTransactionError::InvalidTransaction {
+                            context: InvalidTxError::Expired,
+                            ..
+                        } | RpcTransactionError::UnknownTransaction { .. },
+                    ))
+                );
+                if tool::is_timeout(&err) {
+                    break Err(ErrorKind::Timeout.full(
+                        "the rpc node did not respond to the transaction in time, so it might \
+                        still get executed",
+                        err,
+                    ));
+                }
+                if !lost || rebroadcasts == MAX_REBROADCASTS {
+                    break Err(RpcErrorCode::BroadcastTxFailure.custom(err));
+                }
+
+                // Something already got executed with this nonce in the meantime, so the
+                // transaction can no longer be sent again.
+                let (access_key, latest_hash) =
+                    access_key(client, cache_key.0.clone(), cache_key.1.clone()).await?;
+                if access_key.nonce >= nonce {
+                    break Err(RpcErrorCode::BroadcastTxFailure.custom(err));
+                }
+
+                rebroadcasts += 1;
+                client.rebroadcasts.fetch_add(1, Ordering::SeqCst);
+                tracing::warn!(
+                    target: "workspaces",
+                    "Transaction from {} was lost, re-signing and broadcasting it again",
+                    signer.account_id
+                );
+                block_hash = latest_hash;
+            }
+        })
         .await
+}
+
+/// Sign the `actions` as a delegate action from `signer` to `receiver_id`, to be relayed to
+/// the network by another account. The delegate action is valid for the next `valid_for`
+/// blocks.
+pub(crate) async fn sign_delegate_action(
+    client: &Client,
+    signer: &InMemorySigner,
+    receiver_id: &AccountId,
+    actions: Vec<Action>,
+    valid_for: BlockHeight,
+) -> Result<SignedDelegateAction> {
+    client.check_signer(&signer.account_id)?;
+    let actions = actions
+        .into_iter()
+        .map(NonDelegateAction::try_from)
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| ErrorKind::DataConversion.custom(e))?;
+
+    let cache_key = (signer.account_id.clone(), signer.public_key().into());
+    let (_, nonce) = fetch_tx_nonce(client, &cache_key).await?;
+    let block = client.view_block(Some(Finality::Final.into())).await?;
+
+    let delegate_action = DelegateAction {
+        sender_id: signer.account_id.clone(),
+        receiver_id: receiver_id.clone(),
+        actions,
+        nonce,
+        max_block_height: block.header.height + valid_for,
+        public_key: cache_key.1,
+    };
+    let signature = signer.sign_delegate_action(&delegate_action).await?;
+
+    Ok(SignedDelegateAction {
+        delegate_action,
+        signature,
     })
-    .await
 }
 
 pub(crate) async fn send_batch_tx_async_and_retry(
@@ -592,34 +951,37 @@ pub(crate) async fn send_batch_tx_async_and_retry(
     receiver_id: &AccountId,
     actions: Vec<Action>,
 ) -> Result<TransactionStatus> {
-    let inner = signer.inner();
-    let cache_key = (
-        signer.account_id.clone(),
-        signer.secret_key.public_key().into(),
-    );
-    retry(|| async {
-        let (block_hash, nonce) = fetch_tx_nonce(worker.client(), &cache_key).await?;
-        let hash = worker
-            .client()
-            .query(&methods::broadcast_tx_async::RpcBroadcastTxAsyncRequest {
-                signed_transaction: SignedTransaction::from_actions(
+    let cache_key = (signer.account_id.clone(), signer.public_key().into());
+    let client = worker.client();
+    client.check_signer(&signer.account_id)?;
+    client.record_accounts(&[&signer.account_id, receiver_id]);
+    client
+        .retry_policy
+        .retry(|| async {
+            let (block_hash, nonce) = fetch_tx_nonce(client, &cache_key).await?;
+            let signed_transaction = signer
+                .sign_transaction(Transaction::V0(TransactionV0 {
                     nonce,
-                    signer.account_id.clone(),
-                    receiver_id.clone(),
-                    &inner,
-                    actions.clone(),
+                    signer_id: signer.account_id.clone(),
+                    public_key: cache_key.1.clone(),
+                    receiver_id: receiver_id.clone(),
                     block_hash,
-                    DEFAULT_PRIORITY_FEE,
-                ),
-            })
-            .await
-            .map_err(|e| RpcErrorCode::BroadcastTxFailure.custom(e))?;
-
-        Ok(TransactionStatus::new(
-            worker.clone(),
-            signer.account_id.clone(),
-            hash,
-        ))
-    })
-    .await
+                    actions: actions.clone(),
+                }))
+                .await?;
+            let hash = worker
+                .client()
+                .query(&methods::broadcast_tx_async::RpcBroadcastTxAsyncRequest {
+                    signed_transaction,
+                })
+                .await
+                .map_err(|e| RpcErrorCode::BroadcastTxFailure.custom(e))?;
+
+            Ok(TransactionStatus::new(
+                worker.clone(),
+                signer.account_id.clone(),
+                hash,
+            ))
+        })
+        .await
 }
@@ -0,0 +1,127 @@
+//! A minimal JSON-RPC over HTTP server running in-process on localhost. This is used by
+//! networks that want to serve or intercept RPC requests themselves while still going
+//! through the same [`Client`] that talks to a real RPC node.
+//!
+//! [`Client`]: crate::rpc::client::Client
+
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::sync::Arc;
+
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+use crate::error::ErrorKind;
+use crate::result::Result;
+use crate::rpc::BoxFuture;
+
+/// Handles a single JSON-RPC request object, returning the full JSON-RPC response object.
+pub(crate) type Handler = Arc<dyn Fn(Value) -> BoxFuture<'static, Value> + Send + Sync>;
+
+pub(crate) struct LocalServer {
+    addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl LocalServer {
+    /// Bind to an unused port on localhost and start serving requests with `handler`.
+    pub(crate) async fn start(handler: Handler) -> Result<Self> {
+        let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0))
+            .await
+            .map_err(|e| ErrorKind::Io.full("failed to bind local rpc server", e))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| ErrorKind::Io.full("failed to get local rpc server address", e))?;
+
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let handler = handler.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = serve_connection(stream, handler).await {
+                        debug!(target: "workspaces", "local rpc connection closed: {}", err);
+                    }
+                });
+            }
+        });
+
+        Ok(Self { addr, task })
+    }
+
+    /// The address of this server, to be handed to a [`Client`].
+    ///
+    /// [`Client`]: crate::rpc::client::Client
+    pub(crate) fn rpc_addr(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+}
+
+impl Drop for LocalServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Build a JSON-RPC error response for the request `id`.
+pub(crate) fn error_response(id: Value, name: &str, cause: Value) -> Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {
+            "code": -32000,
+            "message": "Server error",
+            "name": name,
+            "cause": cause,
+        },
+    })
+}
+
+/// Serve HTTP/1.1 requests from a single connection until the client closes it. Only the
+/// subset of HTTP the JSON-RPC client makes use of is supported: `POST` requests with a
+/// `Content-Length` delimited body.
+async fn serve_connection(stream: TcpStream, handler: Handler) -> std::io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    loop {
+        let mut content_length = 0;
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(());
+        }
+        loop {
+            line.clear();
+            reader.read_line(&mut line).await?;
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or_default();
+                }
+            }
+        }
+
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).await?;
+        let response = match serde_json::from_slice::<Value>(&body) {
+            Ok(request) => handler(request).await,
+            Err(err) => error_response(
+                Value::Null,
+                "REQUEST_VALIDATION_ERROR",
+                serde_json::json!({ "name": "PARSE_ERROR", "info": { "error_message": err.to_string() } }),
+            ),
+        };
+
+        let body = response.to_string();
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+            body.len()
+        );
+        writer.write_all(head.as_bytes()).await?;
+        writer.write_all(body.as_bytes()).await?;
+        writer.flush().await?;
+    }
+}
@@ -0,0 +1,261 @@
+//! Middleware that JSON-RPC requests pass through on their way to the RPC node. Each
+//! [`Layer`] gets to inspect, modify, delay or answer a request itself, before passing it
+//! on to the rest of the stack with [`Next::run`]. Layers are added to a network through the
+//! `layer` method of the builders returned by functions like [`testnet`], and run in the order
+//! they were added in:
+//! ```
+//! use near_workspaces::rpc::middleware::{Layer, Next};
+//! use near_workspaces::rpc::BoxFuture;
+//! use serde_json::Value;
+//!
+//! /// Logs the method of every request made.
+//! struct LogMethods;
+//!
+//! impl Layer for LogMethods {
+//!     fn call<'a>(&'a self, request: Value, next: Next<'a>) -> BoxFuture<'a, Value> {
+//!         Box::pin(async move {
+//!             println!("calling {}", request["method"]);
+//!             next.run(request).await
+//!         })
+//!     }
+//! }
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let worker = near_workspaces::testnet().layer(LogMethods).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Note that the retries done by workspaces happen before requests enter the stack, so every
+//! retried request passes through the layers again.
+//!
+//! [`testnet`]: crate::testnet
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::rpc::client::HttpOptions;
+use crate::rpc::local::{error_response, Handler};
+use crate::rpc::BoxFuture;
+use crate::Result;
+
+/// A single layer of middleware for JSON-RPC requests. Requests and responses are the full
+/// JSON-RPC request and response objects, such as `{"jsonrpc": "2.0", "id": .., "method": ..,
+/// "params": ..}`.
+pub trait Layer: Send + Sync {
+    /// Handle the JSON-RPC `request`, returning the response to it. Call [`Next::run`] to pass
+    /// the request on to the rest of the stack, or skip doing so to answer it directly.
+    fn call<'a>(&'a self, request: Value, next: Next<'a>) -> BoxFuture<'a, Value>;
+}
+
+/// The rest of the middleware stack that comes after a [`Layer`], ending at the RPC node.
+pub struct Next<'a> {
+    layers: &'a [Arc<dyn Layer>],
+    upstream: &'a Upstream,
+}
+
+impl Next<'_> {
+    /// Pass the request on to the next layer, or to the RPC node if there are no more layers.
+    pub async fn run(self, request: Value) -> Value {
+        match self.layers.split_first() {
+            Some((layer, layers)) => {
+                let next = Next {
+                    layers,
+                    upstream: self.upstream,
+                };
+                layer.call(request, next).await
+            }
+            None => self.upstream.send(request).await,
+        }
+    }
+}
+
+impl std::fmt::Debug for Next<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Next")
+            .field("layers", &self.layers.len())
+            .field(
+                "rpc_urls",
+                &self
+                    .upstream
+                    .endpoints
+                    .iter()
+                    .map(|endpoint| &endpoint.rpc_url)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// Layer simulating the latency of a remote RPC node, by delaying every request by a fixed
+/// amount plus a random jitter. This allows timeout handling and optimistic UI logic to be
+/// tested under realistic conditions, even against a sandbox running on localhost:
+/// ```
+/// use std::time::{Duration, Instant};
+/// use near_workspaces::rpc::middleware::Latency;
+///
+/// # async fn run() -> anyhow::Result<()> {
+/// let latency = Latency::new(Duration::from_millis(150)).jitter(Duration::from_millis(50));
+/// let worker = near_workspaces::sandbox().layer(latency).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Latency {
+    delay: Duration,
+    jitter: Duration,
+}
+
+impl Latency {
+    /// Delay every request by `delay`.
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            jitter: Duration::ZERO,
+        }
+    }
+
+    /// Additionally delay every request by a random amount of up to `jitter`.
+    pub fn jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+}
+
+impl Layer for Latency {
+    fn call<'a>(&'a self, request: Value, next: Next<'a>) -> BoxFuture<'a, Value> {
+        Box::pin(async move {
+            let jitter = self.jitter.mul_f64(rand::random::<f64>());
+            tokio::time::sleep(self.delay + jitter).await;
+            next.run(request).await
+        })
+    }
+}
+
+/// How long an RPC node that failed a request is considered unhealthy for.
+const UNHEALTHY_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// An RPC node along with its health, as seen by the requests sent to it.
+struct Endpoint {
+    rpc_url: String,
+    /// Until when this node is considered unhealthy, after failing a request.
+    unhealthy_until: std::sync::Mutex<Option<Instant>>,
+}
+
+impl Endpoint {
+    fn is_healthy(&self) -> bool {
+        self.unhealthy_until
+            .lock()
+            .map(|until| until.is_none_or(|until| until <= Instant::now()))
+            .unwrap_or(true)
+    }
+
+    fn mark(&self, healthy: bool) {
+        if let Ok(mut until) = self.unhealthy_until.lock() {
+            *until = (!healthy).then(|| Instant::now() + UNHEALTHY_COOLDOWN);
+        }
+    }
+}
+
+/// The RPC nodes at the bottom of the stack. Requests go to the first healthy node, failing
+/// over to the next ones when a node can't be reached, times out or fails internally. Nodes
+/// that failed get skipped for a while, in favor of the other ones.
+struct Upstream {
+    http: reqwest::Client,
+    endpoints: Vec<Endpoint>,
+    api_key: Option<String>,
+}
+
+impl Upstream {
+    async fn send(&self, request: Value) -> Value {
+        // Healthy nodes come first, while unhealthy ones are still tried as a last resort.
+        let (healthy, unhealthy): (Vec<_>, Vec<_>) = self
+            .endpoints
+            .iter()
+            .partition(|endpoint| endpoint.is_healthy());
+
+        let mut response = Value::Null;
+        for endpoint in healthy.into_iter().chain(unhealthy) {
+            response = self.send_to(endpoint, &request).await;
+            let failed = matches!(
+                response["error"]["name"].as_str(),
+                Some("INTERNAL_ERROR" | "TIMEOUT_ERROR")
+            );
+            endpoint.mark(!failed);
+            if !failed {
+                break;
+            }
+            if self.endpoints.len() > 1 {
+                tracing::warn!(
+                    target: "workspaces",
+                    "RPC node {} failed, failing over to the next one: {}",
+                    endpoint.rpc_url,
+                    response["error"]
+                );
+            }
+        }
+        response
+    }
+
+    async fn send_to(&self, endpoint: &Endpoint, request: &Value) -> Value {
+        let id = request.get("id").cloned().unwrap_or_default();
+        let mut upstream = self.http.post(&endpoint.rpc_url).json(request);
+        if let Some(api_key) = &self.api_key {
+            upstream = upstream.header("x-api-key", api_key);
+        }
+
+        let response = match upstream.send().await {
+            Ok(resp) => resp.json::<Value>().await,
+            Err(err) => Err(err),
+        };
+        response.unwrap_or_else(|err| {
+            let err = if err.is_timeout() {
+                "request to the rpc node timed out".to_string()
+            } else {
+                err.to_string()
+            };
+            error_response(
+                id,
+                "INTERNAL_ERROR",
+                serde_json::json!({
+                    "name": "INTERNAL_ERROR",
+                    "info": { "error_message": err },
+                }),
+            )
+        })
+    }
+}
+
+/// Handler running requests through `layers` before sending them to the first healthy RPC
+/// node out of `rpc_urls`.
+pub(crate) fn stack(
+    layers: Vec<Arc<dyn Layer>>,
+    rpc_urls: &[String],
+    api_key: Option<String>,
+    http: &HttpOptions,
+) -> Result<Handler> {
+    let stack = Arc::new((
+        layers,
+        Upstream {
+            http: http.http_client()?,
+            endpoints: rpc_urls
+                .iter()
+                .map(|rpc_url| Endpoint {
+                    rpc_url: rpc_url.clone(),
+                    unhealthy_until: Default::default(),
+                })
+                .collect(),
+            api_key,
+        },
+    ));
+
+    Ok(Arc::new(move |request: Value| {
+        let stack = stack.clone();
+        Box::pin(async move {
+            let (layers, upstream) = &*stack;
+            Next { layers, upstream }.run(request).await
+        })
+    }))
+}
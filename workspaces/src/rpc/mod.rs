@@ -1,7 +1,11 @@
+pub(crate) mod cassette;
 pub(crate) mod client;
+pub(crate) mod local;
 pub(crate) mod tool;
 
+pub mod middleware;
 pub mod patch;
 pub mod query;
+pub mod retry;
 
 pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
@@ -1,12 +1,20 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use near_jsonrpc_client::errors::{JsonRpcError, JsonRpcServerError};
+use near_jsonrpc_client::methods;
 use near_jsonrpc_client::methods::sandbox_patch_state::RpcSandboxPatchStateRequest;
+use near_jsonrpc_primitives::types::blocks::RpcBlockError;
+use near_jsonrpc_primitives::types::query::{QueryResponseKind, RpcQueryError};
 use near_primitives::state_record::StateRecord;
 use near_primitives::types::{BlockId, BlockReference};
+use near_primitives::views::{QueryRequest, StateChangeKindView};
 use near_token::NearToken;
 
-use crate::error::SandboxErrorCode;
+use crate::error::{ErrorKind, RpcErrorCode, SandboxErrorCode};
 use crate::network::{Sandbox, DEV_ACCOUNT_SEED};
 use crate::types::account::AccountDetails;
-use crate::types::{BlockHeight, KeyType, PublicKey, SecretKey};
+use crate::types::{BlockHeight, PublicKey, SecretKey};
 use crate::{AccessKey, AccountDetailsPatch, Result};
 use crate::{AccountId, Contract, CryptoHash, InMemorySigner, Network, Worker};
 
@@ -102,7 +110,7 @@ impl<'a> ImportContractTransaction<'a> {
         let from_account_id = self.account_id;
         let into_account_id = self.into_account_id.as_ref().unwrap_or(from_account_id);
 
-        let sk = SecretKey::from_seed(KeyType::ED25519, DEV_ACCOUNT_SEED);
+        let sk = SecretKey::from_seed(self.into_network.dev_key_type, DEV_ACCOUNT_SEED);
         let pk = sk.public_key();
         let signer = InMemorySigner::from_secret_key(into_account_id.clone(), sk);
         let block_ref = self.block_ref.unwrap_or_else(BlockReference::latest);
@@ -110,7 +118,7 @@ impl<'a> ImportContractTransaction<'a> {
         let mut account_view = self
             .from_network
             .view_account(from_account_id)
-            .block_reference(block_ref.clone())
+            .at_block(block_ref.clone())
             .await?;
 
         let code_hash = account_view.code_hash;
@@ -126,7 +134,7 @@ impl<'a> ImportContractTransaction<'a> {
             let code = self
                 .from_network
                 .view_code(from_account_id)
-                .block_reference(block_ref.clone())
+                .at_block(block_ref.clone())
                 .await?;
             patch = patch.code(&code);
         }
@@ -135,7 +143,7 @@ impl<'a> ImportContractTransaction<'a> {
             let states = self
                 .from_network
                 .view_state(from_account_id)
-                .block_reference(block_ref)
+                .at_block(block_ref)
                 .await?;
 
             patch = patch.states(
@@ -150,6 +158,112 @@ impl<'a> ImportContractTransaction<'a> {
     }
 }
 
+/// A [`Transaction`]-like object for importing many contracts at once from a network into
+/// sandbox, such as the dozens of related accounts a protocol's live deployment consists of.
+/// The contracts are imported concurrently, each one like an [`ImportContractTransaction`].
+///
+/// [`Transaction`]: crate::operations::Transaction
+pub struct ImportContractsTransaction {
+    account_ids: Vec<AccountId>,
+    from_network: Worker<dyn Network>,
+    into_network: Worker<Sandbox>,
+    import_data: bool,
+    initial_balance: Option<NearToken>,
+    block_ref: Option<BlockReference>,
+}
+
+impl ImportContractsTransaction {
+    pub(crate) fn new(
+        account_ids: Vec<AccountId>,
+        from_network: Worker<dyn Network>,
+        into_network: Worker<Sandbox>,
+    ) -> Self {
+        Self {
+            account_ids,
+            from_network,
+            into_network,
+            import_data: false,
+            initial_balance: None,
+            block_ref: None,
+        }
+    }
+
+    /// Specify at which block height to import the contracts from. Look at
+    /// [`ImportContractTransaction::block_height`] for more info.
+    pub fn block_height(mut self, block_height: BlockHeight) -> Self {
+        self.block_ref = Some(BlockId::Height(block_height).into());
+        self
+    }
+
+    /// Specify at which block hash to import the contracts from. Look at
+    /// [`ImportContractTransaction::block_hash`] for more info.
+    pub fn block_hash(mut self, block_hash: CryptoHash) -> Self {
+        self.block_ref =
+            Some(BlockId::Hash(near_primitives::hash::CryptoHash(block_hash.0)).into());
+        self
+    }
+
+    /// Along with importing the contract code, import the state of every contract as well.
+    /// Look at [`ImportContractTransaction::with_data`] for the limits of doing so.
+    pub fn with_data(mut self) -> Self {
+        self.import_data = true;
+        self
+    }
+
+    /// Specifies the balance of every contract. This will override the balances currently
+    /// on the network this transaction is importing from.
+    pub fn initial_balance(mut self, initial_balance: NearToken) -> Self {
+        self.initial_balance = Some(initial_balance);
+        self
+    }
+
+    /// Process the transaction, and return the imported contracts in the same order as the
+    /// account IDs they were imported from. Errors out if importing any of them fails.
+    pub async fn transact(self) -> Result<Vec<Contract>> {
+        // Pin the block to import from, so every contract is imported from the same state
+        // of the network.
+        let block_ref = match self.block_ref {
+            Some(block_ref) => block_ref,
+            None => {
+                let block = self.from_network.view_block().await?;
+                BlockId::Hash(near_primitives::hash::CryptoHash(block.hash().0)).into()
+            }
+        };
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for (i, account_id) in self.account_ids.into_iter().enumerate() {
+            let from_network = self.from_network.clone();
+            let into_network = self.into_network.clone();
+            let block_ref = block_ref.clone();
+            let import_data = self.import_data;
+            let initial_balance = self.initial_balance;
+
+            tasks.spawn(async move {
+                let mut import =
+                    ImportContractTransaction::new(&account_id, from_network, into_network);
+                import.block_ref = Some(block_ref);
+                import.import_data = import_data;
+                import.initial_balance = initial_balance;
+                import.transact().await.map(|contract| (i, contract))
+            });
+        }
+
+        let mut contracts = Vec::with_capacity(tasks.len());
+        while let Some(imported) = tasks.join_next().await {
+            let imported = imported.map_err(|e| {
+                SandboxErrorCode::PatchStateFailure.full("failed to import contract", e)
+            })?;
+            contracts.push(imported?);
+        }
+        contracts.sort_by_key(|(i, _)| *i);
+
+        Ok(contracts
+            .into_iter()
+            .map(|(_, contract)| contract)
+            .collect())
+    }
+}
+
 /// Internal enum for determining whether to update the account on chain
 /// or to patch an entire account.
 enum AccountUpdate {
@@ -277,7 +391,14 @@ impl PatchTransaction {
     }
 
     /// Perform the state patch transaction into the sandbox network.
-    pub async fn transact(mut self) -> Result<()> {
+    pub async fn transact(self) -> Result<()> {
+        let worker = self.worker.clone();
+        let records = self.into_records().await?;
+        patch_records(&worker, records).await
+    }
+
+    /// Resolve the patches into the records to be sent to sandbox.
+    async fn into_records(mut self) -> Result<Vec<StateRecord>> {
         // NOTE: updating the account is done here because we need to fetch the current
         // account details from the chain. This is an async operation so it is deferred
         // till the transact function.
@@ -324,19 +445,249 @@ impl PatchTransaction {
             self.records
         };
 
-        self.worker
-            .client()
-            .query(&RpcSandboxPatchStateRequest {
-                records: records.clone(),
-            })
-            .await
-            .map_err(|err| SandboxErrorCode::PatchStateFailure.custom(err))?;
-
-        self.worker
-            .client()
-            .query(&RpcSandboxPatchStateRequest { records })
-            .await
-            .map_err(|err| SandboxErrorCode::PatchStateFailure.custom(err))?;
-        Ok(())
+        Ok(records)
+    }
+}
+
+/// A [`Transaction`]-like object for patching the state of many accounts in sandbox with a
+/// single request. This is much faster than patching each account on its own when there
+/// are a lot of them, such as when setting up thousands of storage entries.
+///
+/// [`Transaction`]: crate::operations::Transaction
+pub struct BatchPatchTransaction {
+    worker: Worker<Sandbox>,
+    patches: Vec<PatchTransaction>,
+}
+
+impl BatchPatchTransaction {
+    pub(crate) fn new(worker: &Worker<Sandbox>) -> Self {
+        Self {
+            worker: worker.clone(),
+            patches: vec![],
+        }
+    }
+
+    /// Add the patches of a single account, created with [`Worker::patch`], to this batch.
+    pub fn patch(mut self, patch: PatchTransaction) -> Self {
+        self.patches.push(patch);
+        self
+    }
+
+    /// Patch a single key of the state of `account_id`. Look at [`PatchTransaction::state`]
+    /// for more info.
+    pub fn state(mut self, account_id: &AccountId, key: &[u8], value: &[u8]) -> Self {
+        let patch = match self
+            .patches
+            .iter()
+            .position(|patch| &patch.account_id == account_id)
+        {
+            Some(i) => self.patches.remove(i),
+            None => PatchTransaction::new(&self.worker, account_id.clone()),
+        };
+        self.patches.push(patch.state(key, value));
+        self
+    }
+
+    /// Send all the patches of this batch to sandbox in one request.
+    pub async fn transact(self) -> Result<()> {
+        let mut records = Vec::new();
+        for patch in self.patches {
+            records.extend(patch.into_records().await?);
+        }
+        patch_records(&self.worker, records).await
+    }
+}
+
+async fn patch_records(worker: &Worker<Sandbox>, records: Vec<StateRecord>) -> Result<()> {
+    worker
+        .client()
+        .query(&RpcSandboxPatchStateRequest {
+            records: records.clone(),
+        })
+        .await
+        .map_err(|err| SandboxErrorCode::PatchStateFailure.custom(err))?;
+
+    worker
+        .client()
+        .query(&RpcSandboxPatchStateRequest { records })
+        .await
+        .map_err(|err| SandboxErrorCode::PatchStateFailure.custom(err))?;
+    Ok(())
+}
+
+/// All the accounts, access keys, contract code and contract state of a sandbox, as the
+/// list of records making up the `records` of a `genesis.json`. Create one with
+/// [`Worker::dump_state`], and seed another sandbox with it with [`Worker::load_state`].
+///
+/// [`Worker::dump_state`]: crate::Worker::dump_state
+/// [`Worker::load_state`]: crate::Worker::load_state
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateDump {
+    records: Vec<StateRecord>,
+}
+
+impl StateDump {
+    /// The IDs of the accounts contained in this dump.
+    pub fn account_ids(&self) -> impl Iterator<Item = &AccountId> {
+        self.records.iter().filter_map(|record| match record {
+            StateRecord::Account { account_id, .. } => Some(account_id),
+            _ => None,
+        })
+    }
+
+    /// Number of records contained in this dump.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether this dump contains no records at all.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Serialize this dump into a JSON array of records.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self.records).map_err(|e| ErrorKind::DataConversion.custom(e))
+    }
+
+    /// Deserialize a dump from a JSON array of records, such as the one produced by
+    /// [`StateDump::to_json`] or the `records` of a `genesis.json`.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let records =
+            serde_json::from_str(json).map_err(|e| ErrorKind::DataConversion.custom(e))?;
+        Ok(Self { records })
+    }
+
+    /// Save this dump as JSON into the file at `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_json()?)
+            .map_err(|e| ErrorKind::Io.full(format!("failed to write state dump to {path:?}"), e))
+    }
+
+    /// Load a dump previously saved with [`StateDump::save`] from the file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            ErrorKind::Io.full(format!("failed to read state dump from {path:?}"), e)
+        })?;
+        Self::from_json(&json)
+    }
+
+    pub(crate) async fn capture(worker: &Worker<Sandbox>) -> Result<Self> {
+        let client = worker.client();
+        let head = client.view_block(None).await?;
+        let block_ref: BlockReference = BlockId::Hash(head.header.hash).into();
+
+        // There's no way to list every account through RPC, so gather the ones in genesis
+        // along with the ones touched by any block since.
+        let mut account_ids: BTreeSet<AccountId> = worker
+            .workspace
+            .genesis_account_ids()?
+            .into_iter()
+            .collect();
+        let mut block = head;
+        loop {
+            let changes = client
+                .query(
+                    methods::EXPERIMENTAL_changes_in_block::RpcStateChangesInBlockRequest {
+                        block_reference: BlockId::Hash(block.header.hash).into(),
+                    },
+                )
+                .await
+                .map_err(|e| RpcErrorCode::QueryFailure.custom(e))?;
+            account_ids.extend(changes.changes.into_iter().map(|change| match change {
+                StateChangeKindView::AccountTouched { account_id }
+                | StateChangeKindView::AccessKeyTouched { account_id }
+                | StateChangeKindView::DataTouched { account_id }
+                | StateChangeKindView::ContractCodeTouched { account_id } => account_id,
+            }));
+
+            if block.header.prev_hash == Default::default() {
+                break;
+            }
+            match client
+                .query(methods::block::RpcBlockRequest {
+                    block_reference: BlockId::Hash(block.header.prev_hash).into(),
+                })
+                .await
+            {
+                Ok(prev) => block = prev,
+                // Blocks that were garbage collected by the node are out of reach.
+                Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+                    RpcBlockError::UnknownBlock { .. },
+                ))) => break,
+                Err(err) => return Err(RpcErrorCode::QueryFailure.custom(err)),
+            }
+        }
+
+        let mut records = Vec::new();
+        for account_id in account_ids {
+            let account = match client
+                .query(methods::query::RpcQueryRequest {
+                    block_reference: block_ref.clone(),
+                    request: QueryRequest::ViewAccount {
+                        account_id: account_id.clone(),
+                    },
+                })
+                .await
+            {
+                Ok(resp) => match resp.kind {
+                    QueryResponseKind::ViewAccount(account) => AccountDetails::from(account),
+                    _ => {
+                        return Err(RpcErrorCode::QueryReturnedInvalidData
+                            .message("while querying account"))
+                    }
+                },
+                // Accounts that got deleted since being touched.
+                Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+                    RpcQueryError::UnknownAccount { .. },
+                ))) => continue,
+                Err(err) => return Err(RpcErrorCode::QueryFailure.custom(err)),
+            };
+            let code_hash = account.code_hash;
+            records.push(StateRecord::Account {
+                account_id: account_id.clone(),
+                account: account.into_near_account(),
+            });
+
+            let keys = worker
+                .view_access_keys(&account_id)
+                .at_block(block_ref.clone())
+                .await?;
+            records.extend(keys.into_iter().map(|key| StateRecord::AccessKey {
+                account_id: account_id.clone(),
+                public_key: key.public_key.into(),
+                access_key: key.access_key.into(),
+            }));
+
+            if code_hash != CryptoHash::default() {
+                let code = worker
+                    .view_code(&account_id)
+                    .at_block(block_ref.clone())
+                    .await?;
+                records.push(StateRecord::Contract {
+                    account_id: account_id.clone(),
+                    code,
+                });
+
+                let state = worker
+                    .view_state(&account_id)
+                    .at_block(block_ref.clone())
+                    .await?;
+                let state: BTreeMap<_, _> = state.into_iter().collect();
+                records.extend(state.into_iter().map(|(key, value)| StateRecord::Data {
+                    account_id: account_id.clone(),
+                    data_key: key.into(),
+                    value: value.into(),
+                }));
+            }
+        }
+
+        Ok(Self { records })
+    }
+
+    pub(crate) async fn restore(&self, worker: &Worker<Sandbox>) -> Result<()> {
+        patch_records(worker, self.records.clone()).await
     }
 }
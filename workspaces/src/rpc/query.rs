@@ -34,23 +34,26 @@
 
 use std::collections::HashMap;
 use std::fmt::{Debug, Display};
+use std::time::{Duration, Instant};
 
 use near_account_id::AccountId;
 use near_jsonrpc_client::methods::query::RpcQueryResponse;
 use near_jsonrpc_client::methods::{self, RpcMethod};
 use near_jsonrpc_primitives::types::chunks::ChunkReference;
 use near_jsonrpc_primitives::types::query::QueryResponseKind;
-use near_primitives::types::{BlockId, BlockReference, StoreKey};
+use near_primitives::types::{BlockId, BlockReference, EpochReference, StoreKey};
 use near_primitives::views::{BlockView, QueryRequest};
 use near_token::NearToken;
 
-use crate::error::RpcErrorCode;
+use crate::error::{ErrorKind, RpcErrorCode};
 use crate::operations::Function;
 use crate::result::ViewResultDetails;
 use crate::rpc::client::Client;
 use crate::rpc::{tool, BoxFuture};
 use crate::types::account::AccountDetails;
-use crate::types::{AccessKey, AccessKeyInfo, BlockHeight, Finality, PublicKey, ShardId};
+use crate::types::{
+    self, AccessKey, AccessKeyInfo, BlockHeight, EpochValidators, Finality, PublicKey, ShardId,
+};
 use crate::{Block, Chunk, CryptoHash, Result};
 
 /// `Query` object allows creating queries into the network of our choice. This object is
@@ -61,6 +64,8 @@ pub struct Query<'a, T> {
     pub(crate) method: T,
     pub(crate) client: &'a Client,
     pub(crate) block_ref: Option<BlockReference>,
+    pub(crate) retries: bool,
+    pub(crate) timeout: Option<Duration>,
 }
 
 impl<'a, T> Query<'a, T> {
@@ -69,9 +74,28 @@ impl<'a, T> Query<'a, T> {
             method,
             client,
             block_ref: None,
+            retries: true,
+            timeout: None,
         }
     }
 
+    /// Fail with an [`ErrorKind::Timeout`] error if the query, including any retries of it,
+    /// does not complete within `timeout`. This is on top of the request timeout of the
+    /// network, if one was specified.
+    ///
+    /// [`ErrorKind::Timeout`]: crate::error::ErrorKind::Timeout
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Fail right away if the query fails, instead of retrying it a couple of times with
+    /// an exponential backoff. This keeps the feedback of tests expecting a failure fast.
+    pub fn no_retry(mut self) -> Self {
+        self.retries = false;
+        self
+    }
+
     /// Specify at which block height to query from. Note that only archival
     /// networks will have the full history while networks like mainnet or testnet will
     /// only have the history from 5 or less epochs ago.
@@ -100,12 +124,38 @@ where
         self
     }
 
-    pub(crate) fn block_reference(mut self, value: BlockReference) -> Self {
+    /// Specify the block to query from, by either its height, its hash or [`Finality`].
+    /// This is equivalent to calling into [`block_height`], [`block_hash`] or
+    /// [`finality`] respectively.
+    ///
+    /// [`block_height`]: Query::block_height
+    /// [`block_hash`]: Query::block_hash
+    /// [`finality`]: Query::finality
+    pub fn block_reference(mut self, value: impl Into<types::BlockReference>) -> Self {
+        self.block_ref = Some(value.into().into());
+        self
+    }
+
+    pub(crate) fn at_block(mut self, value: BlockReference) -> Self {
         self.block_ref = Some(value);
         self
     }
 }
 
+impl Query<'_, ViewBlock> {
+    /// Specify at which block [`Finality`] to view the latest block from.
+    pub fn finality(mut self, value: Finality) -> Self {
+        self.block_ref = Some(value.into());
+        self
+    }
+
+    /// Specify the block to view, by either its height, its hash or [`Finality`].
+    pub fn block_reference(mut self, value: impl Into<types::BlockReference>) -> Self {
+        self.block_ref = Some(value.into().into());
+        self
+    }
+}
+
 impl<'a, T, R> std::future::IntoFuture for Query<'a, T>
 where
     T: ProcessQuery<Output = R> + Send + Sync + 'static,
@@ -122,11 +172,40 @@ where
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async move {
             let block_reference = self.block_ref.unwrap_or_else(BlockReference::latest);
-            let resp = self
-                .client
-                .query(self.method.into_request(block_reference)?)
-                .await
-                .map_err(|e| RpcErrorCode::QueryFailure.custom(e))?;
+            let request = self.method.into_request(block_reference)?;
+            let (client, retries) = (self.client, self.retries);
+            let query = async {
+                if retries {
+                    client.query(request).await
+                } else {
+                    client.query_once(&request).await
+                }
+            };
+            let resp = match self.timeout {
+                Some(timeout) => tokio::time::timeout(timeout, query).await.map_err(|_| {
+                    ErrorKind::Timeout.message(format!("query timed out after {:?}", timeout))
+                })?,
+                None => query.await,
+            }
+            .map_err(|e| {
+                if tool::is_timeout(&e) {
+                    ErrorKind::Timeout.full("the rpc node did not respond to the query in time", e)
+                } else if tool::exceeds_query_limits(&e) {
+                    RpcErrorCode::QueryResultTooLarge.full(
+                        "the result is too large to be viewed at once, consider paginating it \
+                        with `Worker::view_paginated`",
+                        e,
+                    )
+                } else if tool::is_unavailable_shard(&e) {
+                    RpcErrorCode::UnavailableShard.full(
+                        "the rpc node only tracks some of the shards, consider connecting to \
+                        one tracking all of them, such as an archival node, through `rpc_addr`",
+                        e,
+                    )
+                } else {
+                    RpcErrorCode::QueryFailure.custom(e)
+                }
+            })?;
 
             T::from_response(resp)
         })
@@ -176,6 +255,15 @@ pub struct ViewState {
     prefix: Option<Vec<u8>>,
 }
 
+impl ViewState {
+    pub(crate) fn new(id: &AccountId) -> Self {
+        Self {
+            account_id: id.clone(),
+            prefix: None,
+        }
+    }
+}
+
 pub struct ViewAccessKey {
     pub(crate) account_id: AccountId,
     pub(crate) public_key: PublicKey,
@@ -187,6 +275,8 @@ pub struct ViewAccessKeyList {
 
 pub struct GasPrice;
 
+pub struct ViewValidators;
+
 impl ProcessQuery for ViewFunction {
     type Method = methods::query::RpcQueryRequest;
     type Output = ViewResultDetails;
@@ -211,7 +301,7 @@ impl ProcessQuery for ViewFunction {
 }
 
 // Specific builder methods attached to a ViewFunction.
-impl Query<'_, ViewFunction> {
+impl<'a> Query<'a, ViewFunction> {
     /// Provide the arguments for the call. These args are serialized bytes from either
     /// a JSON or Borsh serializable set of arguments. To use the more specific versions
     /// with better quality of life, use `args_json` or `args_borsh`.
@@ -234,6 +324,147 @@ impl Query<'_, ViewFunction> {
         self.method.function = self.method.function.args_borsh(args);
         self
     }
+
+    /// Similar to `args`, specify an argument that gets encoded by `encoder`. Look at
+    /// [`ArgsEncoder`] for more info.
+    ///
+    /// [`ArgsEncoder`]: crate::operations::ArgsEncoder
+    pub fn args_with<E: crate::operations::ArgsEncoder<U>, U>(
+        mut self,
+        encoder: E,
+        args: U,
+    ) -> Self {
+        self.method.function = self.method.function.args_with(encoder, args);
+        self
+    }
+
+    /// Memoize the result of this view call for `ttl`. Look at [`CachedView`] for more info.
+    pub fn cached(self, ttl: Duration) -> CachedView<'a> {
+        CachedView { query: self, ttl }
+    }
+}
+
+/// A view call whose result is memoized for a time to live (TTL), such that repeating the
+/// same call with the same arguments at the same block reference is answered from memory
+/// instead of the network. This keeps helpers that repeatedly view data which rarely changes,
+/// such as token metadata on mainnet or archival nodes, fast and within rate limits.
+///
+/// The cache is shared between all workers of the same network. Usually given from
+/// [`Worker::cached_view`].
+///
+/// [`Worker::cached_view`]: crate::Worker::cached_view
+pub struct CachedView<'a> {
+    query: Query<'a, ViewFunction>,
+    ttl: Duration,
+}
+
+impl CachedView<'_> {
+    /// Provide the arguments for the call. Look at [`Query::args`] for more info.
+    pub fn args(mut self, args: Vec<u8>) -> Self {
+        self.query = self.query.args(args);
+        self
+    }
+
+    /// Provide JSON serializable arguments for the call. Look at [`Query::args_json`] for
+    /// more info.
+    pub fn args_json<U: serde::Serialize>(mut self, args: U) -> Self {
+        self.query = self.query.args_json(args);
+        self
+    }
+
+    /// Provide borsh serializable arguments for the call. Look at [`Query::args_borsh`] for
+    /// more info.
+    pub fn args_borsh<U: near_primitives::borsh::BorshSerialize>(mut self, args: U) -> Self {
+        self.query = self.query.args_borsh(args);
+        self
+    }
+
+    /// Provide arguments for the call encoded by `encoder`. Look at [`Query::args_with`] for
+    /// more info.
+    pub fn args_with<E: crate::operations::ArgsEncoder<U>, U>(
+        mut self,
+        encoder: E,
+        args: U,
+    ) -> Self {
+        self.query = self.query.args_with(encoder, args);
+        self
+    }
+
+    /// Specify at which block height to view from. Look at [`Query::block_height`] for more
+    /// info.
+    pub fn block_height(mut self, height: BlockHeight) -> Self {
+        self.query = self.query.block_height(height);
+        self
+    }
+
+    /// Specify at which block hash to view from. Look at [`Query::block_hash`] for more
+    /// info.
+    pub fn block_hash(mut self, hash: CryptoHash) -> Self {
+        self.query = self.query.block_hash(hash);
+        self
+    }
+
+    /// Specify at which block [`Finality`] to view from.
+    pub fn finality(mut self, value: Finality) -> Self {
+        self.query = self.query.finality(value);
+        self
+    }
+}
+
+impl<'a> std::future::IntoFuture for CachedView<'a> {
+    type Output = Result<ViewResultDetails>;
+    type IntoFuture = BoxFuture<'a, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(async move {
+            let Self { query, ttl } = self;
+            let client = query.client;
+
+            // Arguments that failed to serialize have nothing to cache, so let the query
+            // report the error.
+            let Ok(args) = &query.method.function.args else {
+                return query.await;
+            };
+            let key = ViewCacheKey {
+                account_id: query.method.account_id.clone(),
+                function: query.method.function.name.clone(),
+                args: args.clone(),
+                block_ref: format!("{:?}", query.block_ref),
+            };
+
+            if let Some((cached_at, result)) = client.view_cache.lock()?.get(&key) {
+                if cached_at.elapsed() < ttl {
+                    return Ok(result.clone());
+                }
+            }
+
+            let result = query.await?;
+            client
+                .view_cache
+                .lock()?
+                .insert(key, (Instant::now(), result.clone()));
+            Ok(result)
+        })
+    }
+}
+
+impl Debug for CachedView<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachedView")
+            .field("account_id", &self.query.method.account_id)
+            .field("function", &self.query.method.function.name)
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+/// Identifies a view call in the cache of [`CachedView`]s.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ViewCacheKey {
+    account_id: AccountId,
+    function: String,
+    args: Vec<u8>,
+    block_ref: String,
 }
 
 impl ProcessQuery for ViewCode {
@@ -300,7 +531,7 @@ impl ProcessQuery for ViewState {
             block_reference,
             request: QueryRequest::ViewState {
                 account_id: self.account_id,
-                prefix: StoreKey::from(self.prefix.map(Vec::from).unwrap_or_default()),
+                prefix: StoreKey::from(self.prefix.unwrap_or_default()),
                 include_proof: false,
             },
         })
@@ -314,17 +545,7 @@ impl ProcessQuery for ViewState {
     }
 }
 
-impl<'a> Query<'a, ViewState> {
-    pub(crate) fn view_state(client: &'a Client, id: &AccountId) -> Self {
-        Self::new(
-            client,
-            ViewState {
-                account_id: id.clone(),
-                prefix: None,
-            },
-        )
-    }
-
+impl Query<'_, ViewState> {
     /// Set the prefix for viewing the state.
     pub fn prefix(mut self, value: &[u8]) -> Self {
         self.method.prefix = Some(value.into());
@@ -403,6 +624,32 @@ impl ProcessQuery for GasPrice {
     }
 }
 
+impl ProcessQuery for ViewValidators {
+    type Method = methods::validators::RpcValidatorRequest;
+    type Output = EpochValidators;
+
+    fn into_request(self, block_ref: BlockReference) -> Result<Self::Method> {
+        let epoch_reference = match block_ref {
+            // User provided input via `block_hash` or `block_height` functions.
+            BlockReference::BlockId(block_id) => EpochReference::BlockId(block_id),
+            // default case, set by `Query` struct via BlockReference::latest.
+            BlockReference::Finality(_finality) => EpochReference::Latest,
+            // Should not be reachable, unless code got changed.
+            BlockReference::SyncCheckpoint(point) => {
+                return Err(RpcErrorCode::QueryFailure.message(format!(
+                    "Cannot supply sync checkpoint to validators: {point:?}. Potential API bug?"
+                )))
+            }
+        };
+
+        Ok(Self::Method { epoch_reference })
+    }
+
+    fn from_response(resp: <Self::Method as RpcMethod>::Response) -> Result<Self::Output> {
+        Ok(resp.into())
+    }
+}
+
 /// Query object to query for chunk related details at a specific `ChunkReference` which
 /// consists of either a chunk [`CryptoHash`], or a `BlockShardId`.
 ///
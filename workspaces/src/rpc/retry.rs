@@ -0,0 +1,168 @@
+//! Retrying of RPC requests that failed, such as due to a flaky connection to the node.
+//! Look at [`RetryPolicy`] for how to configure it.
+
+use std::convert::TryFrom;
+use std::fmt::Debug;
+use std::time::Duration;
+
+use tokio_retry::strategy::jitter;
+use tokio_retry::RetryIf;
+
+/// Classes of errors a failed RPC request can be retried on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RetryableError {
+    /// The request did not make it to the node or its response did not make it back, such
+    /// as on connection failures or timeouts.
+    Transport,
+    /// The node failed to process the request on its end, such as on internal errors.
+    Server,
+    /// The node processed the request, but it failed on the handler of the method, such as
+    /// on an unknown account or an invalid transaction.
+    Handler,
+}
+
+impl RetryableError {
+    /// Classify an error by its debug representation, which works for the errors of the
+    /// JSON-RPC client, along with any errors wrapping them.
+    fn classify(err: &impl Debug) -> Self {
+        let err = format!("{:?}", err);
+        if err.contains("TransportError") {
+            Self::Transport
+        } else if err.contains("HandlerError") {
+            Self::Handler
+        } else {
+            Self::Server
+        }
+    }
+}
+
+/// Policy for retrying RPC requests that failed, with the delays in between attempts
+/// growing exponentially. The default policy retries up to 4 times on any error, waiting
+/// for 5, 25, 125 and 625 ms, with some jitter added.
+///
+/// ```
+/// use std::time::Duration;
+/// use near_workspaces::rpc::retry::{RetryPolicy, RetryableError};
+///
+/// # async fn run() -> anyhow::Result<()> {
+/// // Retry aggressively on a flaky connection to testnet:
+/// let worker = near_workspaces::testnet()
+///     .retry_policy(
+///         RetryPolicy::default()
+///             .max_retries(10)
+///             .initial_delay(Duration::from_millis(100))
+///             .multiplier(2)
+///             .max_delay(Duration::from_secs(5))
+///             .retry_on(&[RetryableError::Transport, RetryableError::Server]),
+///     )
+///     .await?;
+///
+/// // Fail fast on a local sandbox:
+/// let worker = near_workspaces::sandbox()
+///     .retry_policy(RetryPolicy::none())
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: usize,
+    initial_delay: Duration,
+    multiplier: u32,
+    max_delay: Duration,
+    jitter: bool,
+    retry_on: Vec<RetryableError>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 4,
+            initial_delay: Duration::from_millis(5),
+            multiplier: 5,
+            max_delay: Duration::MAX,
+            jitter: true,
+            retry_on: vec![
+                RetryableError::Transport,
+                RetryableError::Server,
+                RetryableError::Handler,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that never retries, such that failed requests fail right away.
+    pub fn none() -> Self {
+        Self::default().max_retries(0)
+    }
+
+    /// Maximum number of times to retry a failed request, on top of the first attempt.
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Delay before the first retry.
+    pub fn initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Factor the delay grows by after every retry.
+    pub fn multiplier(mut self, multiplier: u32) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Upper bound on the delay in between retries.
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Whether to randomize the delays, such that many clients failing at once don't end up
+    /// retrying in lockstep.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Classes of errors to retry on, where requests failing with any other error fail
+    /// right away.
+    pub fn retry_on(mut self, errors: &[RetryableError]) -> Self {
+        self.retry_on = errors.to_vec();
+        self
+    }
+
+    /// The delays in between retries, one for every retry.
+    pub fn delays(&self) -> impl Iterator<Item = Duration> + Send + '_ {
+        (0..self.max_retries).map(move |retry| {
+            let delay = u32::try_from(retry)
+                .ok()
+                .and_then(|retry| self.multiplier.checked_pow(retry))
+                .and_then(|factor| self.initial_delay.checked_mul(factor))
+                .unwrap_or(self.max_delay)
+                .min(self.max_delay);
+            if self.jitter {
+                jitter(delay)
+            } else {
+                delay
+            }
+        })
+    }
+
+    /// Run `task`, retrying it according to this policy when it fails.
+    pub(crate) async fn retry<R, E, T, F>(&self, task: F) -> T::Output
+    where
+        F: FnMut() -> T + Send,
+        T: core::future::Future<Output = core::result::Result<R, E>> + Send,
+        E: Debug,
+    {
+        RetryIf::start(self.delays(), task, |err: &E| {
+            self.retry_on.contains(&RetryableError::classify(err))
+        })
+        .await
+    }
+}
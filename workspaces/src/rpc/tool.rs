@@ -1,19 +1,19 @@
 use std::collections::HashMap;
 use std::convert::TryInto;
-use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 
 use chrono::Utc;
 use rand::Rng;
+use sha2::{Digest, Sha256};
 use url::Url;
 
 use near_crypto::SecretKey;
-use near_primitives::views::StateItem;
+use near_primitives::views::{StateChangeCauseView, StateItem};
 
 use crate::error::{ErrorKind, RpcErrorCode};
 use crate::result::Result;
-use crate::types::{AccountId, PublicKey};
+use crate::types::{AccountId, CryptoHash, PublicKey};
 
 /// Convert `StateItem`s over to a Map<data_key, value_bytes> representation.
 /// Assumes key and value are base64 encoded, so this also decodes them.
@@ -24,6 +24,109 @@ pub(crate) fn into_state_map(state_items: Vec<StateItem>) -> HashMap<Vec<u8>, Ve
         .collect()
 }
 
+/// The receipt whose execution caused a state change, if it was caused by one.
+pub(crate) fn receipt_of(cause: &StateChangeCauseView) -> Option<CryptoHash> {
+    match cause {
+        StateChangeCauseView::ActionReceiptProcessingStarted { receipt_hash }
+        | StateChangeCauseView::ActionReceiptGasReward { receipt_hash }
+        | StateChangeCauseView::ReceiptProcessing { receipt_hash }
+        | StateChangeCauseView::PostponedReceipt { receipt_hash } => {
+            Some(CryptoHash(receipt_hash.0))
+        }
+        _ => None,
+    }
+}
+
+/// Errors reported by nodes when the result of a query is rejected due to its size, such
+/// as state too large to be viewed, or a view function returning too much data.
+const QUERY_LIMIT_ERRORS: &[&str] = &[
+    "TooLargeContractState",
+    "TOO_LARGE_CONTRACT_STATE",
+    "ReturnedValueLengthExceeded",
+    "GasLimitExceeded",
+];
+
+/// Whether the query failed due to the size of its result exceeding the limits of the node.
+pub(crate) fn exceeds_query_limits(err: &impl std::fmt::Debug) -> bool {
+    let err = format!("{:?}", err);
+    QUERY_LIMIT_ERRORS.iter().any(|marker| err.contains(marker))
+}
+
+/// Errors reported by nodes that only track some of the shards, when queried for data living
+/// in a shard they do not track.
+const UNAVAILABLE_SHARD_ERRORS: &[&str] = &["UnavailableShard", "UNAVAILABLE_SHARD"];
+
+/// Whether the query failed due to the node not tracking the shard the data lives in.
+pub(crate) fn is_unavailable_shard(err: &impl std::fmt::Debug) -> bool {
+    let err = format!("{:?}", err);
+    UNAVAILABLE_SHARD_ERRORS
+        .iter()
+        .any(|marker| err.contains(marker))
+}
+
+/// Whether the query failed due to the account it is about not existing.
+pub(crate) fn is_unknown_account(err: &impl std::fmt::Debug) -> bool {
+    let err = format!("{:?}", err);
+    err.contains("UnknownAccount") || err.contains("UNKNOWN_ACCOUNT")
+}
+
+/// Errors reported by the HTTP client when the RPC node did not respond in time.
+const TIMEOUT_ERRORS: &[&str] = &["TimedOut", "timed out"];
+
+/// Whether the request failed due to hitting the connect or request timeout.
+pub(crate) fn is_timeout(err: &impl std::fmt::Debug) -> bool {
+    let err = format!("{:?}", err);
+    TIMEOUT_ERRORS.iter().any(|marker| err.contains(marker))
+}
+
+/// Make sure we're running within a tokio runtime, which the HTTP client and the tasks serving
+/// local RPC servers need, instead of panicking deep down in them when there is none.
+pub(crate) fn ensure_tokio_runtime() -> Result<()> {
+    tokio::runtime::Handle::try_current()
+        .map(|_| ())
+        .map_err(|e| {
+            ErrorKind::Other.full(
+                "workspaces needs to run within a tokio runtime, such as the one of \
+                `#[tokio::test]`; when using another executor, enter one with \
+                `tokio::runtime::Runtime::enter` first",
+                e,
+            )
+        })
+}
+
+/// Replace the account ids in `text` with their labels. Ids are only replaced when they stand
+/// on their own or are followed by a dot, such as in `market.near.buy` or at the end of a
+/// sentence, so labeling `a.near` leaves `aa.near` and `sub.a.near` untouched.
+pub(crate) fn relabel(text: &str, labels: &HashMap<AccountId, String>) -> String {
+    let is_id_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_');
+
+    let mut relabeled = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(is_id_char) {
+        relabeled.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find(|c: char| !is_id_char(c)).unwrap_or(rest.len());
+        let word = &rest[..end];
+        let labeled = labels
+            .iter()
+            .filter(|(account_id, _)| {
+                word.strip_prefix(account_id.as_str())
+                    .is_some_and(|suffix| suffix.is_empty() || suffix.starts_with('.'))
+            })
+            .max_by_key(|(account_id, _)| account_id.len());
+        match labeled {
+            Some((account_id, label)) => {
+                relabeled.push_str(label);
+                relabeled.push_str(&word[account_id.len()..]);
+            }
+            None => relabeled.push_str(word),
+        }
+        rest = &rest[end..];
+    }
+    relabeled.push_str(rest);
+    relabeled
+}
+
 pub(crate) fn random_account_id() -> AccountId {
     let mut rng = rand::thread_rng();
     let random_num = rng.gen_range(10000000000000usize..99999999999999);
@@ -81,7 +184,7 @@ pub(crate) async fn url_create_account(
             if value == b"false" {
                 return Err(ErrorKind::Other.message(format!(
                     "The new account <{}> could not be created successfully.",
-                    &account_id
+                    account_id
                 )));
             }
         }
@@ -94,10 +197,48 @@ pub(crate) async fn url_create_account(
     Ok(())
 }
 
+/// Gateway used to download `ipfs://` URLs from.
+const IPFS_GATEWAY: &str = "https://ipfs.io/ipfs/";
+
+/// Download the WASM code at `url`, making sure that it matches the hex encoded `sha256`
+/// checksum. `ipfs://<cid>` URLs are downloaded through a public IPFS gateway.
+pub(crate) async fn download_wasm(url: &str, sha256: &str) -> Result<Vec<u8>> {
+    let url = match url.strip_prefix("ipfs://") {
+        Some(cid) => format!("{}{}", IPFS_GATEWAY, cid),
+        None => url.to_string(),
+    };
+
+    let response = reqwest::get(&url)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| ErrorKind::Io.full(format!("failed to download {}", url), err))?;
+    let wasm = response
+        .bytes()
+        .await
+        .map_err(|err| ErrorKind::Io.full(format!("failed to download {}", url), err))?;
+
+    let checksum = format!("{:x}", Sha256::digest(&wasm));
+    if !checksum.eq_ignore_ascii_case(sha256) {
+        return Err(ErrorKind::DataConversion.message(format!(
+            "checksum of {} is {}, while {} was expected",
+            url, checksum, sha256
+        )));
+    }
+
+    Ok(wasm.to_vec())
+}
+
+/// Write the credentials of `id` to the file at `path`. The file is written atomically by
+/// going through a temporary file in the same directory, such that concurrent writers, like
+/// parallel test processes sharing a keystore, never leave a partially written file behind.
 pub(crate) fn write_cred_to_file(path: &Path, id: &AccountId, sk: &SecretKey) -> Result<()> {
-    let mut file = File::create(path).map_err(|err| {
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let mut file = tempfile::NamedTempFile::new_in(dir).map_err(|err| {
         ErrorKind::Io.full(
-            format!("failed to open {path:?} for writing credentials"),
+            format!("failed to create a temporary file in {dir:?} for writing credentials"),
             err,
         )
     })?;
@@ -106,6 +247,7 @@ pub(crate) fn write_cred_to_file(path: &Path, id: &AccountId, sk: &SecretKey) ->
     {
         use std::os::unix::prelude::PermissionsExt;
         let mut perm = file
+            .as_file()
             .metadata()
             .map_err(|err| ErrorKind::Io.full("Failed to retrieve key file metadata.", err))?
             .permissions();
@@ -115,7 +257,8 @@ pub(crate) fn write_cred_to_file(path: &Path, id: &AccountId, sk: &SecretKey) ->
         #[cfg(not(target_os = "macos"))]
         perm.set_mode(libc::S_IWUSR | libc::S_IRUSR);
 
-        file.set_permissions(perm)
+        file.as_file()
+            .set_permissions(perm)
             .map_err(|err| ErrorKind::Io.full("Failed to set permissions for a key file.", err))?;
     }
 
@@ -128,5 +271,13 @@ pub(crate) fn write_cred_to_file(path: &Path, id: &AccountId, sk: &SecretKey) ->
     .into_bytes();
 
     file.write_all(&content)
-        .map_err(|err| ErrorKind::Io.full("Failed to write a key file", err))
+        .map_err(|err| ErrorKind::Io.full("Failed to write a key file", err))?;
+    file.persist(path).map_err(|err| {
+        ErrorKind::Io.full(
+            format!("failed to move credentials into place at {path:?}"),
+            err.error,
+        )
+    })?;
+
+    Ok(())
 }
@@ -9,12 +9,20 @@ use crate::error::ErrorKind;
 use crate::rpc::query::{
     Query, ViewAccessKey, ViewAccessKeyList, ViewAccount, ViewCode, ViewFunction, ViewState,
 };
-use crate::types::{AccountId, InMemorySigner, NearToken, PublicKey, SecretKey};
+use crate::types::{
+    AccountId, InMemorySigner, NearToken, PublicKey, SecretKey, SignedDelegateAction, SignerKey,
+};
 use crate::{BlockHeight, CryptoHash, Network, Worker};
 
-use crate::operations::{CallTransaction, CreateAccountTransaction, Transaction};
+use crate::operations::{
+    CallTransaction, ChunkedCallTransaction, CreateAccountTransaction, Transaction,
+    UpgradeTransaction,
+};
 use crate::result::{Execution, ExecutionFinalResult, Result};
 
+/// Number of blocks the delegate actions of [`Account::sponsor`] stay valid for.
+const SPONSORED_VALID_FOR: BlockHeight = 100;
+
 /// `Account` is directly associated to an account in the network provided by the
 /// [`Worker`] that creates it.
 ///
@@ -35,7 +43,8 @@ impl fmt::Debug for Account {
 }
 
 impl Account {
-    /// Create a new account with the given path to the credentials JSON file
+    /// Create a new account with the given path to the credentials JSON file, such as the
+    /// ones written by near-cli.
     pub fn from_file(
         path: impl AsRef<Path>,
         worker: &Worker<impl Network + 'static>,
@@ -44,6 +53,16 @@ impl Account {
         Ok(Self::new(signer, worker.clone().coerce()))
     }
 
+    /// Create a new account out of the credentials of `id` kept by near-cli in the
+    /// `~/.near-credentials` directory, as in [`InMemorySigner::from_credentials_dir`].
+    pub fn from_credentials_dir(
+        id: &AccountId,
+        worker: &Worker<impl Network + 'static>,
+    ) -> Result<Self> {
+        let signer = InMemorySigner::from_credentials_dir(id)?;
+        Ok(Self::new(signer, worker.clone().coerce()))
+    }
+
     /// Create an [`Account`] object from an [`AccountId`] and [`SecretKey`].
     pub fn from_secret_key(
         id: AccountId,
@@ -56,10 +75,34 @@ impl Account {
         }
     }
 
+    /// Create an [`Account`] object signing with the key at `hd_path` on the Ledger device
+    /// plugged in, as in [`InMemorySigner::from_ledger`].
+    #[cfg(feature = "ledger")]
+    pub fn from_ledger(
+        id: AccountId,
+        hd_path: &str,
+        worker: &Worker<impl Network + 'static>,
+    ) -> Result<Self> {
+        let signer = InMemorySigner::from_ledger(id, hd_path)?;
+        Ok(Self::new(signer, worker.clone().coerce()))
+    }
+
+    /// Create an [`Account`] object for the implicit account controlled by `sk`, with its ID
+    /// derived from the public key as in [`PublicKey::implicit_account_id`]. The account only
+    /// exists on chain once it gets funded, such as with [`Account::fund_implicit_account`].
+    pub fn implicit_from(sk: SecretKey, worker: &Worker<impl Network + 'static>) -> Result<Self> {
+        let id = sk.public_key().implicit_account_id()?;
+        Ok(Self::from_secret_key(id, sk, worker))
+    }
+
     pub(crate) fn new(signer: InMemorySigner, worker: Worker<dyn Network>) -> Self {
         Self { signer, worker }
     }
 
+    pub(crate) fn worker(&self) -> &Worker<dyn Network> {
+        &self.worker
+    }
+
     /// Grab the current account identifier
     pub fn id(&self) -> &AccountId {
         &self.signer.account_id
@@ -84,6 +127,25 @@ impl Account {
         )
     }
 
+    /// Send `payload` to the method `function` of the contract `contract_id`, split across as
+    /// many calls as it takes to fit within the limits of a transaction. Returns a
+    /// [`ChunkedCallTransaction`] object that we can make use of to configure the chunks
+    /// before sending them with [`ChunkedCallTransaction::transact`].
+    pub fn chunked_call(
+        &self,
+        contract_id: &AccountId,
+        function: &str,
+        payload: &[u8],
+    ) -> ChunkedCallTransaction {
+        ChunkedCallTransaction::new(
+            self.worker.clone(),
+            self.signer.clone(),
+            contract_id.clone(),
+            function,
+            payload.to_vec(),
+        )
+    }
+
     /// View call to a specified contract function. Returns a result which can
     /// be deserialized into borsh or JSON.
     pub fn view(&self, contract_id: &AccountId, function: &str) -> Query<'_, ViewFunction> {
@@ -102,6 +164,52 @@ impl Account {
             .await
     }
 
+    /// Create the implicit account controlled by `sk` by transferring `amount` to it, as wallets
+    /// do when onboarding users, and return it as an [`Account`] ready to sign transactions:
+    ///
+    /// ```
+    /// use near_workspaces::types::{KeyType, NearToken, SecretKey};
+    ///
+    /// # async fn run(funder: near_workspaces::Account) -> anyhow::Result<()> {
+    /// let sk = SecretKey::from_random(KeyType::ED25519);
+    /// let user = funder
+    ///     .fund_implicit_account(sk, NearToken::from_near(1))
+    ///     .await?
+    ///     .into_result()?;
+    /// assert_eq!(user.id().as_str().len(), 64);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fund_implicit_account(
+        &self,
+        sk: SecretKey,
+        amount: NearToken,
+    ) -> Result<Execution<Account>> {
+        let id = sk.public_key().implicit_account_id()?;
+        let details = self.transfer_near(&id, amount).await?;
+        self.worker.client().declare_signer(&id);
+
+        Ok(Execution {
+            result: Self::new(InMemorySigner::from_secret_key(id, sk), self.worker.clone()),
+            details,
+        })
+    }
+
+    /// Stake `amount` of this account's tokens with `public_key` as its validator key, which
+    /// makes the account a validator candidate from the next epoch onwards. Staking a lower
+    /// amount than currently staked unstakes the difference, while staking zero unstakes
+    /// everything. Returns the execution details of this transaction.
+    pub async fn stake(
+        &self,
+        public_key: PublicKey,
+        amount: NearToken,
+    ) -> Result<ExecutionFinalResult> {
+        self.batch(self.id())
+            .stake(amount, public_key)
+            .transact()
+            .await
+    }
+
     /// Deletes the current account, and returns the execution details of this
     /// transaction. The beneficiary will receive the funds of the account deleted
     pub async fn delete_account(self, beneficiary_id: &AccountId) -> Result<ExecutionFinalResult> {
@@ -139,6 +247,31 @@ impl Account {
         )
     }
 
+    /// Upgrade the contract `contract_id` to `code` by staging the code in its state and then
+    /// applying it, as done by DAOs and other self-upgrading contracts. Look at
+    /// [`UpgradeTransaction`] for the methods getting called, which can be configured:
+    ///
+    /// ```
+    /// # async fn run(council: near_workspaces::Account, dao: near_workspaces::Contract, new_code: Vec<u8>) -> anyhow::Result<()> {
+    /// council
+    ///     .stage_upgrade(dao.id(), &new_code)
+    ///     .stage_method("store_blob")
+    ///     .apply_method("upgrade_self")
+    ///     .transact()
+    ///     .await?
+    ///     .into_result()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stage_upgrade(&self, contract_id: &AccountId, code: &[u8]) -> UpgradeTransaction {
+        UpgradeTransaction::new(
+            self.worker.clone(),
+            self.signer().clone(),
+            contract_id.clone(),
+            code.to_vec(),
+        )
+    }
+
     /// Create a new sub account. Returns a [`CreateAccountTransaction`] object
     /// that we can make use of to fill out the rest of the details. The subaccount
     /// id will be in the form of: "{new_account_id}.{parent_account_id}"
@@ -162,10 +295,15 @@ impl Account {
             .client()
             .deploy(&self.signer, self.id(), wasm.into())
             .await?;
+        let details = ExecutionFinalResult::from_view(outcome);
+
+        for callback in self.worker.tx_callbacks.iter() {
+            callback(&details)?;
+        }
 
         Ok(Execution {
             result: Contract::new(self.signer().clone(), self.worker.clone()),
-            details: ExecutionFinalResult::from_view(outcome),
+            details,
         })
     }
 
@@ -182,23 +320,71 @@ impl Account {
         )
     }
 
+    /// Relay a [`SignedDelegateAction`] signed by another account to the network, paying for
+    /// the gas of executing its actions on behalf of that account. Look at
+    /// [`Transaction::delegate`] for how to create one.
+    pub async fn relay(&self, action: SignedDelegateAction) -> Result<ExecutionFinalResult> {
+        let sender_id = action.delegate_action.sender_id.clone();
+        self.batch(&sender_id)
+            .signed_delegate(action)
+            .transact()
+            .await
+    }
+
+    /// Execute `tx` on behalf of the account that built it, with this account paying for the
+    /// gas, by having it signed as a delegate action and relaying it right away. This makes
+    /// gasless flows a one-liner:
+    ///
+    /// ```
+    /// # async fn run(relayer: near_workspaces::Account, alice: near_workspaces::Account, contract: near_workspaces::Contract) -> anyhow::Result<()> {
+    /// let outcome = relayer
+    ///     .sponsor(
+    ///         alice
+    ///             .call(contract.id(), "set_status")
+    ///             .args_json(serde_json::json!({ "message": "hello" })),
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Both [`CallTransaction`]s and [`Transaction`]s can be sponsored, with the delegate
+    /// action staying valid for the next 100 blocks. Use [`Transaction::delegate`] along with
+    /// [`Account::relay`] for more control.
+    pub async fn sponsor(&self, tx: impl Into<Transaction>) -> Result<ExecutionFinalResult> {
+        let action = tx.into().delegate(SPONSORED_VALID_FOR).await?;
+        self.relay(action).await
+    }
+
     /// Store the credentials of this account locally in the directory provided.
     pub async fn store_credentials(&self, save_dir: impl AsRef<Path> + Send) -> Result<()> {
         let savepath = save_dir.as_ref();
         std::fs::create_dir_all(&save_dir).map_err(|e| ErrorKind::Io.custom(e))?;
         let savepath = savepath.join(format!("{}.json", self.id()));
-        crate::rpc::tool::write_cred_to_file(&savepath, self.id(), &self.secret_key().0)
+        match &self.signer.key {
+            SignerKey::Secret(secret_key) => {
+                crate::rpc::tool::write_cred_to_file(&savepath, self.id(), &secret_key.0)
+            }
+            #[cfg(feature = "ledger")]
+            SignerKey::Ledger(_) => Err(ErrorKind::Io.message(
+                "the credentials of an account signing with a Ledger device cannot be stored",
+            )),
+        }
     }
 
     /// Get the keys of this account. The public key can be retrieved from the secret key.
+    ///
+    /// # Panics
+    ///
+    /// If the account signs with a Ledger device, which never hands out its secret key.
     pub fn secret_key(&self) -> &SecretKey {
-        &self.signer.secret_key
+        self.signer.secret_key()
     }
 
     /// Sets the [`SecretKey`] of this account. Future transactions will be signed
     /// using this newly provided key.
     pub fn set_secret_key(&mut self, sk: SecretKey) {
-        self.signer.secret_key = sk;
+        self.signer = InMemorySigner::from_secret_key(self.signer.account_id.clone(), sk);
     }
 }
 
@@ -275,6 +461,12 @@ impl Contract {
         self.account.call(self.id(), function)
     }
 
+    /// Send `payload` to the method `function` of the current contract, split across as many
+    /// calls as it takes. Look at [`Account::chunked_call`] for more info.
+    pub fn chunked_call(&self, function: &str, payload: &[u8]) -> ChunkedCallTransaction {
+        self.account.chunked_call(self.id(), function, payload)
+    }
+
     /// Call a view function into the current contract. Returns a result which can
     /// be deserialized into borsh or JSON.
     pub fn view(&self, function: &str) -> Query<'_, ViewFunction> {
@@ -393,7 +585,7 @@ impl From<AccountDetails> for AccountDetailsPatch {
 
 /// Details of an Account or Contract. This is an non-exhaustive list of items
 /// that the account stores in the blockchain state.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 #[non_exhaustive]
 pub struct AccountDetails {
     pub balance: NearToken,
@@ -0,0 +1,187 @@
+use serde::Deserialize;
+
+use crate::error::ErrorKind;
+use crate::result::Result;
+use crate::types::AccountId;
+use crate::CryptoHash;
+
+/// Prefix of logs that contain a [NEP-297] event.
+///
+/// [NEP-297]: https://nomicon.io/Standards/EventsFormat
+const EVENT_LOG_PREFIX: &str = "EVENT_JSON:";
+
+/// An event emitted by a contract in the [NEP-297] format, which is a log of the form
+/// `EVENT_JSON:{"standard": ..., "version": ..., "event": ..., "data": ...}`.
+///
+/// [NEP-297]: https://nomicon.io/Standards/EventsFormat
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Event {
+    /// Name of the standard the event belongs to, such as `nep141`.
+    pub standard: String,
+    /// Version of the standard the event conforms to.
+    pub version: String,
+    /// Name of the event, such as `ft_transfer`.
+    pub event: String,
+    /// Optional data associated to the event.
+    pub data: Option<serde_json::Value>,
+    /// The account of the contract that emitted this event.
+    pub emitter_id: AccountId,
+    /// The hash of the transaction that led to this event being emitted.
+    pub transaction_hash: CryptoHash,
+    /// The hash of the block the event was emitted in.
+    pub block_hash: CryptoHash,
+}
+
+#[derive(Deserialize)]
+struct EventLog {
+    standard: String,
+    version: String,
+    event: String,
+    #[serde(default)]
+    data: Option<serde_json::Value>,
+}
+
+impl Event {
+    /// Parse an event out of a log. Returns `None` if the log is not an event.
+    pub(crate) fn from_log(
+        log: &str,
+        emitter_id: &AccountId,
+        transaction_hash: CryptoHash,
+        block_hash: CryptoHash,
+    ) -> Option<Self> {
+        let json = log.strip_prefix(EVENT_LOG_PREFIX)?;
+        let EventLog {
+            standard,
+            version,
+            event,
+            data,
+        } = serde_json::from_str(json.trim()).ok()?;
+
+        Some(Self {
+            standard,
+            version,
+            event,
+            data,
+            emitter_id: emitter_id.clone(),
+            transaction_hash,
+            block_hash,
+        })
+    }
+
+    /// Deserialize the data associated to this event into a type. If the event has no
+    /// data, it is deserialized from `null`.
+    pub fn data_json<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_value(self.data.clone().unwrap_or_default())
+            .map_err(|e| ErrorKind::DataConversion.custom(e))
+    }
+}
+
+impl Event {
+    /// Decode the data of this event into typed events, if it's an event of type `E`.
+    /// Following [NEP-297], the data is usually a list with an entry for every occurrence of
+    /// the event, so every entry gets decoded. Returns nothing for events of other types.
+    ///
+    /// [NEP-297]: https://nomicon.io/Standards/EventsFormat
+    pub fn decode<E: TypedEvent>(&self) -> Result<Vec<E>> {
+        if self.standard != E::STANDARD || self.event != E::EVENT {
+            return Ok(Vec::new());
+        }
+
+        match &self.data {
+            Some(serde_json::Value::Array(entries)) => entries
+                .iter()
+                .map(|entry| E::from_fields(&EventFields(entry)))
+                .collect(),
+            Some(entry) => Ok(vec![E::from_fields(&EventFields(entry))?]),
+            None => Ok(vec![E::from_fields(&EventFields(
+                &serde_json::Value::Null,
+            ))?]),
+        }
+    }
+}
+
+/// An event with a typed payload, which is usually generated through the [`events!`] macro.
+///
+/// [`events!`]: crate::events
+pub trait TypedEvent: Sized {
+    /// Name of the standard the event belongs to, such as `nep141`.
+    const STANDARD: &'static str;
+    /// Name of the event, such as `ft_transfer`.
+    const EVENT: &'static str;
+
+    /// Decode an entry of the event data.
+    fn from_fields(fields: &EventFields<'_>) -> Result<Self>;
+}
+
+/// An entry of the data of an event, to decode a [`TypedEvent`] from.
+#[derive(Debug, Clone, Copy)]
+pub struct EventFields<'a>(&'a serde_json::Value);
+
+impl EventFields<'_> {
+    /// Deserialize the field called `name`. A missing field is deserialized from `null`,
+    /// such that it can be decoded into an `Option`.
+    pub fn get<T: serde::de::DeserializeOwned>(&self, name: &str) -> Result<T> {
+        let value = self.0.get(name).cloned().unwrap_or_default();
+        serde_json::from_value(value).map_err(|e| {
+            ErrorKind::DataConversion.full(format!("failed to decode event field `{name}`"), e)
+        })
+    }
+}
+
+/// Define typed [NEP-297] events, which can be decoded from the events emitted by contracts
+/// through [`Event::decode`] or [`ExecutionFinalResult::events_of`], instead of digging
+/// through their JSON data. Every field is decoded from the field of the same name in the
+/// event data.
+///
+/// ```
+/// use near_workspaces::AccountId;
+///
+/// near_workspaces::events! {
+///     /// A NEP-141 transfer of fungible tokens.
+///     pub FtTransfer {
+///         old_owner_id: AccountId,
+///         new_owner_id: AccountId,
+///         amount: String,
+///         memo: Option<String>,
+///     } = ("nep141", "ft_transfer");
+/// }
+///
+/// # fn check(result: near_workspaces::result::ExecutionFinalResult) -> anyhow::Result<()> {
+/// let transfers = result.events_of::<FtTransfer>()?;
+/// assert_eq!(transfers[0].amount, "100");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [NEP-297]: https://nomicon.io/Standards/EventsFormat
+/// [`Event::decode`]: crate::types::Event::decode
+/// [`ExecutionFinalResult::events_of`]: crate::result::ExecutionFinalResult::events_of
+#[macro_export]
+macro_rules! events {
+    ($(
+        $(#[$meta:meta])*
+        $vis:vis $name:ident {
+            $( $(#[$field_meta:meta])* $field:ident : $ty:ty ),* $(,)?
+        } = ($standard:expr, $event:expr)
+    );* $(;)?) => {$(
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq)]
+        $vis struct $name {
+            $( $(#[$field_meta])* pub $field: $ty, )*
+        }
+
+        impl $crate::types::TypedEvent for $name {
+            const STANDARD: &'static str = $standard;
+            const EVENT: &'static str = $event;
+
+            fn from_fields(
+                fields: &$crate::types::EventFields<'_>,
+            ) -> $crate::Result<Self> {
+                Ok(Self {
+                    $( $field: fields.get(stringify!($field))?, )*
+                })
+            }
+        }
+    )*};
+}
@@ -0,0 +1,131 @@
+use near_chain_configs::ProtocolConfigView;
+use near_parameters::view::RuntimeFeesConfigView;
+use near_parameters::Fee;
+
+use crate::types::{Gas, NearToken};
+
+/// An action to calculate the fees of with a [`FeeCalculator`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum FeeAction {
+    CreateAccount,
+    /// Deploy contract code that is `code_len` bytes long.
+    DeployContract {
+        code_len: u64,
+    },
+    /// Call into `method_name` with arguments that are `args_len` bytes long. Only the fees
+    /// of the action itself are accounted for, and not the gas used by the contract.
+    FunctionCall {
+        method_name: String,
+        args_len: u64,
+    },
+    Transfer,
+    Stake,
+    AddFullAccessKey,
+    /// Add a function call access key that is allowed to call into `method_names`.
+    AddFunctionCallKey {
+        method_names: Vec<String>,
+    },
+    DeleteKey,
+    DeleteAccount,
+}
+
+/// Calculates the exact fees of actions from the runtime config of the network, so that
+/// tests can assert on exact balances instead of comparing them within some epsilon.
+/// Create one with [`Worker::fee_calculator`].
+///
+/// The fees account for the gas burnt on converting a transaction into a receipt and on
+/// executing that receipt, which is also what the network charges the signer for.
+///
+/// [`Worker::fee_calculator`]: crate::Worker::fee_calculator
+#[derive(Debug, Clone)]
+pub struct FeeCalculator {
+    fees: RuntimeFeesConfigView,
+    storage_amount_per_byte: NearToken,
+    gas_price: NearToken,
+}
+
+impl FeeCalculator {
+    /// Create a calculator from the protocol config of a network and the gas price that
+    /// fees get charged at.
+    pub fn new(config: &ProtocolConfigView, gas_price: NearToken) -> Self {
+        Self {
+            fees: config.runtime_config.transaction_costs.clone(),
+            storage_amount_per_byte: NearToken::from_yoctonear(
+                config.runtime_config.storage_amount_per_byte,
+            ),
+            gas_price,
+        }
+    }
+
+    /// The gas price that fees get charged at.
+    pub fn gas_price(&self) -> NearToken {
+        self.gas_price
+    }
+
+    /// The gas burnt by a transaction with the given `actions`. `sir` specifies whether
+    /// the sender of the transaction is the receiver as well, which makes some actions
+    /// cheaper.
+    pub fn gas_of(&self, actions: &[FeeAction], sir: bool) -> Gas {
+        let receipt = &self.fees.action_receipt_creation_config;
+        let gas = actions
+            .iter()
+            .map(|action| {
+                self.action_fees(action)
+                    .iter()
+                    .map(|(fee, times)| (fee.send_fee(sir) + fee.exec_fee()) * times)
+                    .sum::<u64>()
+            })
+            .sum::<u64>();
+
+        Gas::from_gas(receipt.send_fee(sir) + receipt.exec_fee() + gas)
+    }
+
+    /// The tokens burnt by a transaction with the given `actions`. Look at
+    /// [`FeeCalculator::gas_of`] for more info.
+    pub fn cost_of(&self, actions: &[FeeAction], sir: bool) -> NearToken {
+        self.gas_price
+            .saturating_mul(u128::from(self.gas_of(actions, sir).as_gas()))
+    }
+
+    /// The tokens that have to be locked on an account for it to use `bytes` of storage.
+    pub fn storage_cost(&self, bytes: u64) -> NearToken {
+        self.storage_amount_per_byte
+            .saturating_mul(u128::from(bytes))
+    }
+
+    /// The fees charged for `action`, along with the number of times each of them is charged.
+    fn action_fees(&self, action: &FeeAction) -> Vec<(&Fee, u64)> {
+        let config = &self.fees.action_creation_config;
+        match action {
+            FeeAction::CreateAccount => vec![(&config.create_account_cost, 1)],
+            FeeAction::DeployContract { code_len } => vec![
+                (&config.deploy_contract_cost, 1),
+                (&config.deploy_contract_cost_per_byte, *code_len),
+            ],
+            FeeAction::FunctionCall {
+                method_name,
+                args_len,
+            } => vec![
+                (&config.function_call_cost, 1),
+                (
+                    &config.function_call_cost_per_byte,
+                    method_name.len() as u64 + args_len,
+                ),
+            ],
+            FeeAction::Transfer => vec![(&config.transfer_cost, 1)],
+            FeeAction::Stake => vec![(&config.stake_cost, 1)],
+            FeeAction::AddFullAccessKey => vec![(&config.add_key_cost.full_access_cost, 1)],
+            FeeAction::AddFunctionCallKey { method_names } => {
+                // Every method name is charged along with a separator.
+                let bytes = method_names.iter().map(|name| name.len() as u64 + 1).sum();
+                vec![
+                    (&config.add_key_cost.function_call_cost, 1),
+                    (&config.add_key_cost.function_call_cost_per_byte, bytes),
+                ]
+            }
+            FeeAction::DeleteKey => vec![(&config.delete_key_cost, 1)],
+            FeeAction::DeleteAccount => vec![(&config.delete_account_cost, 1)],
+        }
+    }
+}
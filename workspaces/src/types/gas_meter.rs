@@ -1,19 +1,9 @@
-use std::panic::{RefUnwindSafe, UnwindSafe};
 use std::sync::{Arc, Mutex};
 
 use super::Gas;
-use crate::result::Result;
+use crate::result::{ExecutionFinalResult, Result};
 use crate::Worker;
 
-/// A hook that is called on every transaction that is sent to the network.
-/// This is useful for debugging purposes, or for tracking the amount of gas
-/// that is being used.
-///
-/// The auto-traits [`Send`], [`Sync`], [`UnwindSafe`] and [`RefUnwindSafe`] are added explicitly because they
-/// do not fall under the rules the compiler uses to automatically add them.
-/// See here: <https://doc.rust-lang.org/reference/special-types-and-traits.html#auto-traits>
-pub(crate) type GasHook = Arc<dyn Fn(Gas) -> Result<()> + Send + Sync + UnwindSafe + RefUnwindSafe>;
-
 /// Allows you to meter the amount of gas consumed by transaction(s).
 /// Note: This only works with transactions that resolve to [`crate::result::ExecutionFinalResult`]
 /// Example
@@ -46,15 +36,17 @@ impl GasMeter {
         };
 
         let gas_consumed = Arc::downgrade(&Arc::clone(&meter.gas));
-        worker.tx_callbacks.push(Arc::new(move |gas: Gas| {
-            // upgrades if meter is still alive, else noop.
-            if let Some(consumed) = gas_consumed.upgrade() {
-                let mut consumed = consumed.lock()?;
-                *consumed = Gas::from_gas(consumed.as_gas() + gas.as_gas());
-            }
+        worker
+            .tx_callbacks
+            .push(Arc::new(move |result: &ExecutionFinalResult| {
+                // upgrades if meter is still alive, else noop.
+                if let Some(consumed) = gas_consumed.upgrade() {
+                    let mut consumed = consumed.lock()?;
+                    *consumed = Gas::from_gas(consumed.as_gas() + result.total_gas_burnt.as_gas());
+                }
 
-            Ok(())
-        }));
+                Ok(())
+            }));
 
         meter
     }
@@ -0,0 +1,81 @@
+//! Signing with the NEAR app of a Ledger device.
+
+use std::str::FromStr;
+
+use near_primitives::action::delegate::DelegateAction;
+use near_primitives::borsh;
+use near_primitives::transaction::Transaction;
+use near_slip10::BIP32Path;
+
+use crate::error::ErrorKind;
+use crate::result::Result;
+use crate::types::PublicKey;
+
+/// A key kept on a Ledger device, which only ever hands out its public key and signatures.
+#[derive(Clone)]
+pub(crate) struct LedgerKey {
+    hd_path: BIP32Path,
+    public_key: PublicKey,
+}
+
+impl LedgerKey {
+    /// Look up the public key at `hd_path` on the Ledger device plugged in.
+    pub(crate) fn connect(hd_path: &str) -> Result<Self> {
+        let hd_path = BIP32Path::from_str(hd_path).map_err(|e| {
+            ErrorKind::DataConversion.message(format!("invalid HD path `{hd_path}`: {e:?}"))
+        })?;
+        let public_key = near_ledger::get_public_key_with_display_flag(hd_path.clone(), false)
+            .map_err(|e| {
+                ErrorKind::Io.message(format!(
+                    "failed to get the public key from the Ledger device: {e:?}"
+                ))
+            })?;
+        let public_key = PublicKey(near_crypto::PublicKey::ED25519(
+            near_crypto::ED25519PublicKey(public_key.to_bytes()),
+        ));
+        Ok(Self {
+            hd_path,
+            public_key,
+        })
+    }
+
+    pub(crate) fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Sign `tx`, which has to be confirmed on the device.
+    pub(crate) async fn sign_transaction(
+        &self,
+        tx: &Transaction,
+    ) -> Result<near_crypto::Signature> {
+        let payload = borsh::to_vec(tx).map_err(|e| ErrorKind::DataConversion.custom(e))?;
+        self.sign(payload, near_ledger::sign_transaction).await
+    }
+
+    /// Sign `action` as a delegate action, which has to be confirmed on the device.
+    pub(crate) async fn sign_delegate_action(
+        &self,
+        action: &DelegateAction,
+    ) -> Result<near_crypto::Signature> {
+        let payload = borsh::to_vec(action).map_err(|e| ErrorKind::DataConversion.custom(e))?;
+        self.sign(payload, near_ledger::sign_message_nep366_delegate_action)
+            .await
+    }
+
+    /// Talk to the device on a blocking thread, as it waits for the confirmation of the user.
+    async fn sign(
+        &self,
+        payload: Vec<u8>,
+        sign: fn(&[u8], BIP32Path) -> std::result::Result<Vec<u8>, near_ledger::NEARLedgerError>,
+    ) -> Result<near_crypto::Signature> {
+        let hd_path = self.hd_path.clone();
+        let signature = tokio::task::spawn_blocking(move || sign(&payload, hd_path))
+            .await
+            .map_err(|e| ErrorKind::Other.custom(e))?
+            .map_err(|e| {
+                ErrorKind::Io.message(format!("failed to sign with the Ledger device: {e:?}"))
+            })?;
+        near_crypto::Signature::from_parts(near_crypto::KeyType::ED25519, &signature)
+            .map_err(|e| ErrorKind::DataConversion.custom(e))
+    }
+}
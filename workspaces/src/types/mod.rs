@@ -7,7 +7,19 @@
 pub(crate) mod account;
 pub(crate) mod block;
 pub(crate) mod chunk;
+pub(crate) mod event;
+#[cfg(feature = "experimental")]
+pub(crate) mod fee;
 pub(crate) mod gas_meter;
+#[cfg(feature = "ledger")]
+mod ledger;
+pub(crate) mod outcome_index;
+pub(crate) mod receipt;
+pub(crate) mod scenario;
+mod seed_phrase;
+pub(crate) mod storage;
+pub(crate) mod summary;
+pub(crate) mod validator;
 
 #[cfg(feature = "interop_sdk")]
 mod sdk;
@@ -20,7 +32,9 @@ use std::str::FromStr;
 
 pub use near_account_id::AccountId;
 use near_crypto::Signer;
+use near_primitives::action::delegate::DelegateAction;
 use near_primitives::borsh::{BorshDeserialize, BorshSerialize};
+use near_primitives::transaction::{SignedTransaction, Transaction};
 
 use serde::{Deserialize, Serialize};
 use sha2::Digest;
@@ -30,7 +44,18 @@ use crate::result::Result;
 
 pub use self::account::{AccountDetails, AccountDetailsPatch};
 pub use self::chunk::{Chunk, ChunkHeader};
+pub use self::event::{Event, EventFields, TypedEvent};
+#[cfg(feature = "experimental")]
+pub use self::fee::{FeeAction, FeeCalculator};
 pub use self::gas_meter::GasMeter;
+pub use self::outcome_index::OutcomeIndex;
+pub use self::receipt::{
+    FunctionCallReceipt, GasReport, ReceiptGasUsage, ReceiptNode, ReceiptTree,
+};
+pub use self::scenario::Scenario;
+pub use self::storage::{MethodStorageUsage, ReceiptStorageUsage, StorageReport};
+pub use self::summary::{AccountSummary, WorldSummary};
+pub use self::validator::{EpochValidators, Validator};
 
 /// Nonce is a unit used to determine the order of transactions in the pool.
 pub type Nonce = u64;
@@ -41,6 +66,13 @@ pub use near_gas::NearGas as Gas;
 
 pub use near_token::NearToken;
 
+/// Actions signed by one account to be relayed to the network by another, as described in
+/// [NEP-366](https://github.com/near/NEPs/blob/master/neps/nep-0366.md).
+pub use near_primitives::action::delegate::SignedDelegateAction;
+
+/// Action carried by a receipt, as found in a [`ReceiptNode`].
+pub use near_primitives::views::ActionView;
+
 /// Height of a specific block
 pub type BlockHeight = u64;
 
@@ -52,7 +84,7 @@ fn from_base58(s: &str) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sy
 }
 
 /// Key types supported for either a [`SecretKey`] or [`PublicKey`]
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum KeyType {
     ED25519 = 0,
@@ -175,6 +207,21 @@ impl PublicKey {
     pub fn key_data(&self) -> &[u8] {
         self.0.key_data()
     }
+
+    /// Get the ID of the implicit account controlled by this key, being the 64 character hex
+    /// encoding of its key data. Only [`KeyType::ED25519`] keys have implicit accounts they
+    /// can sign for, so this returns an error for other key types.
+    pub fn implicit_account_id(&self) -> Result<AccountId> {
+        match &self.0 {
+            near_crypto::PublicKey::ED25519(pk) => {
+                Ok(near_primitives::utils::derive_near_implicit_account_id(pk))
+            }
+            _ => Err(ErrorKind::DataConversion.message(format!(
+                "{} keys have no implicit accounts to sign for",
+                self.key_type()
+            ))),
+        }
+    }
 }
 
 impl Display for PublicKey {
@@ -229,7 +276,11 @@ impl BorshDeserialize for PublicKey {
 /// Usually created along with a [`PublicKey`]
 /// to form a keypair associated to the account. To generate a new keypair, use
 /// one of the creation methods found here, such as [`SecretKey::from_seed`]
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+///
+/// The key material is redacted when formatted through [`Debug`] or [`Display`], so that
+/// keys do not accidentally leak into test or CI logs. Use [`SecretKey::expose_secret`]
+/// to explicitly get a hold of the encoded key.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SecretKey(pub(crate) near_crypto::SecretKey);
 
 impl SecretKey {
@@ -249,17 +300,64 @@ impl SecretKey {
         Self(near_crypto::SecretKey::from_seed(key_type, seed))
     }
 
+    /// HD path of the first key that NEAR wallets and near-cli derive out of a seed phrase.
+    pub const DEFAULT_HD_PATH: &'static str = "m/44'/397'/0'";
+
+    /// Recover the ed25519 secret key derived out of a BIP-39 seed `phrase` at `hd_path`, the
+    /// same way NEAR wallets and near-cli do. This allows accounts created in a wallet to be
+    /// used without exporting their raw keys. Most wallets use [`SecretKey::DEFAULT_HD_PATH`].
+    ///
+    /// ```
+    /// use near_workspaces::types::SecretKey;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let phrase = "abandon abandon abandon abandon abandon abandon \
+    ///     abandon abandon abandon abandon abandon about";
+    /// let sk = SecretKey::from_seed_phrase(phrase, SecretKey::DEFAULT_HD_PATH)?;
+    /// assert_eq!(
+    ///     sk.public_key().to_string(),
+    ///     "ed25519:6j4b6zUaty6fD1awqcGCCU9JYGCWYUgdJhQrzfZhqE25",
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// The words of the phrase are not checked against the BIP-39 wordlist, so a mistyped
+    /// phrase recovers a different key rather than failing. Only hardened path segments are
+    /// supported, as is the case for ed25519.
+    pub fn from_seed_phrase(phrase: &str, hd_path: &str) -> Result<Self> {
+        let seed = seed_phrase::derive_ed25519(phrase, hd_path)?;
+        let keypair = ed25519_dalek::SigningKey::from_bytes(&seed).to_keypair_bytes();
+        Ok(Self(near_crypto::SecretKey::ED25519(
+            near_crypto::ED25519SecretKey(keypair),
+        )))
+    }
+
     /// Generate a new secret key provided the [`KeyType`]. This will use OS provided entropy
     /// to generate the key.
     pub fn from_random(key_type: KeyType) -> Self {
         let key_type = key_type.into_near_keytype();
         Self(near_crypto::SecretKey::from_random(key_type))
     }
+
+    /// Get the encoded form of this secret key, such as `ed25519:...`. This is the only way to
+    /// get a hold of the key material as a string, so be careful to not log it anywhere.
+    pub fn expose_secret(&self) -> String {
+        self.0.to_string()
+    }
 }
 
 impl Display for SecretKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-        write!(f, "{}", self.0)
+        write!(f, "{}:<redacted>", self.0.key_type())
+    }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SecretKey")
+            .field(&format_args!("{self}"))
+            .finish()
     }
 }
 
@@ -274,20 +372,57 @@ impl FromStr for SecretKey {
     }
 }
 
+/// Signer of the transactions of an account, which usually holds the secret key of the
+/// account in memory. With the `ledger` feature, it can instead be backed by a Ledger device
+/// through `InMemorySigner::from_ledger`, such that the secret key never leaves it.
 #[derive(Clone)]
 pub struct InMemorySigner {
     pub(crate) account_id: AccountId,
-    pub(crate) secret_key: SecretKey,
+    pub(crate) key: SignerKey,
+}
+
+/// The key an [`InMemorySigner`] signs with.
+#[derive(Clone)]
+pub(crate) enum SignerKey {
+    Secret(SecretKey),
+    #[cfg(feature = "ledger")]
+    Ledger(ledger::LedgerKey),
+}
+
+impl fmt::Debug for InMemorySigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InMemorySigner")
+            .field("account_id", &self.account_id)
+            .field("public_key", &self.public_key())
+            .finish()
+    }
 }
 
 impl InMemorySigner {
     pub fn from_secret_key(account_id: AccountId, secret_key: SecretKey) -> Self {
         Self {
             account_id,
-            secret_key,
+            key: SignerKey::Secret(secret_key),
         }
     }
 
+    /// Create a signer for `account_id` backed by the NEAR app of the Ledger device plugged
+    /// in, using the key at `hd_path`, such as `44'/397'/0'/0'/1'` which is the one used by
+    /// default by near-cli. Transactions need to be confirmed on the device as they get sent,
+    /// so this is meant for scripts run by hand, such as mainnet deployments.
+    ///
+    /// Ledger signers have no secret key to hand out, so [`InMemorySigner::secret_key`] and
+    /// [`Account::secret_key`](crate::Account::secret_key) panic for them.
+    #[cfg(feature = "ledger")]
+    pub fn from_ledger(account_id: AccountId, hd_path: &str) -> Result<Self> {
+        Ok(Self {
+            account_id,
+            key: SignerKey::Ledger(ledger::LedgerKey::connect(hd_path)?),
+        })
+    }
+
+    /// Load a signer from a JSON key file, such as the ones near-cli keeps credentials in,
+    /// containing the `account_id` along with its `secret_key` or `private_key`.
     pub fn from_file(path: &Path) -> Result<Self> {
         let signer = near_crypto::InMemorySigner::from_file(path)
             .map_err(|err| ErrorKind::Io.custom(err))?;
@@ -297,14 +432,123 @@ impl InMemorySigner {
         ))
     }
 
-    pub(crate) fn inner(&self) -> Signer {
-        Signer::InMemory(near_crypto::InMemorySigner::from_secret_key(
-            self.account_id.clone(),
-            self.secret_key.0.clone(),
-        ))
+    /// Load the signer of `account_id` from the credentials near-cli keeps in the
+    /// `~/.near-credentials` directory. Look at [`InMemorySigner::from_credentials_in`] for
+    /// how the key file gets looked up.
+    pub fn from_credentials_dir(account_id: &AccountId) -> Result<Self> {
+        let home = std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .ok_or_else(|| ErrorKind::Io.message("could not find the home directory"))?;
+        Self::from_credentials_in(Path::new(&home).join(".near-credentials"), account_id)
+    }
+
+    /// Load the signer of `account_id` from the near-cli credentials directory `dir`. Key
+    /// files are looked up in the network subdirectories of `dir`, such as `testnet/`, with
+    /// the one of the network the account belongs to looked at first. Both the
+    /// `<account_id>.json` files of near-cli and the `<account_id>/<public_key>.json` files of
+    /// near-cli-rs are supported.
+    pub fn from_credentials_in(dir: impl AsRef<Path>, account_id: &AccountId) -> Result<Self> {
+        let dir = dir.as_ref();
+        let preferred = if account_id.as_str().ends_with(".testnet") {
+            "testnet"
+        } else if account_id.as_str().ends_with(".near") {
+            "mainnet"
+        } else {
+            ""
+        };
+
+        let mut network_dirs = std::fs::read_dir(dir)
+            .map_err(|e| ErrorKind::Io.full(format!("failed to read {}", dir.display()), e))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_dir())
+            .collect::<Vec<_>>();
+        network_dirs.sort_by_key(|path| (!path.ends_with(preferred), path.clone()));
+
+        for network_dir in std::iter::once(dir.to_path_buf()).chain(network_dirs) {
+            let key_file = network_dir.join(format!("{}.json", account_id));
+            if key_file.is_file() {
+                return Self::from_file(&key_file);
+            }
+
+            let key_dir = network_dir.join(account_id.as_str());
+            if let Ok(entries) = std::fs::read_dir(&key_dir) {
+                let mut key_files = entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                    .collect::<Vec<_>>();
+                key_files.sort();
+                if let Some(key_file) = key_files.first() {
+                    return Self::from_file(key_file);
+                }
+            }
+        }
+
+        Err(ErrorKind::Io.message(format!(
+            "no credentials of {} found in {}",
+            account_id,
+            dir.display()
+        )))
+    }
+
+    /// The account this signer signs for.
+    pub fn account_id(&self) -> &AccountId {
+        &self.account_id
+    }
+
+    /// The public key of the key this signer signs with.
+    pub fn public_key(&self) -> PublicKey {
+        match &self.key {
+            SignerKey::Secret(secret_key) => secret_key.public_key(),
+            #[cfg(feature = "ledger")]
+            SignerKey::Ledger(ledger) => ledger.public_key().clone(),
+        }
+    }
+
+    /// The secret key this signer signs with.
+    ///
+    /// # Panics
+    ///
+    /// If the signer is backed by a Ledger device, which never hands out its secret key.
+    pub fn secret_key(&self) -> &SecretKey {
+        match &self.key {
+            SignerKey::Secret(secret_key) => secret_key,
+            #[cfg(feature = "ledger")]
+            SignerKey::Ledger(_) => panic!("the secret key of a Ledger signer cannot be read"),
+        }
+    }
+
+    pub(crate) async fn sign_transaction(&self, tx: Transaction) -> Result<SignedTransaction> {
+        match &self.key {
+            SignerKey::Secret(secret_key) => Ok(tx.sign(&in_memory(&self.account_id, secret_key))),
+            #[cfg(feature = "ledger")]
+            SignerKey::Ledger(ledger) => {
+                let signature = ledger.sign_transaction(&tx).await?;
+                Ok(SignedTransaction::new(signature, tx))
+            }
+        }
+    }
+
+    pub(crate) async fn sign_delegate_action(
+        &self,
+        action: &DelegateAction,
+    ) -> Result<near_crypto::Signature> {
+        match &self.key {
+            SignerKey::Secret(secret_key) => {
+                Ok(in_memory(&self.account_id, secret_key).sign(action.get_nep461_hash().as_ref()))
+            }
+            #[cfg(feature = "ledger")]
+            SignerKey::Ledger(ledger) => ledger.sign_delegate_action(action).await,
+        }
     }
 }
 
+fn in_memory(account_id: &AccountId, secret_key: &SecretKey) -> Signer {
+    Signer::InMemory(near_crypto::InMemorySigner::from_secret_key(
+        account_id.clone(),
+        secret_key.0.clone(),
+    ))
+}
+
 impl TryFrom<crate::network::ValidatorKey> for InMemorySigner {
     type Error = crate::error::Error;
 
@@ -551,3 +795,46 @@ impl From<Finality> for near_primitives::types::BlockReference {
         value.into()
     }
 }
+
+/// Reference to the block to query from, either by its height, its hash or the latest one
+/// with a specific [`Finality`]. Note that only archival networks will have the full history
+/// of the chain when referencing past blocks.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum BlockReference {
+    Height(BlockHeight),
+    Hash(CryptoHash),
+    Finality(Finality),
+}
+
+impl From<BlockHeight> for BlockReference {
+    fn from(height: BlockHeight) -> Self {
+        Self::Height(height)
+    }
+}
+
+impl From<CryptoHash> for BlockReference {
+    fn from(hash: CryptoHash) -> Self {
+        Self::Hash(hash)
+    }
+}
+
+impl From<Finality> for BlockReference {
+    fn from(finality: Finality) -> Self {
+        Self::Finality(finality)
+    }
+}
+
+impl From<BlockReference> for near_primitives::types::BlockReference {
+    fn from(value: BlockReference) -> Self {
+        use near_primitives::types::BlockId;
+
+        match value {
+            BlockReference::Height(height) => BlockId::Height(height).into(),
+            BlockReference::Hash(hash) => {
+                BlockId::Hash(near_primitives::hash::CryptoHash(hash.0)).into()
+            }
+            BlockReference::Finality(finality) => finality.into(),
+        }
+    }
+}
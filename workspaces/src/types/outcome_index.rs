@@ -0,0 +1,108 @@
+use std::sync::{Arc, Mutex};
+
+use crate::result::{ExecutionFinalResult, ExecutionOutcome, Result};
+use crate::types::{AccountId, Event};
+use crate::Worker;
+
+/// An in-memory index of every transaction sent through a worker, along with the outcomes of
+/// the receipts it produced. This makes end-of-test assertions such as "exactly 3 `ft_transfer`
+/// events were emitted" possible without having to keep track of every result by hand.
+/// Note: This only works with transactions that resolve to [`crate::result::ExecutionFinalResult`]
+/// Example
+/// ```rust, ignore, no_run
+/// let mut worker = near_workspaces::sandbox().await?;
+/// let index = OutcomeIndex::now(&mut worker);
+///
+/// // ... run the transactions of the test through `worker`.
+///
+/// assert_eq!(index.events("nep141", "ft_transfer")?.len(), 3);
+/// assert!(index.failures()?.is_empty());
+/// ```
+pub struct OutcomeIndex {
+    results: Arc<Mutex<Vec<ExecutionFinalResult>>>,
+}
+
+impl OutcomeIndex {
+    /// Create a new empty index, which records the transactions sent through `worker` from
+    /// this point onwards.
+    pub fn now<T: ?Sized>(worker: &mut Worker<T>) -> Self {
+        let index = Self {
+            results: Arc::new(Mutex::new(Vec::new())),
+        };
+
+        let results = Arc::downgrade(&index.results);
+        worker
+            .tx_callbacks
+            .push(Arc::new(move |result: &ExecutionFinalResult| {
+                // upgrades if index is still alive, else noop.
+                if let Some(results) = results.upgrade() {
+                    results.lock()?.push(result.clone());
+                }
+
+                Ok(())
+            }));
+
+        index
+    }
+
+    /// All the transactions recorded so far, in the order they were sent in.
+    pub fn transactions(&self) -> Result<Vec<ExecutionFinalResult>> {
+        Ok(self.results.lock()?.clone())
+    }
+
+    /// The transactions recorded so far that called into the function `method`. Only function
+    /// calls made by the transactions themselves are considered, not the ones made by the
+    /// receipts they produced.
+    pub fn calls(&self, method: &str) -> Result<Vec<ExecutionFinalResult>> {
+        Ok(self
+            .results
+            .lock()?
+            .iter()
+            .filter(|result| result.method_names.iter().any(|name| name == method))
+            .cloned()
+            .collect())
+    }
+
+    /// The transaction and receipt outcomes executed on the account `account_id`.
+    pub fn outcomes_of(&self, account_id: &AccountId) -> Result<Vec<ExecutionOutcome>> {
+        Ok(self
+            .outcomes()?
+            .into_iter()
+            .filter(|outcome| &outcome.executor_id == account_id)
+            .collect())
+    }
+
+    /// The transaction and receipt outcomes that failed.
+    pub fn failures(&self) -> Result<Vec<ExecutionOutcome>> {
+        Ok(self
+            .outcomes()?
+            .into_iter()
+            .filter(ExecutionOutcome::is_failure)
+            .collect())
+    }
+
+    /// The events of the given `standard` and `event` name emitted by any of the outcomes.
+    pub fn events(&self, standard: &str, event: &str) -> Result<Vec<Event>> {
+        Ok(self
+            .outcomes()?
+            .iter()
+            .flat_map(ExecutionOutcome::events)
+            .filter(|e| e.standard == standard && e.event == event)
+            .collect())
+    }
+
+    /// Clear out everything recorded so far.
+    pub fn reset(&self) -> Result<()> {
+        self.results.lock()?.clear();
+        Ok(())
+    }
+
+    fn outcomes(&self) -> Result<Vec<ExecutionOutcome>> {
+        Ok(self
+            .results
+            .lock()?
+            .iter()
+            .flat_map(|result| result.outcomes().into_iter().cloned())
+            .collect())
+    }
+}
@@ -0,0 +1,390 @@
+use std::fmt;
+
+use near_primitives::views::{ReceiptEnumView, ReceiptView};
+
+use crate::result::{ExecutionFinalResult, ExecutionOutcome};
+use crate::types::{AccountId, ActionView, CryptoHash, Gas, NearToken};
+
+/// A function call made by a receipt of a transaction, such as a cross contract call made
+/// by a contract. Look at [`Worker::function_call_receipts`] for how to get them.
+///
+/// [`Worker::function_call_receipts`]: crate::Worker::function_call_receipts
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct FunctionCallReceipt {
+    /// ID of the receipt making the call.
+    pub receipt_id: CryptoHash,
+    /// Account that created the receipt, such as the contract making a cross contract call.
+    pub predecessor_id: AccountId,
+    /// Account the function got called on.
+    pub receiver_id: AccountId,
+    /// Account that signed the transaction the receipt originates from.
+    pub signer_id: AccountId,
+    /// Name of the function called.
+    pub method_name: String,
+    /// Arguments the function got called with.
+    pub args: Vec<u8>,
+    /// Gas attached to the call.
+    pub gas: Gas,
+    /// Deposit attached to the call.
+    pub deposit: NearToken,
+}
+
+impl FunctionCallReceipt {
+    /// All the function calls made by the given receipt, which is none for receipts such as
+    /// the ones refunding gas.
+    pub(crate) fn from_view(view: ReceiptView) -> Vec<Self> {
+        let ReceiptView {
+            predecessor_id,
+            receiver_id,
+            receipt_id,
+            receipt,
+            ..
+        } = view;
+        let ReceiptEnumView::Action {
+            signer_id, actions, ..
+        } = receipt
+        else {
+            return Vec::new();
+        };
+
+        actions
+            .into_iter()
+            .filter_map(|action| match action {
+                ActionView::FunctionCall {
+                    method_name,
+                    args,
+                    gas,
+                    deposit,
+                } => Some(Self {
+                    receipt_id: CryptoHash(receipt_id.0),
+                    predecessor_id: predecessor_id.clone(),
+                    receiver_id: receiver_id.clone(),
+                    signer_id: signer_id.clone(),
+                    method_name,
+                    args: args.into(),
+                    gas: Gas::from_gas(gas),
+                    deposit: NearToken::from_yoctonear(deposit),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// The gas attached to a receipt calling into a function compared to the gas it burnt. Look
+/// at [`Worker::gas_report`] for how to get them.
+///
+/// [`Worker::gas_report`]: crate::Worker::gas_report
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ReceiptGasUsage {
+    /// ID of the receipt.
+    pub receipt_id: CryptoHash,
+    /// Account that created the receipt, such as the contract making a cross contract call.
+    pub predecessor_id: AccountId,
+    /// Account the functions got called on.
+    pub receiver_id: AccountId,
+    /// Names of the functions called by the receipt, which is more than one for batches.
+    pub method_names: Vec<String>,
+    /// Gas attached to the receipt, summed up over all of its function calls.
+    pub gas_attached: Gas,
+    /// Gas burnt while executing the receipt.
+    pub gas_burnt: Gas,
+}
+
+impl ReceiptGasUsage {
+    /// How many times more gas was attached than burnt, or `None` if no gas was burnt.
+    pub fn attached_to_burnt_ratio(&self) -> Option<f64> {
+        if self.gas_burnt.as_gas() == 0 {
+            return None;
+        }
+        Some(self.gas_attached.as_gas() as f64 / self.gas_burnt.as_gas() as f64)
+    }
+}
+
+/// Report of the gas attached to every receipt calling into a function in the call tree of a
+/// transaction, against the gas burnt by it. Useful for tuning the gas constants contracts
+/// attach to cross contract calls.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct GasReport {
+    /// Usage of every receipt calling into a function, in the order they got executed in.
+    pub receipts: Vec<ReceiptGasUsage>,
+}
+
+impl GasReport {
+    pub(crate) fn new(calls: Vec<FunctionCallReceipt>, result: &ExecutionFinalResult) -> Self {
+        let receipts = result
+            .receipt_outcomes()
+            .iter()
+            .filter_map(|outcome| {
+                let mut calls = calls
+                    .iter()
+                    .filter(|call| call.receipt_id == outcome.transaction_hash)
+                    .peekable();
+                let first = calls.peek().copied()?;
+                let mut usage = ReceiptGasUsage {
+                    receipt_id: first.receipt_id,
+                    predecessor_id: first.predecessor_id.clone(),
+                    receiver_id: first.receiver_id.clone(),
+                    method_names: Vec::new(),
+                    gas_attached: Gas::from_gas(0),
+                    gas_burnt: outcome.gas_burnt,
+                };
+                for call in calls {
+                    usage.method_names.push(call.method_name.clone());
+                    usage.gas_attached =
+                        Gas::from_gas(usage.gas_attached.as_gas() + call.gas.as_gas());
+                }
+                Some(usage)
+            })
+            .collect();
+
+        Self { receipts }
+    }
+
+    /// Receipts that were attached more than `factor` times the gas they ended up burning.
+    pub fn over_attached(&self, factor: f64) -> Vec<&ReceiptGasUsage> {
+        self.receipts
+            .iter()
+            .filter(|usage| {
+                usage
+                    .attached_to_burnt_ratio()
+                    .is_none_or(|ratio| ratio > factor)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for GasReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for usage in &self.receipts {
+            writeln!(
+                f,
+                "{} -> {}.{}: attached {}, burnt {}",
+                usage.predecessor_id,
+                usage.receiver_id,
+                usage.method_names.join(","),
+                usage.gas_attached,
+                usage.gas_burnt,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A receipt spawned by a transaction, along with the receipts it spawned in turn. Look at
+/// [`Worker::receipt_tree`] for how to get them.
+///
+/// [`Worker::receipt_tree`]: crate::Worker::receipt_tree
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ReceiptNode {
+    /// ID of the receipt.
+    pub receipt_id: CryptoHash,
+    /// Account that created the receipt, such as the contract making a cross contract call,
+    /// or `system` for refunds.
+    pub predecessor_id: AccountId,
+    /// Account the receipt got executed on.
+    pub receiver_id: AccountId,
+    /// Actions carried by the receipt, which are none for receipts carrying data.
+    pub actions: Vec<ActionView>,
+    /// Outcome of executing the receipt, with its status, logs and the gas it burnt.
+    pub outcome: ExecutionOutcome,
+    /// Receipts spawned by this one, such as cross contract calls and their callbacks.
+    pub children: Vec<ReceiptNode>,
+}
+
+impl ReceiptNode {
+    /// Names of the functions called by the receipt, which is more than one for batches.
+    pub fn method_names(&self) -> Vec<&str> {
+        self.actions
+            .iter()
+            .filter_map(|action| match action {
+                ActionView::FunctionCall { method_name, .. } => Some(method_name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Checks whether the execution of this receipt failed. Receipts under it may fail
+    /// without this one failing, as contracts get to handle the failures of their calls.
+    pub fn is_failure(&self) -> bool {
+        self.outcome.is_failure()
+    }
+
+    /// This receipt followed by all the receipts under it, depth first.
+    pub fn nodes(&self) -> Vec<&ReceiptNode> {
+        let mut nodes = vec![self];
+        for child in &self.children {
+            nodes.extend(child.nodes());
+        }
+        nodes
+    }
+
+    fn path_to<'a>(&'a self, receipt_id: &CryptoHash, path: &mut Vec<&'a ReceiptNode>) -> bool {
+        path.push(self);
+        if &self.receipt_id == receipt_id
+            || self
+                .children
+                .iter()
+                .any(|child| child.path_to(receipt_id, path))
+        {
+            return true;
+        }
+        path.pop();
+        false
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(
+            f,
+            "{:indent$}{} -> {}",
+            "",
+            self.predecessor_id,
+            self.receiver_id,
+            indent = depth * 2
+        )?;
+        let method_names = self.method_names();
+        if !method_names.is_empty() {
+            write!(f, ".{}", method_names.join(","))?;
+        }
+        writeln!(
+            f,
+            ": {}, burnt {}",
+            self.outcome.status_summary(),
+            self.outcome.gas_burnt
+        )?;
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// The tree of receipts spawned by an executed transaction, as reconstructed from its outcomes
+/// and receipts. Rendering it shows every hop of a chain of cross contract calls, indented
+/// under the receipt that made it:
+/// ```text
+/// alice.near -> market.near.buy: succeeded, burnt 3.9 Tgas
+///   market.near -> token.near.ft_transfer: failed: Smart contract panicked: not enough balance, burnt 2.1 Tgas
+///   market.near -> market.near.on_transfer: succeeded, burnt 1.2 Tgas
+///     system -> alice.near: succeeded, burnt 0 gas
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ReceiptTree {
+    /// Outcome of the transaction itself.
+    pub transaction: ExecutionOutcome,
+    /// Receipts the transaction got converted into, which is only one unless the transaction
+    /// failed before producing any.
+    pub receipts: Vec<ReceiptNode>,
+}
+
+impl ReceiptTree {
+    pub(crate) fn new(views: Vec<ReceiptView>, result: &ExecutionFinalResult) -> Self {
+        let transaction = result.outcome();
+        let receipts = Self::children(
+            &transaction.receipt_ids,
+            &transaction.executor_id,
+            &views,
+            result,
+        );
+        Self {
+            transaction: transaction.clone(),
+            receipts,
+        }
+    }
+
+    fn children(
+        receipt_ids: &[CryptoHash],
+        parent_id: &AccountId,
+        views: &[ReceiptView],
+        result: &ExecutionFinalResult,
+    ) -> Vec<ReceiptNode> {
+        receipt_ids
+            .iter()
+            .filter_map(|receipt_id| {
+                let outcome = result
+                    .receipt_outcomes()
+                    .iter()
+                    .find(|outcome| &outcome.transaction_hash == receipt_id)?;
+                let view = views.iter().find(|view| view.receipt_id.0 == receipt_id.0);
+                // Receipts missing from the ones the node returned are assumed to have been
+                // created by the account executing their parent:
+                let (predecessor_id, actions) = match view {
+                    Some(view) => {
+                        let actions = match &view.receipt {
+                            ReceiptEnumView::Action { actions, .. } => actions.clone(),
+                            _ => Vec::new(),
+                        };
+                        (view.predecessor_id.clone(), actions)
+                    }
+                    None => (parent_id.clone(), Vec::new()),
+                };
+                Some(ReceiptNode {
+                    receipt_id: *receipt_id,
+                    predecessor_id,
+                    receiver_id: outcome.executor_id.clone(),
+                    actions,
+                    outcome: outcome.clone(),
+                    children: Self::children(
+                        &outcome.receipt_ids,
+                        &outcome.executor_id,
+                        views,
+                        result,
+                    ),
+                })
+            })
+            .collect()
+    }
+
+    /// All the receipts of the tree, depth first, such that every receipt comes right before
+    /// the receipts it spawned.
+    pub fn nodes(&self) -> Vec<&ReceiptNode> {
+        self.receipts
+            .iter()
+            .flat_map(|receipt| receipt.nodes())
+            .collect()
+    }
+
+    /// The receipts whose execution failed, depth first.
+    pub fn failures(&self) -> Vec<&ReceiptNode> {
+        self.nodes()
+            .into_iter()
+            .filter(|receipt| receipt.is_failure())
+            .collect()
+    }
+
+    /// The chain of receipts leading from the transaction down to the receipt `receipt_id`,
+    /// ending with it, or an empty chain if the receipt is not part of the tree.
+    pub fn path_to(&self, receipt_id: &CryptoHash) -> Vec<&ReceiptNode> {
+        let mut path = Vec::new();
+        for receipt in &self.receipts {
+            if receipt.path_to(receipt_id, &mut path) {
+                break;
+            }
+        }
+        path
+    }
+
+    /// The chain of receipts leading from the transaction down to the first receipt that
+    /// failed, ending with it, or an empty chain if none failed. This is the hop by hop trace
+    /// of which callee actually failed.
+    pub fn failure_path(&self) -> Vec<&ReceiptNode> {
+        match self.failures().first() {
+            Some(failure) => self.path_to(&failure.receipt_id),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for ReceiptTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for receipt in &self.receipts {
+            receipt.fmt_indented(f, 0)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::Gas;
+use crate::result::{ExecutionFinalResult, Result};
+use crate::types::AccountId;
+use crate::{Network, Worker};
+
+/// Recorder of everything happening in a multi-actor scenario, such that it can be rendered
+/// as a single chronological report of the actors involved, the calls they made along with
+/// the gas they burnt, the events they emitted and the failures they ran into. This is meant
+/// for debugging failing scenarios, as well as for attaching to bug reports.
+/// Note: This only works with transactions that resolve to [`crate::result::ExecutionFinalResult`]
+/// Example
+/// ```rust, ignore, no_run
+/// let mut worker = near_workspaces::sandbox().await?;
+/// let scenario = Scenario::now(&mut worker, "auction");
+///
+/// scenario.note("bidding starts")?;
+/// // ... run the transactions of the scenario through `worker`.
+///
+/// println!("{}", scenario.report()?);
+/// ```
+pub struct Scenario {
+    name: String,
+    started: Instant,
+    worker: Worker<dyn Network>,
+    steps: Arc<Mutex<Vec<(Duration, Step)>>>,
+}
+
+enum Step {
+    Note(String),
+    Transaction(Box<ExecutionFinalResult>),
+}
+
+impl Scenario {
+    /// Start recording the scenario `name` out of the transactions sent through `worker`
+    /// from this point onwards. Accounts only pick up recorders registered before they were
+    /// created.
+    pub fn now<T: Network + 'static>(worker: &mut Worker<T>, name: impl Into<String>) -> Self {
+        let started = Instant::now();
+        let steps = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::downgrade(&steps);
+        worker
+            .tx_callbacks
+            .push(Arc::new(move |result: &ExecutionFinalResult| {
+                // upgrades if the scenario is still alive, else noop.
+                if let Some(steps) = recorded.upgrade() {
+                    steps.lock()?.push((
+                        started.elapsed(),
+                        Step::Transaction(Box::new(result.clone())),
+                    ));
+                }
+
+                Ok(())
+            }));
+
+        Self {
+            name: name.into(),
+            started,
+            worker: worker.clone().coerce(),
+            steps,
+        }
+    }
+
+    /// Mark the start of a new phase of the scenario, such as `bidding closes`, which shows up
+    /// in the report in between the transactions sent before and after it.
+    pub fn note(&self, note: impl Into<String>) -> Result<()> {
+        self.steps
+            .lock()?
+            .push((self.started.elapsed(), Step::Note(note.into())));
+        Ok(())
+    }
+
+    /// All the transactions recorded so far, in the order they were sent in.
+    pub fn transactions(&self) -> Result<Vec<ExecutionFinalResult>> {
+        Ok(self
+            .steps
+            .lock()?
+            .iter()
+            .filter_map(|(_, step)| match step {
+                Step::Transaction(result) => Some((**result).clone()),
+                Step::Note(_) => None,
+            })
+            .collect())
+    }
+
+    /// The accounts that signed or executed any of the transactions recorded so far.
+    pub fn actors(&self) -> Result<BTreeSet<AccountId>> {
+        Ok(self
+            .transactions()?
+            .iter()
+            .flat_map(|result| result.outcomes().into_iter().cloned())
+            .map(|outcome| outcome.executor_id)
+            .collect())
+    }
+
+    /// Render the chronological report of the scenario so far, with accounts referred to by
+    /// their [labels](Worker::label) where they have one:
+    /// ```text
+    /// scenario `auction`: 2 transactions, 8.1 Tgas burnt, 1 failed
+    /// actors: alice (dev-...test.near), auction (dev-...test.near)
+    /// [0.000s] note: bidding starts
+    /// [0.731s] #1 alice -> auction.bid: succeeded, burnt 4.2 Tgas
+    ///   alice: succeeded, burnt 0.3 Tgas
+    ///   auction: succeeded, burnt 3.9 Tgas
+    ///   event: auction/bid_placed
+    /// [1.402s] #2 bob -> auction.bid: failed: Smart contract panicked: bid too low, burnt 3.9 Tgas
+    ///   ...
+    /// ```
+    pub fn report(&self) -> Result<String> {
+        let actors = self
+            .actors()?
+            .into_iter()
+            .map(|id| match self.worker.label_of(&id) {
+                Some(label) => format!("{} ({})", label, id),
+                None => id.to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        let steps = self.steps.lock()?;
+        let transactions = steps
+            .iter()
+            .filter_map(|(_, step)| match step {
+                Step::Transaction(result) => Some(&**result),
+                Step::Note(_) => None,
+            })
+            .collect::<Vec<_>>();
+        let burnt = transactions.iter().fold(Gas::from_gas(0), |burnt, result| {
+            burnt.saturating_add(result.total_gas_burnt)
+        });
+        let failed = transactions
+            .iter()
+            .filter(|result| result.is_failure())
+            .count();
+
+        let mut report = String::new();
+        let _ = writeln!(
+            report,
+            "scenario `{}`: {} transactions, {} burnt, {} failed",
+            self.name,
+            transactions.len(),
+            burnt,
+            failed
+        );
+
+        let _ = writeln!(report, "actors: {}", actors.join(", "));
+
+        let mut number = 0;
+        for (at, step) in steps.iter() {
+            let _ = write!(report, "[{:.3}s] ", at.as_secs_f64());
+            match step {
+                Step::Note(note) => {
+                    let _ = writeln!(report, "note: {}", note);
+                }
+                Step::Transaction(result) => {
+                    number += 1;
+                    let _ = write!(report, "#{} {}", number, self.worker.relabel(&**result));
+                    for event in result
+                        .outcomes()
+                        .iter()
+                        .flat_map(|outcome| outcome.events())
+                    {
+                        let _ = writeln!(report, "  event: {}/{}", event.standard, event.event);
+                    }
+                }
+            }
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,96 @@
+//! Derivation of ed25519 keys from BIP-39 seed phrases, following SLIP-10 the same way NEAR
+//! wallets and near-cli do.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+
+use crate::error::ErrorKind;
+use crate::result::Result;
+
+type HmacSha512 = Hmac<Sha512>;
+
+const PBKDF2_ROUNDS: u32 = 2048;
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+
+/// Derive the 32 byte ed25519 seed at `hd_path` out of the seed `phrase`.
+pub(crate) fn derive_ed25519(phrase: &str, hd_path: &str) -> Result<[u8; 32]> {
+    let phrase = normalize(phrase)?;
+    let path = parse_hd_path(hd_path)?;
+
+    let seed = bip39_seed(&phrase);
+    let (mut key, mut chain_code) = split(hmac_sha512(b"ed25519 seed", &[&seed]));
+    for index in path {
+        let data = [&[0u8][..], &key, &(index | HARDENED_OFFSET).to_be_bytes()];
+        (key, chain_code) = split(hmac_sha512(&chain_code, &data));
+    }
+    Ok(key)
+}
+
+fn normalize(phrase: &str) -> Result<String> {
+    if !phrase.is_ascii() {
+        return Err(ErrorKind::DataConversion.message("seed phrase must be made of ASCII words"));
+    }
+    let words = phrase
+        .split_whitespace()
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>();
+    if ![12, 15, 18, 21, 24].contains(&words.len()) {
+        return Err(ErrorKind::DataConversion.message(format!(
+            "seed phrase must have 12, 15, 18, 21 or 24 words, but has {}",
+            words.len()
+        )));
+    }
+    Ok(words.join(" "))
+}
+
+fn parse_hd_path(hd_path: &str) -> Result<Vec<u32>> {
+    let invalid = || {
+        ErrorKind::DataConversion.message(format!(
+            "invalid HD path `{hd_path}`, expected hardened segments such as m/44'/397'/0'"
+        ))
+    };
+
+    let mut segments = hd_path.split('/');
+    if segments.next() != Some("m") {
+        return Err(invalid());
+    }
+    segments
+        .map(|segment| {
+            segment
+                .strip_suffix('\'')
+                .and_then(|index| index.parse::<u32>().ok())
+                .filter(|index| *index < HARDENED_OFFSET)
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
+
+/// PBKDF2-HMAC-SHA512 with an empty passphrase. The 64 byte output fits within a single
+/// block, so there is no need for a full PBKDF2 implementation.
+fn bip39_seed(phrase: &str) -> [u8; 64] {
+    let mut block = hmac_sha512(phrase.as_bytes(), &[b"mnemonic", &1u32.to_be_bytes()]);
+    let mut seed = block;
+    for _ in 1..PBKDF2_ROUNDS {
+        block = hmac_sha512(phrase.as_bytes(), &[&block]);
+        for (seed, byte) in seed.iter_mut().zip(block) {
+            *seed ^= byte;
+        }
+    }
+    seed
+}
+
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> [u8; 64] {
+    let mut mac = HmacSha512::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for data in data {
+        mac.update(data);
+    }
+    mac.finalize().into_bytes().into()
+}
+
+fn split(bytes: [u8; 64]) -> ([u8; 32], [u8; 32]) {
+    let mut left = [0; 32];
+    let mut right = [0; 32];
+    left.copy_from_slice(&bytes[..32]);
+    right.copy_from_slice(&bytes[32..]);
+    (left, right)
+}
@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::types::{AccountId, CryptoHash};
+
+/// The storage written and deleted by a receipt calling into a contract. Look at
+/// [`Worker::storage_report`] for how to get them.
+///
+/// [`Worker::storage_report`]: crate::Worker::storage_report
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ReceiptStorageUsage {
+    /// ID of the receipt.
+    pub receipt_id: CryptoHash,
+    /// Account of the contract the receipt got executed on.
+    pub account_id: AccountId,
+    /// Names of the functions called by the receipt, which is more than one for batches.
+    pub method_names: Vec<String>,
+    /// Bytes of the keys and values written by the receipt, whether new or overwritten.
+    pub bytes_written: u64,
+    /// Bytes of the keys deleted by the receipt.
+    pub bytes_deleted: u64,
+    /// How much the storage usage of the account grew by, or shrunk by if negative. Unlike
+    /// the bytes written and deleted, this accounts for overwritten values and for the
+    /// overhead the protocol charges per record.
+    pub storage_growth: i64,
+}
+
+/// The storage usage of all the calls into a method, as aggregated by [`StorageReport`].
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct MethodStorageUsage {
+    /// Number of receipts that called into the method.
+    pub calls: u64,
+    /// Bytes of the keys and values written, summed up over all the calls.
+    pub bytes_written: u64,
+    /// Bytes of the keys deleted, summed up over all the calls.
+    pub bytes_deleted: u64,
+    /// Growth of the storage usage, summed up over all the calls.
+    pub storage_growth: i64,
+}
+
+impl MethodStorageUsage {
+    /// The average growth of the storage usage per call.
+    pub fn growth_per_call(&self) -> f64 {
+        if self.calls == 0 {
+            return 0.0;
+        }
+        self.storage_growth as f64 / self.calls as f64
+    }
+}
+
+/// Report of the storage written and deleted by every receipt calling into a contract, across
+/// any number of transactions. Storage cost regressions matter as much as gas regressions,
+/// as every byte a contract keeps has to be paid for by locking up tokens:
+/// ```
+/// # async fn run(worker: near_workspaces::Worker<near_workspaces::network::Sandbox>, index: near_workspaces::types::OutcomeIndex) -> anyhow::Result<()> {
+/// let report = worker.storage_report(&index.transactions()?).await?;
+/// let over_budget = report.over_budget(200);
+/// assert!(over_budget.is_empty(), "{}", report);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct StorageReport {
+    /// Usage of every receipt calling into a contract, in the order they got executed in.
+    pub receipts: Vec<ReceiptStorageUsage>,
+}
+
+impl StorageReport {
+    /// The usage of the receipts aggregated per method, with receipts calling into several
+    /// methods in a batch counting towards the methods joined by commas, such as `a,b`.
+    pub fn by_method(&self) -> BTreeMap<String, MethodStorageUsage> {
+        let mut methods = BTreeMap::<_, MethodStorageUsage>::new();
+        for usage in &self.receipts {
+            let method = methods.entry(usage.method_names.join(",")).or_default();
+            method.calls += 1;
+            method.bytes_written += usage.bytes_written;
+            method.bytes_deleted += usage.bytes_deleted;
+            method.storage_growth += usage.storage_growth;
+        }
+        methods
+    }
+
+    /// The methods whose storage usage grows by more than `bytes_per_call` on average.
+    pub fn over_budget(&self, bytes_per_call: u64) -> Vec<(String, MethodStorageUsage)> {
+        self.by_method()
+            .into_iter()
+            .filter(|(_, usage)| usage.growth_per_call() > bytes_per_call as f64)
+            .collect()
+    }
+}
+
+impl fmt::Display for StorageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (method, usage) in self.by_method() {
+            writeln!(
+                f,
+                "{}: {} calls, wrote {} bytes, deleted {} bytes, grew by {} bytes ({:.1} per call)",
+                method,
+                usage.calls,
+                usage.bytes_written,
+                usage.bytes_deleted,
+                usage.storage_growth,
+                usage.growth_per_call(),
+            )?;
+        }
+        Ok(())
+    }
+}
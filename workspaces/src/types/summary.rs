@@ -0,0 +1,90 @@
+use std::fmt;
+
+use crate::types::{AccountDetails, AccountId};
+
+/// State of an account known to a worker, as part of a [`WorldSummary`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct AccountSummary {
+    /// ID of the account.
+    pub account_id: AccountId,
+    /// Label of the account, if it was given one with [`Worker::label`].
+    ///
+    /// [`Worker::label`]: crate::Worker::label
+    pub label: Option<String>,
+    /// Details of the account, or `None` if it doesn't exist (anymore), such as after
+    /// getting deleted.
+    pub details: Option<AccountDetails>,
+}
+
+/// Summary of the state of all the accounts known to a worker, being the ones that signed or
+/// received transactions sent through it, along with the labeled ones. Look at
+/// [`Worker::summarize`] for how to get one. Its [`Display`](fmt::Display) implementation
+/// prints a table meant to be read at a glance, such as at the end of a failing scenario:
+/// ```text
+/// account                  balance              locked  storage  code hash
+/// test.near                1000000000 NEAR      0 NEAR  182      11111111111111111111111111111111
+/// buyer (dev-...test.near) 99.99 NEAR           0 NEAR  182      11111111111111111111111111111111
+/// ```
+///
+/// [`Worker::summarize`]: crate::Worker::summarize
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct WorldSummary {
+    /// The accounts, ordered by their ID.
+    pub accounts: Vec<AccountSummary>,
+}
+
+impl WorldSummary {
+    /// The summary of the account `account_id`, if it is known.
+    pub fn account(&self, account_id: &AccountId) -> Option<&AccountSummary> {
+        self.accounts
+            .iter()
+            .find(|account| &account.account_id == account_id)
+    }
+}
+
+impl fmt::Display for WorldSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = ["account", "balance", "locked", "storage", "code hash"];
+        let rows = self
+            .accounts
+            .iter()
+            .map(|account| {
+                let name = match &account.label {
+                    Some(label) => format!("{} ({})", label, account.account_id),
+                    None => account.account_id.to_string(),
+                };
+                match &account.details {
+                    Some(details) => [
+                        name,
+                        details.balance.to_string(),
+                        details.locked.to_string(),
+                        details.storage_usage.to_string(),
+                        details.code_hash.to_string(),
+                    ],
+                    None => [name, "deleted".into(), "".into(), "".into(), "".into()],
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut widths = header.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        let header = header.map(String::from);
+        for row in std::iter::once(&header).chain(&rows) {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
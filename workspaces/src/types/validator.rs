@@ -0,0 +1,85 @@
+use near_primitives::views::{
+    validator_stake_view::ValidatorStakeView, CurrentEpochValidatorInfo, EpochValidatorInfo,
+    NextEpochValidatorInfo,
+};
+
+use crate::types::{AccountId, BlockHeight, NearToken, PublicKey};
+
+/// The validators of an epoch, along with the ones elected to validate the next epoch. This
+/// is a non-exhaustive list of the details returned by the network, where newer fields can
+/// be added in the future.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct EpochValidators {
+    /// Height of the epoch, being the number of epochs since genesis.
+    pub epoch_height: u64,
+    /// Height of the first block in the epoch.
+    pub epoch_start_height: BlockHeight,
+    /// Validators of the epoch.
+    pub current_validators: Vec<Validator>,
+    /// Validators elected for the next epoch.
+    pub next_validators: Vec<Validator>,
+    /// Staking proposals made during the epoch, which take effect in a later epoch.
+    pub current_proposals: Vec<Validator>,
+}
+
+/// A single validator, or validator candidate, within an epoch.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Validator {
+    pub account_id: AccountId,
+    pub public_key: PublicKey,
+    pub stake: NearToken,
+    /// Whether the validator got slashed. This is only ever set for current validators.
+    pub is_slashed: bool,
+}
+
+impl From<EpochValidatorInfo> for EpochValidators {
+    fn from(info: EpochValidatorInfo) -> Self {
+        Self {
+            epoch_height: info.epoch_height,
+            epoch_start_height: info.epoch_start_height,
+            current_validators: info
+                .current_validators
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            next_validators: info.next_validators.into_iter().map(Into::into).collect(),
+            current_proposals: info.current_proposals.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<CurrentEpochValidatorInfo> for Validator {
+    fn from(info: CurrentEpochValidatorInfo) -> Self {
+        Self {
+            account_id: info.account_id,
+            public_key: PublicKey(info.public_key),
+            stake: NearToken::from_yoctonear(info.stake),
+            is_slashed: info.is_slashed,
+        }
+    }
+}
+
+impl From<NextEpochValidatorInfo> for Validator {
+    fn from(info: NextEpochValidatorInfo) -> Self {
+        Self {
+            account_id: info.account_id,
+            public_key: PublicKey(info.public_key),
+            stake: NearToken::from_yoctonear(info.stake),
+            is_slashed: false,
+        }
+    }
+}
+
+impl From<ValidatorStakeView> for Validator {
+    fn from(view: ValidatorStakeView) -> Self {
+        let ValidatorStakeView::V1(view) = view;
+        Self {
+            account_id: view.account_id,
+            public_key: PublicKey(view.public_key),
+            stake: NearToken::from_yoctonear(view.stake),
+            is_slashed: false,
+        }
+    }
+}
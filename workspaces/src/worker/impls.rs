@@ -1,21 +1,45 @@
-use near_primitives::views::StatusResponse;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
 
-use crate::network::{Info, RootAccountSubaccountCreator, Sandbox, Testnet};
+use near_jsonrpc_client::errors::{JsonRpcError, JsonRpcServerError};
+use near_jsonrpc_client::methods;
+use near_jsonrpc_primitives::types::blocks::RpcBlockError;
+use near_primitives::types::{BlockId, BlockReference};
+use near_primitives::views::{
+    FinalExecutionOutcomeViewEnum, ReceiptView, StateChangeValueView, StateChangesRequestView,
+    StatusResponse,
+};
+
+use crate::error::{ErrorKind, RpcErrorCode};
+use crate::network::{
+    Info, MockNetwork, RootAccountSubaccountCreator, Sandbox, SnapshotId, Testnet, ValidatorKey,
+};
 use crate::network::{NetworkClient, NetworkInfo};
-use crate::operations::{CallTransaction, Function};
+use crate::operations::{CallTransaction, Function, TransactionStatus, WaitForEvent};
 use crate::result::{ExecutionFinalResult, Result};
 use crate::rpc::client::Client;
-use crate::rpc::patch::{ImportContractTransaction, PatchTransaction};
+use crate::rpc::patch::{
+    BatchPatchTransaction, ImportContractTransaction, ImportContractsTransaction, PatchTransaction,
+    StateDump,
+};
 use crate::rpc::query::{
-    GasPrice, Query, QueryChunk, ViewAccessKey, ViewAccessKeyList, ViewAccount, ViewBlock,
-    ViewCode, ViewFunction, ViewState,
+    CachedView, GasPrice, Query, QueryChunk, ViewAccessKey, ViewAccessKeyList, ViewAccount,
+    ViewBlock, ViewCode, ViewFunction, ViewState, ViewValidators,
+};
+use crate::rpc::tool;
+use crate::types::{
+    AccessKey, AccountDetailsPatch, AccountId, AccountSummary, BlockHeight, Event,
+    FunctionCallReceipt, GasReport, InMemorySigner, NearToken, PublicKey, ReceiptStorageUsage,
+    ReceiptTree, StorageReport, WorldSummary,
 };
-use crate::types::{AccountId, InMemorySigner, NearToken, PublicKey};
 use crate::worker::Worker;
-use crate::{Account, Network};
+use crate::{Account, Block, CryptoHash, Network};
 
 #[cfg(feature = "experimental")]
 use {
+    crate::types::FeeCalculator,
     near_chain_configs::{GenesisConfig, ProtocolConfigView},
     near_jsonrpc_client::methods::tx::RpcTransactionResponse,
     near_jsonrpc_primitives::types::{
@@ -24,11 +48,8 @@ use {
         transactions::TransactionInfo,
     },
     near_primitives::{
-        types::{BlockReference, MaybeBlockId},
-        views::{
-            validator_stake_view::ValidatorStakeView, ReceiptView, StateChangesRequestView,
-            TxExecutionStatus,
-        },
+        types::{Finality, MaybeBlockId},
+        views::{validator_stake_view::ValidatorStakeView, TxExecutionStatus},
     },
 };
 
@@ -37,6 +58,8 @@ impl<T: ?Sized> Clone for Worker<T> {
         Self {
             workspace: self.workspace.clone(),
             tx_callbacks: self.tx_callbacks.clone(),
+            pinned_block: self.pinned_block.clone(),
+            dev_key_type: self.dev_key_type,
         }
     }
 }
@@ -58,6 +81,52 @@ where
         self.workspace.client()
     }
 
+    /// Create a [`Query`] into the network, which will be pinned to a specific block if
+    /// this worker was handed out by [`Worker::consistent_view`].
+    pub(crate) fn query<M>(&self, method: M) -> Query<'_, M> {
+        let mut query = Query::new(self.client(), method);
+        query.block_ref = self.pinned_block.clone();
+        query
+    }
+
+    /// Run `f` with a worker where all view calls are pinned to the same block, being the
+    /// latest one at the time this gets called. This avoids flaky assertions that would
+    /// otherwise read values from either side of a block boundary.
+    ///
+    /// Note that only views made through the worker handed to `f` are pinned, and not the
+    /// ones made through an [`Account`] or [`Contract`] created from a different worker.
+    ///
+    /// ```
+    /// use near_workspaces::{Contract, DevNetwork, Worker};
+    ///
+    /// async fn read_both(worker: &Worker<impl DevNetwork>, contract: &Contract) -> anyhow::Result<()> {
+    ///     let (a, b) = worker
+    ///         .consistent_view(|pinned| async move {
+    ///             let a = pinned.view(contract.id(), "get_a").await?;
+    ///             let b = pinned.view(contract.id(), "get_b").await?;
+    ///             anyhow::Ok((a.json::<u64>()?, b.json::<u64>()?))
+    ///         })
+    ///         .await??;
+    ///     assert_eq!(a, b);
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`Contract`]: crate::Contract
+    pub async fn consistent_view<F, Fut, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(Self) -> Fut,
+        Fut: std::future::Future<Output = R>,
+    {
+        let block = self.view_block().await?;
+        let mut pinned = self.clone();
+        pinned.pinned_block = Some(BlockReference::BlockId(BlockId::Hash(
+            near_primitives::hash::CryptoHash(block.hash().0),
+        )));
+
+        Ok(f(pinned).await)
+    }
+
     /// Call into a contract's view function. Returns a [`Query`] which allows us
     /// to specify further details like the arguments of the view call, or at what
     /// point in the chain we want to view.
@@ -65,35 +134,42 @@ where
         self.view_by_function(contract_id, Function::new(function))
     }
 
+    /// Call into a contract's view function, memoizing the result for `ttl`. Repeating the
+    /// same call within `ttl` is answered from memory instead of the network, which is useful
+    /// for data that rarely changes, such as token metadata on mainnet or archival nodes.
+    /// Look at [`CachedView`] for more info.
+    pub fn cached_view(
+        &self,
+        ttl: std::time::Duration,
+        contract_id: &AccountId,
+        function: &str,
+    ) -> CachedView<'_> {
+        self.view(contract_id, function).cached(ttl)
+    }
+
     pub(crate) fn view_by_function(
         &self,
         contract_id: &AccountId,
         function: Function,
     ) -> Query<'_, ViewFunction> {
-        Query::new(
-            self.client(),
-            ViewFunction {
-                account_id: contract_id.clone(),
-                function,
-            },
-        )
+        self.query(ViewFunction {
+            account_id: contract_id.clone(),
+            function,
+        })
     }
 
     /// View the WASM code bytes of a contract on the network.
     pub fn view_code(&self, contract_id: &AccountId) -> Query<'_, ViewCode> {
-        Query::new(
-            self.client(),
-            ViewCode {
-                account_id: contract_id.clone(),
-            },
-        )
+        self.query(ViewCode {
+            account_id: contract_id.clone(),
+        })
     }
 
     /// View the state of a account/contract on the network. This will return the internal
     /// state of the account in the form of a map of key-value pairs; where STATE contains
     /// info on a contract's internal data.
     pub fn view_state(&self, contract_id: &AccountId) -> Query<'_, ViewState> {
-        Query::view_state(self.client(), contract_id)
+        self.query(ViewState::new(contract_id))
     }
 
     /// View the block from the network. Supply additional parameters such as [`block_height`]
@@ -102,7 +178,7 @@ where
     /// [`block_height`]: Query::block_height
     /// [`block_hash`]: Query::block_hash
     pub fn view_block(&self) -> Query<'_, ViewBlock> {
-        Query::new(self.client(), ViewBlock)
+        self.query(ViewBlock)
     }
 
     /// View the chunk from the network once awaited. Supply additional parameters such as
@@ -123,13 +199,10 @@ where
     ///
     /// [`AccessKey`]: crate::types::AccessKey
     pub fn view_access_key(&self, id: &AccountId, pk: &PublicKey) -> Query<'_, ViewAccessKey> {
-        Query::new(
-            self.client(),
-            ViewAccessKey {
-                account_id: id.clone(),
-                public_key: pk.clone(),
-            },
-        )
+        self.query(ViewAccessKey {
+            account_id: id.clone(),
+            public_key: pk.clone(),
+        })
     }
 
     /// Views all the [`AccessKey`]s of the account specified by [`AccountId`]. This will
@@ -137,26 +210,124 @@ where
     ///
     /// [`AccessKey`]: crate::types::AccessKey
     pub fn view_access_keys(&self, id: &AccountId) -> Query<'_, ViewAccessKeyList> {
-        Query::new(
-            self.client(),
-            ViewAccessKeyList {
-                account_id: id.clone(),
-            },
-        )
+        self.query(ViewAccessKeyList {
+            account_id: id.clone(),
+        })
     }
 
     /// View account details of a specific account on the network.
     pub fn view_account(&self, account_id: &AccountId) -> Query<'_, ViewAccount> {
-        Query::new(
-            self.client(),
-            ViewAccount {
-                account_id: account_id.clone(),
-            },
-        )
+        self.query(ViewAccount {
+            account_id: account_id.clone(),
+        })
     }
 
+    /// View the gas price of the network in yoctoNEAR per unit of gas, which is what fees get
+    /// charged at. Supply [`block_height`] or [`block_hash`] to view the gas price at a
+    /// specific block rather than the latest one.
+    ///
+    /// [`block_height`]: Query::block_height
+    /// [`block_hash`]: Query::block_hash
     pub fn gas_price(&self) -> Query<'_, GasPrice> {
-        Query::new(self.client(), GasPrice)
+        self.query(GasPrice)
+    }
+
+    /// View the validators of the latest epoch, along with the ones elected for the next
+    /// epoch. Supply [`block_height`] or [`block_hash`] to view the validators of the epoch
+    /// containing that block instead.
+    ///
+    /// [`block_height`]: Query::block_height
+    /// [`block_hash`]: Query::block_hash
+    pub fn validators(&self) -> Query<'_, ViewValidators> {
+        self.query(ViewValidators)
+    }
+
+    /// The number of blocks left until the current epoch ends, going by the epoch length in
+    /// the genesis config of the network. Epochs can end a few blocks later than that, as the
+    /// last block of an epoch has to be final.
+    pub async fn blocks_until_epoch_end(&self) -> Result<u64> {
+        let epoch_length = self.client().epoch_length().await?;
+        let sync_info = self.client().status().await?.sync_info;
+        let epoch_start_height = match sync_info.epoch_start_height {
+            Some(height) => height,
+            None => self.validators().await?.epoch_start_height,
+        };
+        Ok((epoch_start_height + epoch_length).saturating_sub(sync_info.latest_block_height))
+    }
+
+    /// Label the account `account_id` as `label`, such as `buyer`, for output meant to be read
+    /// by people. The label is shared by all clones of this worker, and replaces any previous
+    /// label of the account. Look at [`Worker::relabel`] for where labels get substituted.
+    pub fn label(&self, account_id: &AccountId, label: impl Into<String>) {
+        if let Ok(mut labels) = self.client().labels.write() {
+            labels.insert(account_id.clone(), label.into());
+        }
+    }
+
+    /// The label of the account `account_id`, if it was given one with [`Worker::label`].
+    pub fn label_of(&self, account_id: &AccountId) -> Option<String> {
+        self.client()
+            .labels
+            .read()
+            .ok()
+            .and_then(|labels| labels.get(account_id).cloned())
+    }
+
+    /// Format `value`, replacing the ids of [labeled] accounts with their labels. This makes
+    /// failure output read `buyer -> marketplace` instead of `dev-20240101-1234.test.near ->
+    /// dev-20240101-5678.test.near`, and works with outcomes, logs and diffs alike:
+    ///
+    /// ```
+    /// # async fn run(worker: near_workspaces::Worker<near_workspaces::network::Sandbox>, buyer: near_workspaces::Account, market: near_workspaces::Contract) -> anyhow::Result<()> {
+    /// worker.label(buyer.id(), "buyer");
+    /// worker.label(market.id(), "marketplace");
+    ///
+    /// let outcome = buyer.call(market.id(), "buy").transact().await?;
+    /// assert!(outcome.is_success(), "{}", worker.relabel(&outcome));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [labeled]: Worker::label
+    pub fn relabel(&self, value: impl fmt::Display) -> String {
+        let text = value.to_string();
+        match self.client().labels.read() {
+            Ok(labels) if !labels.is_empty() => tool::relabel(&text, &labels),
+            _ => text,
+        }
+    }
+
+    /// Only allow the `accounts` to sign transactions from now on, such that transactions
+    /// signed by any other account fail without being sent. This catches scenarios
+    /// accidentally relying on the root account, or on accounts leaking in from other tests
+    /// sharing the same sandbox. Calling it again declares more accounts.
+    ///
+    /// Dev accounts created through this worker, along with subaccounts created by declared
+    /// accounts, are declared automatically, as they are part of the scenario by design:
+    ///
+    /// ```
+    /// use near_workspaces::types::NearToken;
+    ///
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let worker = near_workspaces::sandbox().await?;
+    /// worker.declare_signers([]);
+    ///
+    /// let alice = worker.dev_create_account().await?;
+    /// let bob = worker.dev_create_account().await?;
+    /// alice.transfer_near(bob.id(), NearToken::from_near(1)).await?;
+    ///
+    /// // The root account was not declared:
+    /// let root = worker.root_account()?;
+    /// assert!(root.transfer_near(bob.id(), NearToken::from_near(1)).await.is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn declare_signers(&self, accounts: impl IntoIterator<Item = AccountId>) {
+        if let Ok(mut allowed) = self.client().allowed_signers.write() {
+            allowed
+                .get_or_insert_with(Default::default)
+                .extend(accounts);
+        }
     }
 }
 
@@ -176,7 +347,6 @@ where
             .transfer_near(signer, receiver_id, amount_yocto)
             .await
             .map(ExecutionFinalResult::from_view)
-            .map_err(crate::error::Error::from)
     }
 
     /// Deletes an account from the network. The beneficiary will receive the balance
@@ -191,13 +361,509 @@ where
             .delete_account(signer, account_id, beneficiary_id)
             .await
             .map(ExecutionFinalResult::from_view)
-            .map_err(crate::error::Error::from)
     }
 
     /// Returns the status of the network.
     pub async fn status(&self) -> Result<StatusResponse> {
         self.client().status().await
     }
+
+    /// Discover the accounts referenced by the state of `contract_id`, such as the accounts
+    /// deployed by a factory contract. Only the state under the given `prefix` is scanned;
+    /// an empty prefix scans the whole state. Both keys (with the prefix stripped) and values
+    /// are considered, where an account ID can either be stored as raw UTF-8 bytes or as a
+    /// borsh serialized string.
+    ///
+    /// The returned accounts are deduplicated and sorted, and can be passed straight to
+    /// [`Worker::import_contracts`] to bring all of them into sandbox.
+    pub async fn accounts_in_state(
+        &self,
+        contract_id: &AccountId,
+        prefix: &[u8],
+    ) -> Result<Vec<AccountId>> {
+        let state = Query::new(self.client(), ViewState::new(contract_id))
+            .prefix(prefix)
+            .await?;
+
+        let mut accounts = BTreeSet::new();
+        for (key, value) in &state {
+            let key = key.strip_prefix(prefix).unwrap_or(key);
+            accounts.extend(parse_account_id(key));
+            accounts.extend(parse_account_id(value));
+        }
+
+        Ok(accounts.into_iter().collect())
+    }
+
+    /// Download the wasm code currently deployed to `account_id` into the directory `dir`,
+    /// such that tests can pin exactly what's deployed on a network. The file is named after
+    /// the account and the hash of its code, as in `<account_id>-<code_hash>.wasm`, and its
+    /// path gets returned.
+    pub async fn download_code(
+        &self,
+        account_id: &AccountId,
+        dir: impl AsRef<Path>,
+    ) -> Result<PathBuf> {
+        // View the account and its code at the same block, so the hash matches the code.
+        let block = Query::new(self.client(), ViewBlock).await?;
+        let account = Query::new(
+            self.client(),
+            ViewAccount {
+                account_id: account_id.clone(),
+            },
+        )
+        .block_hash(*block.hash())
+        .await?;
+        let code = Query::new(
+            self.client(),
+            ViewCode {
+                account_id: account_id.clone(),
+            },
+        )
+        .block_hash(*block.hash())
+        .await?;
+
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir).map_err(|e| ErrorKind::Io.custom(e))?;
+        let path = dir.join(format!("{}-{}.wasm", account_id, account.code_hash));
+        std::fs::write(&path, code)
+            .map_err(|e| ErrorKind::Io.full(format!("failed to write {:?}", path), e))?;
+
+        Ok(path)
+    }
+
+    /// Binary search the blocks within `from..=to` heights for the first one where
+    /// `predicate` holds, such as the first block where an account's balance reached some
+    /// amount. `predicate` has to be monotonic over the range: once it holds for a block, it
+    /// has to hold for every block after it as well. Returns `None` if it holds for none of
+    /// the blocks. Heights without a block, such as ones skipped by the network, are passed
+    /// over.
+    ///
+    /// The [`Block`] is handed to `predicate`, so that it can query the state at that
+    /// block with [`Query::block_hash`]. Note that only archival networks have the full
+    /// history of the chain.
+    pub async fn find_block_where<F, Fut>(
+        &self,
+        from: BlockHeight,
+        to: BlockHeight,
+        mut predicate: F,
+    ) -> Result<Option<Block>>
+    where
+        F: FnMut(Block) -> Fut,
+        Fut: Future<Output = Result<bool>>,
+    {
+        let (mut lo, mut hi) = (from, to);
+        let mut found = None;
+        while lo <= hi {
+            let mid = lo + (hi - lo) / 2;
+
+            // Take the first block at or after `mid`, skipping the heights without one.
+            let mut block = None;
+            for height in mid..=hi {
+                block = self.block_at(height).await?;
+                if block.is_some() {
+                    break;
+                }
+            }
+            let Some(block) = block else {
+                // No blocks left in the upper half of the range.
+                if mid == 0 {
+                    break;
+                }
+                hi = mid - 1;
+                continue;
+            };
+
+            let height = block.height();
+            if predicate(block.clone()).await? {
+                found = Some(block);
+                if mid == 0 {
+                    break;
+                }
+                hi = mid - 1;
+            } else {
+                lo = height + 1;
+            }
+        }
+
+        Ok(found)
+    }
+
+    /// Sample the balance of `account_id` every `step` blocks within `from..=to` heights,
+    /// returning the series of block heights along with the balance at each of them. Heights
+    /// without a block, such as ones skipped by the network, are left out of the series.
+    ///
+    /// Note that only archival networks have the full history of the chain.
+    pub async fn balance_history(
+        &self,
+        account_id: &AccountId,
+        from: BlockHeight,
+        to: BlockHeight,
+        step: BlockHeight,
+    ) -> Result<Vec<(BlockHeight, NearToken)>> {
+        if step == 0 {
+            return Err(ErrorKind::Other.message("balance history step has to be non-zero"));
+        }
+
+        let mut history = Vec::new();
+        for height in (from..=to).step_by(step as usize) {
+            let Some(block) = self.block_at(height).await? else {
+                continue;
+            };
+            let account = Query::new(
+                self.client(),
+                ViewAccount {
+                    account_id: account_id.clone(),
+                },
+            )
+            .block_hash(*block.hash())
+            .await?;
+            history.push((height, account.balance));
+        }
+
+        Ok(history)
+    }
+
+    /// Poll the state of `contract_id` until a value stored under `key_prefix` satisfies
+    /// `predicate`, returning that value. This is useful for waiting on state that gets
+    /// updated by asynchronous receipts, instead of sleeping for an arbitrary amount of time.
+    /// Errors out with [`ErrorKind::Timeout`] if no such value shows up within `timeout`.
+    pub async fn wait_for_state<F>(
+        &self,
+        contract_id: &AccountId,
+        key_prefix: &[u8],
+        predicate: F,
+        timeout: std::time::Duration,
+    ) -> Result<Vec<u8>>
+    where
+        F: Fn(&[u8]) -> bool,
+    {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let state = self.view_state(contract_id).prefix(key_prefix).await?;
+            if let Some(value) = state.into_values().find(|value| predicate(value)) {
+                return Ok(value);
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                return Err(ErrorKind::Timeout.message(format!(
+                    "state of `{}` did not satisfy the predicate within {:?}",
+                    contract_id, timeout
+                )));
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        }
+    }
+
+    /// Get every function call made by the receipts of an executed transaction, such as the
+    /// cross contract calls made by the contracts it called into, along with the deposit and
+    /// gas attached to each. This allows asserting that a contract forwards exactly the
+    /// intended deposit to the contracts downstream. The function calls of the transaction
+    /// itself are not included.
+    pub async fn function_call_receipts(
+        &self,
+        result: &ExecutionFinalResult,
+    ) -> Result<Vec<FunctionCallReceipt>> {
+        Ok(self
+            .receipts_of(result)
+            .await?
+            .into_iter()
+            .flat_map(FunctionCallReceipt::from_view)
+            .collect())
+    }
+
+    /// Reconstruct the tree of receipts an executed transaction spawned, with the actions
+    /// each receipt carried along with how its execution went. Where the outcomes of a
+    /// transaction are a flat list, the tree shows which contract called into which, such
+    /// that a failure deep in a chain of cross contract calls can be traced back hop by hop.
+    /// Look at [`ReceiptTree`] for more info.
+    pub async fn receipt_tree(&self, result: &ExecutionFinalResult) -> Result<ReceiptTree> {
+        let receipts = self.receipts_of(result).await?;
+        Ok(ReceiptTree::new(receipts, result))
+    }
+
+    /// All the receipts of an executed transaction, which unlike its outcomes carry the
+    /// actions of each receipt and the account that created it.
+    async fn receipts_of(&self, result: &ExecutionFinalResult) -> Result<Vec<ReceiptView>> {
+        let transaction = result.outcome();
+        let resp = self
+            .client()
+            .query(
+                methods::EXPERIMENTAL_tx_status::RpcTransactionStatusRequest {
+                    transaction_info: methods::tx::TransactionInfo::TransactionId {
+                        tx_hash: near_primitives::hash::CryptoHash(transaction.transaction_hash.0),
+                        sender_account_id: transaction.executor_id.clone(),
+                    },
+                    wait_until: near_primitives::views::TxExecutionStatus::ExecutedOptimistic,
+                },
+            )
+            .await
+            .map_err(|e| RpcErrorCode::QueryFailure.custom(e))?;
+
+        match resp.final_execution_outcome {
+            Some(FinalExecutionOutcomeViewEnum::FinalExecutionOutcomeWithReceipt(outcome)) => {
+                Ok(outcome.receipts)
+            }
+            _ => Err(RpcErrorCode::QueryReturnedInvalidData
+                .message("while querying the receipts of a transaction")),
+        }
+    }
+
+    /// Report the gas attached to every function call made by the receipts of an executed
+    /// transaction against the gas they burnt, such as to find cross contract calls that
+    /// attach far more gas than they need. Look at [`GasReport`] for more info.
+    pub async fn gas_report(&self, result: &ExecutionFinalResult) -> Result<GasReport> {
+        let calls = self.function_call_receipts(result).await?;
+        Ok(GasReport::new(calls, result))
+    }
+
+    /// Report the storage written and deleted by every receipt calling into a contract in the
+    /// call trees of the executed transactions `results`, such as to catch methods whose
+    /// storage usage grows by more than expected per call. Look at [`StorageReport`] for
+    /// more info.
+    pub async fn storage_report(&self, results: &[ExecutionFinalResult]) -> Result<StorageReport> {
+        let mut receipts = Vec::new();
+        for result in results {
+            // The function calls of the transaction itself are made by its first receipt, on
+            // top of the ones made by the receipts further down its call tree:
+            let mut calls = Vec::<(CryptoHash, Vec<String>)>::new();
+            if let Some(receipt_id) = result.outcome().receipt_ids.first() {
+                if !result.method_names.is_empty() {
+                    calls.push((*receipt_id, result.method_names.clone()));
+                }
+            }
+            for call in self.function_call_receipts(result).await? {
+                match calls.last_mut() {
+                    Some((receipt_id, method_names)) if *receipt_id == call.receipt_id => {
+                        method_names.push(call.method_name)
+                    }
+                    _ => calls.push((call.receipt_id, vec![call.method_name])),
+                }
+            }
+
+            for (receipt_id, method_names) in calls {
+                let Some(outcome) = result
+                    .receipt_outcomes()
+                    .iter()
+                    .find(|outcome| outcome.transaction_hash == receipt_id)
+                else {
+                    continue;
+                };
+                let (bytes_written, bytes_deleted, storage_growth) = self
+                    .receipt_storage_changes(outcome.block_hash, receipt_id, &outcome.executor_id)
+                    .await?;
+                receipts.push(ReceiptStorageUsage {
+                    receipt_id,
+                    account_id: outcome.executor_id.clone(),
+                    method_names,
+                    bytes_written,
+                    bytes_deleted,
+                    storage_growth,
+                });
+            }
+        }
+        Ok(StorageReport { receipts })
+    }
+
+    /// The bytes written and deleted by the receipt `receipt_id` executed on `account_id` in
+    /// the block `block_hash`, along with how much the storage usage of the account grew by.
+    async fn receipt_storage_changes(
+        &self,
+        block_hash: CryptoHash,
+        receipt_id: CryptoHash,
+        account_id: &AccountId,
+    ) -> Result<(u64, u64, i64)> {
+        let data = self
+            .client()
+            .state_changes_in_block(
+                block_hash,
+                StateChangesRequestView::DataChanges {
+                    account_ids: vec![account_id.clone()],
+                    key_prefix: Vec::new().into(),
+                },
+            )
+            .await?;
+        let (mut bytes_written, mut bytes_deleted) = (0, 0);
+        for change in data {
+            if tool::receipt_of(&change.cause) != Some(receipt_id) {
+                continue;
+            }
+            match change.value {
+                StateChangeValueView::DataUpdate { key, value, .. } => {
+                    bytes_written += (key.len() + value.len()) as u64;
+                }
+                StateChangeValueView::DataDeletion { key, .. } => {
+                    bytes_deleted += key.len() as u64;
+                }
+                _ => {}
+            }
+        }
+
+        let usages = self
+            .client()
+            .state_changes_in_block(
+                block_hash,
+                StateChangesRequestView::AccountChanges {
+                    account_ids: vec![account_id.clone()],
+                },
+            )
+            .await?
+            .into_iter()
+            .filter_map(|change| match change.value {
+                StateChangeValueView::AccountUpdate { account, .. } => {
+                    Some((tool::receipt_of(&change.cause), account.storage_usage))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let caused = |(cause, _): &(Option<CryptoHash>, u64)| *cause == Some(receipt_id);
+        let (Some(first), Some(last)) = (
+            usages.iter().position(caused),
+            usages.iter().rposition(caused),
+        ) else {
+            return Ok((bytes_written, bytes_deleted, 0));
+        };
+
+        // The usage before the receipt is the one left by the change preceding it within the
+        // block, or the one at the end of the previous block if there is none:
+        let before = match first.checked_sub(1) {
+            Some(previous) => usages[previous].1,
+            None => {
+                let block = self
+                    .client()
+                    .view_block(Some(BlockReference::BlockId(BlockId::Hash(
+                        near_primitives::hash::CryptoHash(block_hash.0),
+                    ))))
+                    .await?;
+                self.view_account(account_id)
+                    .block_hash(CryptoHash(block.header.prev_hash.0))
+                    .await?
+                    .storage_usage
+            }
+        };
+        let storage_growth = usages[last].1 as i64 - before as i64;
+        Ok((bytes_written, bytes_deleted, storage_growth))
+    }
+
+    /// Summarize the state of all the accounts known to this worker, being the ones that
+    /// signed or received transactions sent through it, along with the [labeled] ones. This
+    /// is handy to print at the end of a failing scenario, to get the state of the world at
+    /// a glance. Look at [`WorldSummary`] for more info.
+    ///
+    /// [labeled]: Worker::label
+    pub async fn summarize(&self) -> Result<WorldSummary> {
+        let mut account_ids = self
+            .client()
+            .known_accounts
+            .lock()
+            .map(|known| known.clone())
+            .unwrap_or_default();
+        if let Ok(labels) = self.client().labels.read() {
+            account_ids.extend(labels.keys().cloned());
+        }
+
+        let mut accounts = Vec::with_capacity(account_ids.len());
+        for account_id in account_ids {
+            let details = match self.view_account(&account_id).await {
+                Ok(details) => Some(details),
+                Err(err) if tool::is_unknown_account(&err) => None,
+                Err(err) => return Err(err),
+            };
+            accounts.push(AccountSummary {
+                label: self.label_of(&account_id),
+                account_id,
+                details,
+            });
+        }
+        Ok(WorldSummary { accounts })
+    }
+
+    /// Call into a view function returning a list of items page by page, following the
+    /// convention of `function` taking a `from_index` and `limit` pair of numeric arguments
+    /// along with the given `args`, and returning at most `limit` items. Pages of `page_size`
+    /// items are requested until one comes back short. This allows testing contracts
+    /// returning lists too big to be viewed at once.
+    ///
+    /// Pages exceeding the limits of the node are retried with half the page size, until a
+    /// single item per page still doesn't fit. Note that the pages are viewed at different
+    /// blocks, unless this gets called from within [`Worker::consistent_view`].
+    pub async fn view_paginated<R: serde::de::DeserializeOwned>(
+        &self,
+        contract_id: &AccountId,
+        function: &str,
+        args: serde_json::Value,
+        page_size: u64,
+    ) -> Result<Vec<R>> {
+        let mut args = match args {
+            serde_json::Value::Object(args) => args,
+            serde_json::Value::Null => serde_json::Map::new(),
+            _ => {
+                return Err(ErrorKind::DataConversion
+                    .message("paginated view arguments have to be a JSON object"))
+            }
+        };
+        if page_size == 0 {
+            return Err(ErrorKind::Other.message("paginated view page size has to be non-zero"));
+        }
+
+        let mut items = Vec::new();
+        let mut limit = page_size;
+        loop {
+            args.insert("from_index".into(), items.len().into());
+            args.insert("limit".into(), limit.into());
+            let page = match self.view(contract_id, function).args_json(&args).await {
+                Ok(page) => page.json::<Vec<R>>()?,
+                Err(err) if err.kind() == &RpcErrorCode::QueryResultTooLarge.into() => {
+                    if limit == 1 {
+                        return Err(RpcErrorCode::QueryResultTooLarge.full(
+                            format!(
+                                "a single item returned by `{}` on `{}` is too large to be viewed",
+                                function, contract_id
+                            ),
+                            err,
+                        ));
+                    }
+                    limit /= 2;
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            let done = (page.len() as u64) < limit;
+            items.extend(page);
+            if done {
+                return Ok(items);
+            }
+        }
+    }
+
+    /// View the block at the given height, or `None` if there is no block at it.
+    async fn block_at(&self, height: BlockHeight) -> Result<Option<Block>> {
+        let resp = self
+            .client()
+            .query(methods::block::RpcBlockRequest {
+                block_reference: BlockId::Height(height).into(),
+            })
+            .await;
+
+        match resp {
+            Ok(view) => Ok(Some(view.into())),
+            Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+                RpcBlockError::UnknownBlock { .. },
+            ))) => Ok(None),
+            Err(err) => Err(RpcErrorCode::QueryFailure.custom(err)),
+        }
+    }
+
+    /// Number of times a transaction sent through this worker expired or got lost before
+    /// being executed, and had to be re-signed with a fresh block hash and broadcasted again.
+    /// The nonce is kept the same when doing so, such that at most one of the signed
+    /// transactions can ever be executed.
+    pub fn rebroadcasted_transactions(&self) -> u64 {
+        self.client()
+            .rebroadcasts
+            .load(std::sync::atomic::Ordering::SeqCst)
+    }
 }
 
 #[cfg(feature = "experimental")]
@@ -237,6 +903,14 @@ where
         self.client().protocol_config(block_reference).await
     }
 
+    /// Create a [`FeeCalculator`] from the latest protocol config and gas price of the
+    /// network, for calculating the exact fees of actions.
+    pub async fn fee_calculator(&self) -> Result<FeeCalculator> {
+        let config = self.protocol_config(Finality::Final.into()).await?;
+        let gas_price = self.gas_price().await?;
+        Ok(FeeCalculator::new(&config, gas_price))
+    }
+
     /// Provides a receipt associated with the given receipt reference.
     pub async fn receipt(&self, receipt_reference: ReceiptReference) -> Result<ReceiptView> {
         self.client().receipt(receipt_reference).await
@@ -282,6 +956,95 @@ where
             function,
         )
     }
+
+    /// Wait for a [NEP-297] event of the given `standard` and `event` name that also satisfies
+    /// `predicate` to be emitted by any contract. Only outcomes of transactions included in
+    /// blocks produced after awaiting the returned [`WaitForEvent`] are looked at. This is
+    /// useful for testing pipelines that span multiple transactions, such as a relayer picking
+    /// up and finishing a job:
+    /// ```no_run
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let worker = near_workspaces::sandbox().await?;
+    /// let event = worker
+    ///     .wait_for_event("nep141", "ft_transfer", |event| event.data.is_some())
+    ///     .timeout(std::time::Duration::from_secs(30))
+    ///     .await?;
+    /// println!("{} emitted {:?}", event.emitter_id, event.data);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [NEP-297]: https://nomicon.io/Standards/EventsFormat
+    pub fn wait_for_event<'a>(
+        &self,
+        standard: &str,
+        event: &str,
+        predicate: impl Fn(&Event) -> bool + Send + Sync + 'a,
+    ) -> WaitForEvent<'a> {
+        WaitForEvent::new(self.clone().coerce(), standard, event, predicate)
+    }
+
+    /// Look up a transaction by its hash and the account that signed it, such as one sent
+    /// with [`transact_async`] or by tooling outside of workspaces. The returned
+    /// [`TransactionStatus`] can be checked with [`status`], or `.await`ed to wait on the
+    /// final execution outcome of the transaction.
+    ///
+    /// [`transact_async`]: crate::operations::Transaction::transact_async
+    /// [`status`]: TransactionStatus::status
+    pub fn transaction_status(
+        &self,
+        tx_hash: CryptoHash,
+        sender_id: &AccountId,
+    ) -> TransactionStatus {
+        TransactionStatus::new(
+            self.clone().coerce(),
+            sender_id.clone(),
+            near_primitives::hash::CryptoHash(tx_hash.0),
+        )
+    }
+
+    /// Take the transactions that were detached by dropping the future sending them, such as
+    /// a `transact()` cancelled by a `select!` or a timeout, before its outcome came back.
+    /// These transactions might still get executed afterwards, so a cleanup step can use the
+    /// returned statuses to wait on them instead of them affecting later assertions. Once
+    /// taken, transactions are no longer returned by later calls.
+    pub fn take_detached_transactions(&self) -> Vec<TransactionStatus> {
+        let detached = self
+            .client()
+            .detached_txs
+            .lock()
+            .map(|mut detached| std::mem::take(&mut *detached))
+            .unwrap_or_default();
+
+        detached
+            .into_iter()
+            .map(|(hash, sender_id)| TransactionStatus::new(self.clone().coerce(), sender_id, hash))
+            .collect()
+    }
+
+    /// Wait on all the [detached transactions] to be executed, returning their outcomes.
+    /// A transaction dropped before it made it to the network never gets executed, so this
+    /// gives up with an [`ErrorKind::Timeout`] error after `timeout`.
+    ///
+    /// [detached transactions]: Worker::take_detached_transactions
+    pub async fn wait_for_detached_transactions(
+        &self,
+        timeout: std::time::Duration,
+    ) -> Result<Vec<ExecutionFinalResult>> {
+        let detached = self.take_detached_transactions();
+        let wait = async {
+            let mut outcomes = Vec::with_capacity(detached.len());
+            for tx in detached {
+                outcomes.push(tx.wait().await?);
+            }
+            Ok(outcomes)
+        };
+        tokio::time::timeout(timeout, wait).await.map_err(|_| {
+            ErrorKind::Timeout.message(format!(
+                "detached transactions were not executed within {timeout:?}"
+            ))
+        })?
+    }
 }
 
 impl Worker<Testnet> {
@@ -320,6 +1083,24 @@ impl Worker<Sandbox> {
         ImportContractTransaction::new(id, worker.clone().coerce(), self.clone())
     }
 
+    /// Import many contracts at once from the given network, and return us a
+    /// [`ImportContractsTransaction`] which allows to specify further details just like
+    /// [`import_contract`]. The contracts are imported concurrently. To discover the accounts
+    /// to import, such as the ones created by a factory, look at [`Worker::accounts_in_state`].
+    ///
+    /// [`import_contract`]: Worker::import_contract
+    pub fn import_contracts(
+        &self,
+        ids: impl IntoIterator<Item = AccountId>,
+        worker: &Worker<impl Network + 'static>,
+    ) -> ImportContractsTransaction {
+        ImportContractsTransaction::new(
+            ids.into_iter().collect(),
+            worker.clone().coerce(),
+            self.clone(),
+        )
+    }
+
     /// Start patching the state of the account specified by the [`AccountId`]. This will create
     /// a [`PatchTransaction`] that will allow us to patch access keys, code, and contract state.
     /// This is similar to functions like [`Account::batch`] where we can perform multiple actions
@@ -328,6 +1109,51 @@ impl Worker<Sandbox> {
         PatchTransaction::new(self, account_id.clone())
     }
 
+    /// Start patching the state of many accounts at once, which will all be sent to sandbox
+    /// in a single request once [`BatchPatchTransaction::transact`] gets called. Look at
+    /// [`BatchPatchTransaction`] for how to add the patches of each account.
+    pub fn batch_patch(&self) -> BatchPatchTransaction {
+        BatchPatchTransaction::new(self)
+    }
+
+    /// Patch the details of an existing account in the sandbox, such as its balance, locked
+    /// amount or storage usage. Only the fields set in the [`AccountDetailsPatch`] are
+    /// overwritten, while the rest keep their current values on chain. This is handy for
+    /// setting up an exact balance without going through a sequence of transfers.
+    pub async fn patch_account(
+        &self,
+        account_id: &AccountId,
+        patch: AccountDetailsPatch,
+    ) -> Result<()> {
+        let mut account = AccountDetailsPatch::from(self.view_account(account_id).await?);
+        account.reduce(patch);
+        self.patch(account_id).account(account).transact().await
+    }
+
+    /// Add or overwrite an access key of an account in the sandbox. This allows us to take
+    /// control of accounts we don't own any keys of, such as ones imported from mainnet, by
+    /// giving them a key we do own. Use [`Account::from_secret_key`] afterwards to sign
+    /// transactions as that account.
+    pub async fn patch_access_key(
+        &self,
+        account_id: &AccountId,
+        public_key: PublicKey,
+        access_key: AccessKey,
+    ) -> Result<()> {
+        self.patch(account_id)
+            .access_key(public_key, access_key)
+            .transact()
+            .await
+    }
+
+    /// Swap the code of an account in the sandbox for `wasm`, without sending a deploy
+    /// transaction or owning any of the keys of the account. The state of the contract is
+    /// left untouched, which allows testing how a contract imported from mainnet behaves
+    /// when upgraded to a local build.
+    pub async fn patch_code(&self, account_id: &AccountId, wasm: &[u8]) -> Result<()> {
+        self.patch(account_id).code(wasm).transact().await
+    }
+
     /// Patch state into the sandbox network, given a prefix key and value. This will allow us
     /// to set contract state that we have acquired in some manner, where we are able to test
     /// random cases that are hard to come up naturally as state evolves.
@@ -340,6 +1166,82 @@ impl Worker<Sandbox> {
         self.workspace.patch_state(contract_id, key, value).await
     }
 
+    /// Remove a key from the state of a contract in sandbox, which patching state is not able
+    /// to do. This allows simulating storage removals, or state with missing keys.
+    ///
+    /// Look at [`Worker::delete_states`] for how the key gets removed.
+    pub async fn delete_state(&self, contract_id: &AccountId, key: &[u8]) -> Result<()> {
+        self.delete_states(contract_id, [key]).await
+    }
+
+    /// Remove many keys from the state of a contract in sandbox at once. This works by
+    /// temporarily swapping the code of the contract for a small one which removes the keys
+    /// when called by the root account, and patching the original code back afterwards.
+    pub async fn delete_states<'a>(
+        &self,
+        contract_id: &AccountId,
+        keys: impl IntoIterator<Item = &'a [u8]>,
+    ) -> Result<()> {
+        const STATE_CLEANUP_WASM: &[u8] = include_bytes!("../../res/state_cleanup.wasm");
+        const KEYS_PER_CALL: usize = 1000;
+
+        let keys: Vec<Vec<u8>> = keys.into_iter().map(<[u8]>::to_vec).collect();
+        let code_hash = self.view_account(contract_id).await?.code_hash;
+        let code = if code_hash == CryptoHash::default() {
+            None
+        } else {
+            Some(self.view_code(contract_id).await?)
+        };
+
+        self.patch_code(contract_id, STATE_CLEANUP_WASM).await?;
+        let root = self.root_account()?;
+        let mut removed = Ok(());
+        for keys in keys.chunks(KEYS_PER_CALL) {
+            removed = root
+                .call(contract_id, "clean")
+                .args_borsh(keys)
+                .max_gas()
+                .transact()
+                .await
+                .and_then(|result| result.into_result().map(drop).map_err(Into::into));
+            if removed.is_err() {
+                break;
+            }
+        }
+
+        // Restore the original code, even if removing the keys failed.
+        match code {
+            Some(code) => self.patch_code(contract_id, &code).await?,
+            None => {
+                self.patch_account(
+                    contract_id,
+                    AccountDetailsPatch::default().code_hash(CryptoHash::default()),
+                )
+                .await?
+            }
+        }
+        removed
+    }
+
+    /// Export the accounts, access keys, contract code and contract state of this sandbox at
+    /// the current head of the chain. The [`StateDump`] can be saved as a fixture, compared
+    /// against another dump taken later on, or loaded into another sandbox with
+    /// [`Worker::load_state`].
+    ///
+    /// Since accounts cannot be listed through RPC, the accounts in the dump are the ones in
+    /// genesis along with the ones touched by any block still kept by the node. Accounts only
+    /// touched in blocks that the node already garbage collected, which can happen after
+    /// fast forwarding over many epochs, are left out.
+    pub async fn dump_state(&self) -> Result<StateDump> {
+        StateDump::capture(self).await
+    }
+
+    /// Patch every record of a [`StateDump`] into this sandbox, overwriting the accounts
+    /// contained in it.
+    pub async fn load_state(&self, dump: &StateDump) -> Result<()> {
+        dump.restore(self).await
+    }
+
     /// Fast forward to a point in the future. The delta block height is supplied to tell the
     /// network to advanced a certain amount of blocks. This comes with the advantage only having
     /// to wait a fraction of the time it takes to produce the same number of blocks.
@@ -353,13 +1255,123 @@ impl Worker<Sandbox> {
         self.workspace.fast_forward(delta_height).await
     }
 
+    /// Fast forward to the start of the next epoch, such as to test staking, rewards or
+    /// anything else that only happens at epoch boundaries, without hard-coding the length of
+    /// epochs. Look at [`Worker::fast_forward`] for more info.
+    pub async fn fast_forward_to_next_epoch(&self) -> Result<()> {
+        let epoch_height = self.validators().await?.epoch_height;
+        self.fast_forward(self.blocks_until_epoch_end().await?.max(1))
+            .await?;
+        // Make up for the few blocks epochs can go on for after their expected end:
+        while self.validators().await?.epoch_height == epoch_height {
+            self.fast_forward(1).await?;
+        }
+        Ok(())
+    }
+
+    /// Capture the full chain state of this sandbox, returning a [`SnapshotId`] that can later
+    /// be passed to [`Worker::restore`] to roll the chain back to this point. This is useful for
+    /// setting up an expensive fixture once and resetting to it between test cases.
+    ///
+    /// The sandbox node gets briefly stopped while its data directory is being copied, so this
+    /// is only available for sandboxes spawned by workspaces and not ones we connect to.
+    pub async fn snapshot(&self) -> Result<SnapshotId> {
+        self.workspace.snapshot().await
+    }
+
+    /// Roll back the chain state of this sandbox to a snapshot previously taken with
+    /// [`Worker::snapshot`]. Everything that happened after the snapshot, including blocks
+    /// produced, is discarded.
+    pub async fn restore(&self, id: SnapshotId) -> Result<()> {
+        self.workspace.restore(id).await
+    }
+
     /// The port being used by RPC
     pub fn rpc_port(&self) -> Option<u16> {
         self.workspace.server.rpc_port()
     }
 
+    /// Stop the sandbox node and remove its home directory, unless it got spawned with
+    /// `sandbox().keep_files(true)`. This otherwise happens once the last clone of this worker
+    /// is dropped, and is a no-op for sandboxes that were not spawned by workspaces. The
+    /// worker can no longer be used to talk to the node afterwards.
+    pub async fn shutdown(&self) -> Result<()> {
+        self.workspace.server.shutdown().await
+    }
+
+    /// The latest lines of output of the sandbox node, if it got spawned with logs captured
+    /// through `sandbox().capture_logs(true)`. Otherwise, the output goes straight to the
+    /// output of the test, so nothing is returned.
+    pub fn sandbox_logs(&self) -> Vec<String> {
+        self.workspace
+            .server
+            .logs
+            .as_ref()
+            .map(|logs| logs.lines())
+            .unwrap_or_default()
+    }
+
+    /// The port being used by the node for networking, if it got spawned by workspaces.
+    /// Ports are locked while a sandbox starts up, so sandboxes spawned in parallel, even
+    /// from separate test processes, never end up on the same ports.
+    pub fn net_port(&self) -> Option<u16> {
+        self.workspace.server.net_port()
+    }
+
+    /// The home directory holding the chain data and configuration of the node. Every
+    /// sandbox spawned by workspaces gets a fresh one. This is `None` when connected to a
+    /// node through a known validator key instead of its home directory.
+    pub fn home_dir(&self) -> Option<&Path> {
+        match &self.workspace.server.validator_key {
+            ValidatorKey::HomeDir(home_dir) => Some(home_dir),
+            ValidatorKey::Known(..) => None,
+        }
+    }
+
     /// Get the address the client is using to connect to the RPC of the network.
     pub fn rpc_addr(&self) -> String {
         self.workspace.server.rpc_addr()
     }
 }
+
+impl Worker<MockNetwork> {
+    /// Respond to every RPC request for `method` with `result`, such as `"block"` or `"query"`.
+    /// Fixtures registered later take precedence over earlier ones.
+    ///
+    /// ```
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let worker = near_workspaces::mock().await?;
+    /// worker.respond("gas_price", serde_json::json!({ "gas_price": "100000000" }));
+    /// assert_eq!(worker.gas_price().await?.as_yoctonear(), 100_000_000);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn respond(&self, method: &str, result: serde_json::Value) {
+        self.workspace.add_fixture(method, None, Ok(result));
+    }
+
+    /// Respond to RPC requests for `method` with `result` only when the request params
+    /// contain `params`. Objects in `params` only need to be a subset of the request params,
+    /// so for example `{"request_type": "view_account", "account_id": "alice.near"}` will
+    /// match the view account query of `alice.near` at any block.
+    pub fn respond_to(&self, method: &str, params: serde_json::Value, result: serde_json::Value) {
+        self.workspace.add_fixture(method, Some(params), Ok(result));
+    }
+
+    /// Respond to RPC requests for `method` with a JSON-RPC `error` object, such as
+    /// `{"code": -32000, "message": "Server error", "name": "HANDLER_ERROR", "cause": {...}}`.
+    pub fn respond_error(&self, method: &str, error: serde_json::Value) {
+        self.workspace.add_fixture(method, None, Err(error));
+    }
+}
+
+/// Parse an account ID stored either as raw UTF-8 bytes or as a borsh serialized string.
+fn parse_account_id(bytes: &[u8]) -> Option<AccountId> {
+    let parse = |bytes: &[u8]| std::str::from_utf8(bytes).ok()?.parse().ok();
+    match bytes {
+        [a, b, c, d, rest @ ..] if u32::from_le_bytes([*a, *b, *c, *d]) as usize == rest.len() => {
+            parse(rest)
+        }
+        _ => parse(bytes),
+    }
+}
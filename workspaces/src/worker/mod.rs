@@ -1,13 +1,29 @@
 mod impls;
 
 use std::fmt;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::path::Path;
 use std::sync::Arc;
 
+use near_primitives::types::BlockReference;
+
 use crate::network::builder::NetworkBuilder;
-use crate::network::{Betanet, Custom, Mainnet, Sandbox, Testnet};
-use crate::types::gas_meter::GasHook;
+use crate::network::ValidatorKey;
+use crate::network::{Betanet, Custom, Mainnet, MockNetwork, Sandbox, Testnet};
+use crate::result::ExecutionFinalResult;
+use crate::types::KeyType;
 use crate::{Network, Result};
 
+/// A hook that is called on every transaction that is sent to the network.
+/// This is useful for debugging purposes, or for tracking the amount of gas
+/// that is being used.
+///
+/// The auto-traits [`Send`], [`Sync`], [`UnwindSafe`] and [`RefUnwindSafe`] are added explicitly because they
+/// do not fall under the rules the compiler uses to automatically add them.
+/// See here: <https://doc.rust-lang.org/reference/special-types-and-traits.html#auto-traits>
+pub(crate) type TxHook =
+    Arc<dyn Fn(&ExecutionFinalResult) -> Result<()> + Send + Sync + UnwindSafe + RefUnwindSafe>;
+
 /// The `Worker` type allows us to interact with any NEAR related networks,
 /// such as mainnet and testnet.
 ///
@@ -16,7 +32,9 @@ use crate::{Network, Result};
 /// deploying a contract, or interacting with transactions.
 pub struct Worker<T: ?Sized> {
     pub(crate) workspace: Arc<T>,
-    pub(crate) tx_callbacks: Vec<GasHook>,
+    pub(crate) tx_callbacks: Vec<TxHook>,
+    pub(crate) pinned_block: Option<BlockReference>,
+    pub(crate) dev_key_type: KeyType,
 }
 
 impl<T> Worker<T>
@@ -27,6 +45,8 @@ where
         Self {
             workspace: Arc::new(network),
             tx_callbacks: vec![],
+            pinned_block: None,
+            dev_key_type: KeyType::ED25519,
         }
     }
 }
@@ -36,6 +56,8 @@ impl<T: Network + 'static> Worker<T> {
         Worker {
             workspace: self.workspace,
             tx_callbacks: self.tx_callbacks,
+            pinned_block: self.pinned_block,
+            dev_key_type: self.dev_key_type,
         }
     }
 }
@@ -53,11 +75,22 @@ pub fn sandbox<'a>() -> NetworkBuilder<'a, Sandbox> {
     NetworkBuilder::new("sandbox")
 }
 
-/// Spin up a new sandbox instance, and grab a [`Worker`] that interacts with it.
-pub async fn sandbox_with_version<'a>(version: &str) -> Result<Worker<Sandbox>> {
-    let network_builder = NetworkBuilder::new("sandbox");
-    let network = Sandbox::from_builder_with_version(network_builder, version).await?;
-    Ok(Worker::new(network))
+/// Spin up a new sandbox instance running the given `version` of the sandbox node, and
+/// grab a [`Worker`] that interacts with it. This is the same as `sandbox().version(version)`.
+pub async fn sandbox_with_version(version: &str) -> Result<Worker<Sandbox>> {
+    sandbox().version(version).await
+}
+
+/// Connect to a sandbox node that's already running on `rpc_port` of localhost, such as a
+/// long-lived node used for debugging or one shared across test binaries, and grab a
+/// [`Worker`] that interacts with it. `home_dir` is the directory the node got initialized
+/// with through `near-sandbox --home {home_dir} init`, which holds its validator key. The
+/// node is left running once the worker gets dropped.
+pub async fn sandbox_attach(rpc_port: u16, home_dir: impl AsRef<Path>) -> Result<Worker<Sandbox>> {
+    sandbox()
+        .rpc_addr(&format!("http://localhost:{}", rpc_port))
+        .validator_key(ValidatorKey::HomeDir(home_dir.as_ref().into()))
+        .await
 }
 
 /// Connect to the [testnet](https://explorer.testnet.near.org/) network, and grab
@@ -89,6 +122,12 @@ pub fn betanet<'a>() -> NetworkBuilder<'a, Betanet> {
     NetworkBuilder::new("betanet")
 }
 
+/// Spin up an offline [`MockNetwork`], and grab a [`Worker`] that interacts with it. All RPC
+/// requests are answered from the fixtures registered through [`Worker::respond`].
+pub fn mock<'a>() -> NetworkBuilder<'a, MockNetwork> {
+    NetworkBuilder::new("mock")
+}
+
 /// Connect to a custom network, and grab a [`Worker`] that can interact with it.
 ///
 /// Note: the burden of ensuring the methods that are able to be called are left up to the user.
@@ -0,0 +1,60 @@
+//! A contract without any dependencies exposing a single `clean` method, which removes the
+//! keys given to it as a borsh serialized `Vec<Vec<u8>>` from the state of the contract.
+
+#![no_std]
+
+use core::arch::wasm32;
+
+const INPUT_REGISTER: u64 = 0;
+const EVICTED_REGISTER: u64 = u64::MAX - 1;
+const PAGE_SIZE: usize = 64 * 1024;
+
+#[link(wasm_import_module = "env")]
+extern "C" {
+    fn input(register_id: u64);
+    fn register_len(register_id: u64) -> u64;
+    fn read_register(register_id: u64, ptr: u64);
+    fn storage_remove(key_len: u64, key_ptr: u64, register_id: u64) -> u64;
+}
+
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    wasm32::unreachable()
+}
+
+/// Read the little endian `u32` at `pos` of `data`.
+fn read_u32(data: &[u8], pos: usize) -> u32 {
+    match data.get(pos..pos + 4) {
+        Some(bytes) => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        None => wasm32::unreachable(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn clean() {
+    unsafe {
+        input(INPUT_REGISTER);
+        let len = register_len(INPUT_REGISTER) as usize;
+
+        // There is no allocator, so grow the memory to make room for the input instead.
+        let pages = len.div_ceil(PAGE_SIZE);
+        let base = match wasm32::memory_grow(0, pages) {
+            usize::MAX => wasm32::unreachable(),
+            prev => prev * PAGE_SIZE,
+        };
+        read_register(INPUT_REGISTER, base as u64);
+        let data = core::slice::from_raw_parts(base as *const u8, len);
+
+        let count = read_u32(data, 0);
+        let mut pos = 4;
+        for _ in 0..count {
+            let key_len = read_u32(data, pos) as usize;
+            pos += 4;
+            if data.len() < pos + key_len {
+                wasm32::unreachable();
+            }
+            storage_remove(key_len as u64, (base + pos) as u64, EVICTED_REGISTER);
+            pos += key_len;
+        }
+    }
+}
@@ -67,6 +67,89 @@ async fn test_transfer_near() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test(tokio::test)]
+async fn test_secp256k1_accounts() -> anyhow::Result<()> {
+    use near_workspaces::types::KeyType;
+
+    let mut worker = near_workspaces::sandbox().await?;
+    worker.set_dev_key_type(KeyType::SECP256K1);
+
+    let alice = worker.dev_create_account().await?;
+    assert_eq!(alice.secret_key().key_type(), KeyType::SECP256K1);
+    let sub = alice
+        .create_subaccount("sub")
+        .initial_balance(NearToken::from_near(1))
+        .transact()
+        .await?
+        .into_result()?;
+    assert_eq!(sub.secret_key().key_type(), KeyType::SECP256K1);
+
+    // Transactions signed with secp256k1 keys go through just like ed25519 ones:
+    let bob = worker.dev_create_account().await?;
+    let bob_balance = bob.view_account().await?.balance;
+    sub.transfer_near(bob.id(), NearToken::from_millinear(100))
+        .await?
+        .into_result()?;
+    assert!(bob.view_account().await?.balance > bob_balance);
+
+    let keys = sub.view_access_keys().await?;
+    assert_eq!(keys[0].public_key.key_type(), KeyType::SECP256K1);
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_implicit_account() -> anyhow::Result<()> {
+    use near_workspaces::types::{KeyType, SecretKey};
+    use near_workspaces::Account;
+
+    let worker = near_workspaces::sandbox().await?;
+    let funder = worker.dev_create_account().await?;
+    let sk = SecretKey::from_random(KeyType::ED25519);
+
+    let unfunded = Account::implicit_from(sk.clone(), &worker)?;
+    assert!(unfunded.view_account().await.is_err());
+
+    let user = funder
+        .fund_implicit_account(sk, NearToken::from_near(1))
+        .await?
+        .into_result()?;
+    assert_eq!(user.id(), unfunded.id());
+    assert_eq!(user.view_account().await?.balance, NearToken::from_near(1));
+
+    // The implicit account can sign for itself right away:
+    user.transfer_near(funder.id(), NearToken::from_millinear(100))
+        .await?
+        .into_result()?;
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_stage_upgrade_rejected() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker
+        .dev_deploy(include_bytes!("../../examples/res/status_message.wasm"))
+        .await?;
+    let code_hash = contract.view_account().await?.code_hash;
+
+    // The status message contract has no method to stage code with, so the upgrade stops at
+    // the first chunk and leaves the contract untouched:
+    let err = contract
+        .as_account()
+        .stage_upgrade(
+            contract.id(),
+            include_bytes!("../../examples/res/adder.wasm"),
+        )
+        .chunk_size(1024)
+        .transact()
+        .await;
+    assert!(err.is_err());
+    assert_eq!(contract.view_account().await?.code_hash, code_hash);
+
+    Ok(())
+}
+
 #[test(tokio::test)]
 async fn test_delete_account() -> anyhow::Result<()> {
     let worker = near_workspaces::sandbox().await?;
@@ -96,3 +179,274 @@ async fn test_delete_account() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test(tokio::test)]
+async fn test_deploy_to_subaccount() -> anyhow::Result<()> {
+    let mut worker = near_workspaces::sandbox().await?;
+    let account = worker.dev_create_account().await?;
+    let sub = account
+        .create_subaccount("status")
+        .initial_balance(NearToken::from_near(10))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let meter = near_workspaces::types::GasMeter::now(&mut worker);
+    // Accounts only pick up hooks registered before they were created:
+    let sub = near_workspaces::Account::from_secret_key(
+        sub.id().clone(),
+        sub.secret_key().clone(),
+        &worker,
+    );
+    let deploy = sub
+        .deploy(include_bytes!("../../examples/res/status_message.wasm"))
+        .await?;
+    assert_eq!(meter.elapsed()?, deploy.details.total_gas_burnt);
+    let contract = deploy.into_result()?;
+
+    contract
+        .call("set_status")
+        .args_json(serde_json::json!({ "message": "deployed" }))
+        .transact()
+        .await?
+        .into_result()?;
+    let status: String = contract
+        .view("get_status")
+        .args_json(serde_json::json!({ "account_id": contract.id() }))
+        .await?
+        .json()?;
+    assert_eq!(status, "deployed");
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_create_subaccount_with_function_call_key() -> anyhow::Result<()> {
+    use near_workspaces::types::{AccessKey, AccessKeyPermission, KeyType, SecretKey};
+
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker
+        .dev_deploy(include_bytes!("../../examples/res/status_message.wasm"))
+        .await?;
+    let account = worker.dev_create_account().await?;
+
+    let restricted_sk = SecretKey::from_random(KeyType::ED25519);
+    let sub = account
+        .create_subaccount("player")
+        .add_key(
+            restricted_sk.public_key(),
+            AccessKey::function_call_access(
+                contract.id(),
+                &["set_status"],
+                Some(NearToken::from_millinear(250)),
+            ),
+        )
+        .transact()
+        .await?
+        .into_result()?;
+
+    let key = sub.view_access_key(&restricted_sk.public_key()).await?;
+    match key.permission {
+        AccessKeyPermission::FunctionCall(permission) => {
+            assert_eq!(permission.receiver_id, contract.id().as_str());
+            assert_eq!(permission.method_names, vec!["set_status".to_string()]);
+            assert_eq!(permission.allowance, Some(NearToken::from_millinear(250)));
+        }
+        AccessKeyPermission::FullAccess => panic!("expected a function call access key"),
+    }
+
+    // The restricted key can call into the allowed method, but nothing else.
+    let restricted =
+        near_workspaces::Account::from_secret_key(sub.id().clone(), restricted_sk, &worker);
+    restricted
+        .call(contract.id(), "set_status")
+        .args_json(serde_json::json!({ "message": "hello" }))
+        .transact()
+        .await?
+        .into_result()?;
+    assert!(restricted
+        .transfer_near(account.id(), NearToken::from_near(1))
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_balance_history() -> anyhow::Result<()> {
+    const SENT_AMOUNT: NearToken = NearToken::from_near(5);
+
+    let worker = near_workspaces::sandbox().await?;
+    let (alice, bob) = (
+        worker.dev_create_tla().await?,
+        worker.dev_create_tla().await?,
+    );
+    let before = bob.view_account().await?.balance;
+    let from = worker.view_block().await?.height();
+
+    alice
+        .transfer_near(bob.id(), SENT_AMOUNT)
+        .await?
+        .into_result()?;
+    worker.fast_forward(5).await?;
+    let to = worker.view_block().await?.height();
+
+    let history = worker.balance_history(bob.id(), from, to, 2).await?;
+    assert!(!history.is_empty());
+    assert!(history.windows(2).all(|w| w[0].0 < w[1].0));
+    assert_eq!(history.first().unwrap().1, before);
+    assert_eq!(
+        history.last().unwrap().1,
+        before.saturating_add(SENT_AMOUNT)
+    );
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_stake() -> anyhow::Result<()> {
+    const STAKE: NearToken = NearToken::from_near(50);
+
+    let worker = near_workspaces::sandbox().await?;
+    let alice = worker.dev_create_tla().await?;
+
+    alice
+        .stake(alice.secret_key().public_key(), STAKE)
+        .await?
+        .into_result()?;
+    assert_eq!(alice.view_account().await?.locked, STAKE);
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_view_access_keys_after_rotation() -> anyhow::Result<()> {
+    use near_workspaces::types::{AccessKey, AccessKeyPermission, KeyType, SecretKey};
+
+    let worker = near_workspaces::sandbox().await?;
+    let mut account = worker.dev_create_account().await?;
+    let old_pk = account.secret_key().public_key();
+    let new_sk = SecretKey::from_random(KeyType::ED25519);
+
+    // Rotate the full access key of the account over to a new one.
+    account
+        .batch(account.id())
+        .add_key(new_sk.public_key(), AccessKey::full_access())
+        .transact()
+        .await?
+        .into_result()?;
+    let keys = worker.view_access_keys(account.id()).await?;
+    assert_eq!(keys.len(), 2);
+
+    account.set_secret_key(new_sk.clone());
+    account
+        .batch(account.id())
+        .delete_key(old_pk)
+        .transact()
+        .await?
+        .into_result()?;
+
+    let keys = account.view_access_keys().await?;
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0].public_key, new_sk.public_key());
+    assert_eq!(
+        keys[0].access_key.nonce,
+        account.view_access_key(&new_sk.public_key()).await?.nonce
+    );
+    assert!(matches!(
+        keys[0].access_key.permission,
+        AccessKeyPermission::FullAccess
+    ));
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_assert_delete_account_refund() -> anyhow::Result<()> {
+    use near_workspaces::assertions::assert_delete_account_refund;
+
+    let worker = near_workspaces::sandbox().await?;
+    let (alice, bob) = (
+        worker.dev_create_account().await?,
+        worker.dev_create_account().await?,
+    );
+
+    assert_delete_account_refund(alice, bob.id(), NearToken::from_millinear(1))
+        .await?
+        .into_result()?;
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_validators() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let validators = worker.validators().await?;
+    assert_eq!(validators.current_validators.len(), 1);
+    assert_eq!(validators.current_validators[0].account_id, "test.near");
+
+    // Staking makes a proposal for a later epoch.
+    let alice = worker.dev_create_tla().await?;
+    alice
+        .stake(alice.secret_key().public_key(), NearToken::from_near(50))
+        .await?
+        .into_result()?;
+    let validators = worker.validators().await?;
+    assert!(validators
+        .current_proposals
+        .iter()
+        .any(|proposal| &proposal.account_id == alice.id()));
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_labeled_outcome() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let (alice, bob) = (
+        worker.dev_create_account().await?,
+        worker.dev_create_account().await?,
+    );
+    worker.label(alice.id(), "alice");
+    worker.label(bob.id(), "bob");
+
+    let outcome = alice
+        .transfer_near(bob.id(), NearToken::from_millinear(1))
+        .await?;
+    let summary = worker.relabel(&outcome);
+    assert!(
+        summary.starts_with("alice -> bob: succeeded"),
+        "{}",
+        summary
+    );
+    assert!(!summary.contains(alice.id().as_str()), "{}", summary);
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_summarize() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let alice = worker.dev_create_account().await?;
+    let bob = alice
+        .create_subaccount("bob")
+        .transact()
+        .await?
+        .into_result()?;
+    bob.clone()
+        .delete_account(alice.id())
+        .await?
+        .into_result()?;
+
+    let summary = worker.summarize().await?;
+    let details = summary
+        .account(alice.id())
+        .unwrap()
+        .details
+        .as_ref()
+        .unwrap();
+    assert!(details.balance > NearToken::from_near(0));
+    assert!(summary.account(bob.id()).unwrap().details.is_none());
+
+    Ok(())
+}
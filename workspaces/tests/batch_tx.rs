@@ -40,3 +40,68 @@ async fn test_batch_tx() -> anyhow::Result<()> {
     assert_eq!(status_msg, "world_hello");
     Ok(())
 }
+
+#[test(tokio::test)]
+async fn test_relay_delegate_action() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker
+        .dev_deploy(include_bytes!("../../examples/res/status_message.wasm"))
+        .await?;
+    let alice = worker.dev_create_account().await?;
+    let relayer = worker.dev_create_account().await?;
+
+    let signed = alice
+        .batch(contract.id())
+        .call(Function::new("set_status").args_json(json!({
+            "message": "relayed",
+        })))
+        .delegate(100)
+        .await?;
+
+    let alice_balance = alice.view_account().await?.balance;
+    let relayer_balance = relayer.view_account().await?.balance;
+    relayer.relay(signed).await?.into_result()?;
+
+    let status: String = contract
+        .view("get_status")
+        .args_json(json!({ "account_id": alice.id() }))
+        .await?
+        .json()?;
+    assert_eq!(status, "relayed");
+
+    // The gas was paid for by the relayer instead of alice.
+    assert!(alice.view_account().await?.balance >= alice_balance);
+    assert!(relayer.view_account().await?.balance < relayer_balance);
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_sponsor_call() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker
+        .dev_deploy(include_bytes!("../../examples/res/status_message.wasm"))
+        .await?;
+    let alice = worker.dev_create_account().await?;
+    let relayer = worker.dev_create_account().await?;
+
+    let alice_balance = alice.view_account().await?.balance;
+    relayer
+        .sponsor(
+            alice
+                .call(contract.id(), "set_status")
+                .args_json(json!({ "message": "sponsored" })),
+        )
+        .await?
+        .into_result()?;
+
+    let status: String = contract
+        .view("get_status")
+        .args_json(json!({ "account_id": alice.id() }))
+        .await?
+        .json()?;
+    assert_eq!(status, "sponsored");
+    assert!(alice.view_account().await?.balance >= alice_balance);
+
+    Ok(())
+}
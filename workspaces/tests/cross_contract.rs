@@ -69,3 +69,103 @@ async fn test_cross_contract_calls() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_cross_contract_folded_stacks() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker.dev_deploy(FACTORY_CONTRACT).await?;
+    let status_amt = NearToken::from_near(35);
+
+    let status_id: AccountId = format!("status.{}", contract.id()).parse().unwrap();
+    let outcome = cross_contract_create_contract(&status_id, &status_amt, &contract).await?;
+    let folded = outcome.folded_stacks();
+
+    // Every outcome in the call tree ends up as its own line.
+    assert_eq!(folded.lines().count(), outcome.outcomes().len());
+
+    let total: u64 = folded
+        .lines()
+        .map(|line| line.rsplit_once(' ').unwrap().1.parse::<u64>().unwrap())
+        .sum();
+    assert_eq!(total, outcome.total_gas_burnt.as_gas());
+
+    let status_frame = format!("{};{};{}", contract.id(), contract.id(), status_id);
+    assert!(
+        folded.lines().any(|line| line.starts_with(&status_frame)),
+        "expected the created account to be nested under the factory: {}",
+        folded
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cross_contract_function_call_receipts() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker.dev_deploy(FACTORY_CONTRACT).await?;
+    let status_amt = NearToken::from_near(35);
+
+    let status_id: AccountId = format!("status.{}", contract.id()).parse().unwrap();
+    cross_contract_create_contract(&status_id, &status_amt, &contract)
+        .await?
+        .into_result()?;
+
+    let outcome = contract
+        .call("complex_call")
+        .args_json((status_id.clone(), "hello world"))
+        .max_gas()
+        .transact()
+        .await?;
+    let receipts = worker.function_call_receipts(&outcome).await?;
+
+    let set_status = receipts
+        .iter()
+        .find(|receipt| receipt.method_name == "set_status")
+        .expect("expected the factory to call into the status contract");
+    assert_eq!(&set_status.predecessor_id, contract.id());
+    assert_eq!(set_status.receiver_id, status_id);
+    assert_eq!(set_status.deposit, NearToken::from_yoctonear(0));
+
+    // The call made by the transaction itself is not a part of the receipts.
+    assert!(receipts
+        .iter()
+        .all(|receipt| receipt.method_name != "complex_call"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cross_contract_gas_report() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker.dev_deploy(FACTORY_CONTRACT).await?;
+    let status_amt = NearToken::from_near(35);
+
+    let status_id: AccountId = format!("status.{}", contract.id()).parse().unwrap();
+    cross_contract_create_contract(&status_id, &status_amt, &contract)
+        .await?
+        .into_result()?;
+
+    let outcome = contract
+        .call("complex_call")
+        .args_json((status_id.clone(), "hello world"))
+        .max_gas()
+        .transact()
+        .await?;
+    let report = worker.gas_report(&outcome).await?;
+
+    let set_status = report
+        .receipts
+        .iter()
+        .find(|usage| usage.method_names == ["set_status"])
+        .expect("expected the call into the status contract to be reported");
+    assert_eq!(set_status.receiver_id, status_id);
+    assert!(set_status.gas_burnt.as_gas() > 0);
+    assert!(set_status.gas_attached >= set_status.gas_burnt);
+
+    let ratio = set_status.attached_to_burnt_ratio().unwrap();
+    assert!(report.over_attached(ratio / 2.0).contains(&set_status));
+    assert!(!report.over_attached(ratio).contains(&set_status));
+    assert!(report.to_string().contains("set_status"));
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use near_workspaces::datagen;
+use near_workspaces::types::NearToken;
+use near_workspaces::AccountId;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+#[test]
+fn test_datagen_is_reproducible() -> anyhow::Result<()> {
+    let parent: AccountId = "token.test.near".parse()?;
+    let generate = || {
+        let mut rng = StdRng::seed_from_u64(7);
+        (
+            datagen::ft_metadata(&mut rng),
+            datagen::account_ids(&mut rng, &parent, 50),
+        )
+    };
+    assert_eq!(generate(), generate());
+
+    let (metadata, ids) = generate();
+    assert_eq!(metadata.spec, "ft-1.0.0");
+    assert!(metadata.decimals <= 24);
+    let json = serde_json::to_value(&metadata)?;
+    assert_eq!(json["symbol"], metadata.symbol.as_str());
+
+    let mut distinct = ids.clone();
+    distinct.sort();
+    distinct.dedup();
+    assert_eq!(distinct.len(), 50);
+    assert!(ids.iter().all(|id| id.is_sub_account_of(&parent)));
+
+    Ok(())
+}
+
+#[test]
+fn test_datagen_balances() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let total = NearToken::from_near(1_000_000);
+    let mut balances = datagen::balances(&mut rng, 1000, total);
+    assert_eq!(balances.len(), 1000);
+    let sum = balances
+        .iter()
+        .fold(NearToken::from_near(0), |sum, b| sum.saturating_add(*b));
+    assert_eq!(sum, total);
+
+    // The top 20% of holders hold most of the supply:
+    balances.sort_unstable_by(|a, b| b.cmp(a));
+    let top = balances[..200]
+        .iter()
+        .fold(NearToken::from_near(0), |sum, b| sum.saturating_add(*b));
+    assert!(top > total.saturating_div(2), "{}", top);
+
+    assert!(datagen::balances(&mut rng, 0, total).is_empty());
+}
+
+#[test]
+fn test_datagen_nft_metadata() -> anyhow::Result<()> {
+    let mut rng = StdRng::seed_from_u64(7);
+    let contract = datagen::nft_contract_metadata(&mut rng);
+    assert_eq!(contract.spec, "nft-1.0.0");
+
+    let token = datagen::nft_token_metadata(&mut rng, 3);
+    assert!(token.title.unwrap().ends_with("#3"));
+    assert_eq!(token.copies, Some(1));
+    let json = serde_json::to_value(datagen::nft_token_metadata(&mut rng, 4))?;
+    assert!(json["media"].as_str().unwrap().ends_with("/4.png"));
+
+    Ok(())
+}
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use test_log::test;
 
 use near_workspaces::network::ValidatorKey;
+use near_workspaces::registry::{ContractRegistry, WasmSource};
 use near_workspaces::{pick_unused_port, DevNetwork, Worker};
 
 const NFT_WASM_FILEPATH: &str = "../examples/res/non_fungible_token.wasm";
@@ -86,6 +87,42 @@ async fn test_dev_deploy_sandbox() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test(tokio::test)]
+async fn test_dev_deploy_to_sandbox() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let wasm = std::fs::read(NFT_WASM_FILEPATH)?;
+
+    let contract = worker.dev_deploy_to(&wasm, "nft.test.near").await?;
+    assert_eq!(contract.id().as_str(), "nft.test.near");
+    assert_eq!(contract.view_code().await?, wasm);
+
+    // Only direct subaccounts of the root account can be created:
+    assert!(worker.dev_deploy_to(&wasm, "nft.alice.near").await.is_err());
+    assert!(worker
+        .dev_deploy_to(&wasm, "a.nft.test.near")
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_registry_deploy_sandbox() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let registry = ContractRegistry::new().register("nft", WasmSource::path(NFT_WASM_FILEPATH));
+
+    let contract = registry.deploy(&worker, "nft").await?;
+    assert_eq!(
+        contract.view_code().await?,
+        std::fs::read(NFT_WASM_FILEPATH)?
+    );
+
+    let contract = registry.deploy_to(&worker, "nft", "nft.test.near").await?;
+    assert_eq!(contract.id().as_str(), "nft.test.near");
+
+    Ok(())
+}
+
 #[test(tokio::test)]
 async fn test_dev_deploy_testnet() -> anyhow::Result<()> {
     let worker = near_workspaces::testnet().await?;
@@ -107,6 +144,14 @@ async fn test_dev_create_account_testnet() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test(tokio::test)]
+#[ignore = "betanet is not always available"]
+async fn test_dev_create_account_betanet() -> anyhow::Result<()> {
+    let worker = near_workspaces::betanet().await?;
+    dev_create_account_and_assert(worker).await?;
+    Ok(())
+}
+
 #[test(tokio::test)]
 async fn test_manually_spawned_deploy() -> anyhow::Result<()> {
     let rpc_port = pick_unused_port().await?;
@@ -134,3 +179,149 @@ async fn test_manually_spawned_deploy() -> anyhow::Result<()> {
     child.kill().await?;
     Ok(())
 }
+
+#[test(tokio::test)]
+async fn test_sandbox_attach() -> anyhow::Result<()> {
+    let rpc_port = pick_unused_port().await?;
+    let net_port = pick_unused_port().await?;
+    let home_dir = std::env::temp_dir().join(format!("test-sandbox-attach-{}", rpc_port));
+
+    near_sandbox_utils::init(&home_dir)?
+        .wait_with_output()
+        .await
+        .unwrap();
+    near_workspaces::network::set_sandbox_genesis(&home_dir)?;
+    let mut child = near_sandbox_utils::run(&home_dir, rpc_port, net_port)?;
+
+    let worker = near_workspaces::sandbox_attach(rpc_port, &home_dir).await?;
+    assert_eq!(worker.rpc_port(), Some(rpc_port));
+    deploy_and_assert(worker).await?;
+
+    // The node outlives the worker, so another one can attach to it.
+    let worker = near_workspaces::sandbox_attach(rpc_port, &home_dir).await?;
+    worker.view_block().await?;
+
+    child.kill().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_sandbox_version() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox()
+        .version(near_sandbox_utils::DEFAULT_NEAR_SANDBOX_VERSION)
+        .await?;
+    deploy_and_assert(worker).await?;
+
+    // The version of an already running sandbox cannot be chosen.
+    let secret_key =
+        near_workspaces::types::SecretKey::from_random(near_workspaces::types::KeyType::ED25519);
+    let err = near_workspaces::sandbox()
+        .rpc_addr("http://localhost:3030")
+        .validator_key(ValidatorKey::Known("test.near".parse()?, secret_key))
+        .version("1.35.0")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("version"), "{}", err);
+
+    Ok(())
+}
+
+/// Serve `body` over HTTP on localhost for every request made, returning the URL to it.
+async fn serve(body: Vec<u8>) -> anyhow::Result<String> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}/contract.wasm", listener.local_addr()?);
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).await;
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = stream.write_all(head.as_bytes()).await;
+            let _ = stream.write_all(&body).await;
+        }
+    });
+
+    Ok(url)
+}
+
+#[test(tokio::test)]
+async fn test_dev_deploy_from_url() -> anyhow::Result<()> {
+    use sha2::Digest;
+
+    let wasm = std::fs::read(NFT_WASM_FILEPATH)?;
+    let checksum = format!("{:x}", sha2::Sha256::digest(&wasm));
+    let url = serve(wasm).await?;
+
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker.dev_deploy_from_url(&url, &checksum).await?;
+    contract
+        .call("new_default_meta")
+        .args_json(serde_json::json!({
+            "owner_id": contract.id()
+        }))
+        .transact()
+        .await?
+        .into_result()?;
+    let actual: NftMetadata = contract.view("nft_metadata").await?.json()?;
+    assert_eq!(actual, expected());
+
+    // The downloaded code has to match the pinned checksum:
+    let mismatch = "0".repeat(64);
+    assert!(worker.dev_deploy_from_url(&url, &mismatch).await.is_err());
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_download_code() -> anyhow::Result<()> {
+    let wasm = std::fs::read(NFT_WASM_FILEPATH)?;
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker.dev_deploy(&wasm).await?;
+
+    let dir = tempfile::tempdir()?;
+    let path = worker.download_code(contract.id(), dir.path()).await?;
+
+    let code_hash = contract.view_account().await?.code_hash;
+    assert_eq!(
+        path.file_name().unwrap().to_str().unwrap(),
+        format!("{}-{}.wasm", contract.id(), code_hash)
+    );
+    assert_eq!(std::fs::read(path)?, wasm);
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_capture_sandbox_logs() -> anyhow::Result<()> {
+    std::env::set_var("NEAR_ENABLE_SANDBOX_LOG", "1");
+    let worker = near_workspaces::sandbox().capture_logs(true).await?;
+    deploy_and_assert(worker.clone()).await?;
+    assert!(!worker.sandbox_logs().is_empty());
+
+    // Logs are not captured by default.
+    let worker = near_workspaces::sandbox().await?;
+    assert!(worker.sandbox_logs().is_empty());
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_sandbox_shutdown() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let home_dir = worker.home_dir().unwrap().to_path_buf();
+    worker.shutdown().await?;
+    assert!(!home_dir.exists());
+    assert!(worker.view_block().await.is_err());
+
+    let worker = near_workspaces::sandbox().keep_files(true).await?;
+    let home_dir = worker.home_dir().unwrap().to_path_buf();
+    worker.shutdown().await?;
+    assert!(home_dir.join("genesis.json").exists());
+    std::fs::remove_dir_all(home_dir)?;
+
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use std::time::Duration;
+
+use serde_json::json;
+use test_log::test;
+
+use near_workspaces::error::ErrorKind;
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, AccountId, Contract, DevNetwork, Worker};
+
+near_workspaces::events! {
+    FtTransfer {
+        old_owner_id: AccountId,
+        new_owner_id: AccountId,
+        amount: String,
+        memo: Option<String>,
+    } = ("nep141", "ft_transfer");
+}
+
+const FT_WASM_FILEPATH: &str = "../examples/res/fungible_token.wasm";
+
+async fn init(worker: &Worker<impl DevNetwork>) -> anyhow::Result<(Contract, Account)> {
+    let contract = worker.dev_deploy(&std::fs::read(FT_WASM_FILEPATH)?).await?;
+    contract
+        .call("new_default_meta")
+        .args_json(json!({
+            "owner_id": contract.id(),
+            "total_supply": NearToken::from_near(1_000).as_yoctonear().to_string(),
+        }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let bob = worker.dev_create_account().await?;
+    bob.call(contract.id(), "storage_deposit")
+        .deposit(NearToken::from_millinear(125))
+        .transact()
+        .await?
+        .into_result()?;
+
+    Ok((contract, bob))
+}
+
+#[test(tokio::test)]
+async fn test_outcome_events() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let (contract, bob) = init(&worker).await?;
+
+    let outcome = contract
+        .call("ft_transfer")
+        .args_json(json!({ "receiver_id": bob.id(), "amount": "100" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let events = outcome
+        .outcomes()
+        .into_iter()
+        .flat_map(|outcome| outcome.events())
+        .collect::<Vec<_>>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].standard, "nep141");
+    assert_eq!(events[0].event, "ft_transfer");
+    assert_eq!(&events[0].emitter_id, contract.id());
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_wait_for_event() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let (contract, bob) = init(&worker).await?;
+
+    let wait = worker
+        .wait_for_event("nep141", "ft_transfer", |event| {
+            event
+                .data_json::<Vec<serde_json::Value>>()
+                .is_ok_and(|data| {
+                    data.iter()
+                        .any(|transfer| transfer["new_owner_id"] == bob.id().as_str())
+                })
+        })
+        .timeout(Duration::from_secs(30));
+    let transfer = async {
+        // Give the waiter a chance to start watching blocks before sending the transfer.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        contract
+            .call("ft_transfer")
+            .args_json(json!({ "receiver_id": bob.id(), "amount": "100" }))
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await
+    };
+
+    let (event, transfer) = tokio::join!(wait, transfer);
+    transfer?.into_result()?;
+    let event = event?;
+    assert_eq!(&event.emitter_id, contract.id());
+
+    // Nothing else gets transferred, so waiting again times out.
+    let err = worker
+        .wait_for_event("nep141", "ft_transfer", |_| true)
+        .timeout(Duration::from_secs(3))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::Timeout);
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_compare_outcomes() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let (contract, bob) = init(&worker).await?;
+
+    let transfer = |amount: &'static str| {
+        contract
+            .call("ft_transfer")
+            .args_json(json!({ "receiver_id": bob.id(), "amount": amount }))
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+    };
+    let golden = transfer("100").await?;
+
+    let diff = near_workspaces::compare(&golden, &transfer("100").await?);
+    assert!(diff.is_equivalent(0.05), "{:#?}", diff);
+
+    let diff = near_workspaces::compare(&golden, &transfer("200").await?);
+    assert!(!diff.status_changed);
+    assert_eq!(diff.events_removed.len(), 1);
+    assert_eq!(diff.events_added.len(), 1);
+    assert_eq!(
+        diff.events_added[0].data.as_ref().unwrap()[0]["amount"],
+        "200"
+    );
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_typed_events() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let (contract, bob) = init(&worker).await?;
+
+    let outcome = contract
+        .call("ft_transfer")
+        .args_json(json!({ "receiver_id": bob.id(), "amount": "100", "memo": "hello" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+
+    let transfers = outcome.events_of::<FtTransfer>()?;
+    assert_eq!(
+        transfers,
+        vec![FtTransfer {
+            old_owner_id: contract.id().clone(),
+            new_owner_id: bob.id().clone(),
+            amount: "100".to_string(),
+            memo: Some("hello".to_string()),
+        }]
+    );
+
+    Ok(())
+}
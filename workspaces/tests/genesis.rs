@@ -0,0 +1,51 @@
+use test_log::test;
+
+use near_workspaces::network::GenesisConfig;
+use near_workspaces::types::{KeyType, NearToken, SecretKey};
+use near_workspaces::{Account, AccountId};
+
+#[test(tokio::test)]
+async fn test_custom_genesis() -> anyhow::Result<()> {
+    let id: AccountId = "alice.test.near".parse()?;
+    let sk = SecretKey::from_seed(KeyType::ED25519, "alice");
+    let min_gas_price = NearToken::from_yoctonear(500_000_000);
+
+    let worker = near_workspaces::sandbox()
+        .genesis(
+            GenesisConfig::new()
+                .epoch_length(50)
+                .min_gas_price(min_gas_price)
+                .add_account(id.clone(), sk.public_key(), NearToken::from_near(1_000)),
+        )
+        .await?;
+
+    assert_eq!(worker.gas_price().await?, min_gas_price);
+
+    let details = worker.view_account(&id).await?;
+    assert_eq!(details.balance, NearToken::from_near(1_000));
+
+    // The genesis account is usable with the key it was given.
+    let alice = Account::from_secret_key(id, sk, &worker);
+    alice
+        .transfer_near(worker.root_account()?.id(), NearToken::from_near(1))
+        .await?
+        .into_result()?;
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_fast_forward_to_next_epoch() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox()
+        .genesis(GenesisConfig::new().epoch_length(20))
+        .await?;
+
+    assert!(worker.blocks_until_epoch_end().await? <= 20);
+
+    let epoch_height = worker.validators().await?.epoch_height;
+    worker.fast_forward_to_next_epoch().await?;
+    assert_eq!(worker.validators().await?.epoch_height, epoch_height + 1);
+    assert!(worker.blocks_until_epoch_end().await? <= 20);
+
+    Ok(())
+}
@@ -0,0 +1,1384 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::{engine::general_purpose, Engine as _};
+use serde_json::{json, Value};
+
+use near_workspaces::network::NetworkInfo;
+use near_workspaces::registry::{ContractRegistry, WasmSource};
+use near_workspaces::rpc::middleware::{Layer, Next};
+use near_workspaces::rpc::BoxFuture;
+use near_workspaces::types::{KeyType, NearToken, SecretKey};
+use near_workspaces::{Account, AccountId};
+
+#[tokio::test]
+async fn test_mock_view_account() -> anyhow::Result<()> {
+    let worker = near_workspaces::mock().await?;
+    let alice: AccountId = "alice.near".parse()?;
+
+    worker.respond_to(
+        "query",
+        json!({ "request_type": "view_account", "account_id": "alice.near" }),
+        json!({
+            "amount": NearToken::from_near(10).as_yoctonear().to_string(),
+            "locked": "0",
+            "code_hash": "11111111111111111111111111111111",
+            "storage_usage": 182,
+            "storage_paid_at": 0,
+            "block_height": 1,
+            "block_hash": "11111111111111111111111111111111",
+        }),
+    );
+
+    let details = worker.view_account(&alice).await?;
+    assert_eq!(details.balance, NearToken::from_near(10));
+    assert_eq!(details.storage_usage, 182);
+
+    // No fixture matches the params of bob's account:
+    let bob: AccountId = "bob.near".parse()?;
+    assert!(worker.view_account(&bob).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mock_override_fixture() -> anyhow::Result<()> {
+    let worker = near_workspaces::mock().await?;
+
+    worker.respond("gas_price", json!({ "gas_price": "100" }));
+    assert_eq!(worker.gas_price().await?, NearToken::from_yoctonear(100));
+
+    worker.respond("gas_price", json!({ "gas_price": "200" }));
+    assert_eq!(worker.gas_price().await?, NearToken::from_yoctonear(200));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mock_rebroadcast_expired_tx() -> anyhow::Result<()> {
+    let worker = near_workspaces::mock().await?;
+    let sk = SecretKey::from_seed(KeyType::ED25519, "alice");
+    let alice = Account::from_secret_key("alice.near".parse()?, sk, &worker);
+
+    worker.respond_to(
+        "query",
+        json!({ "request_type": "view_access_key", "account_id": "alice.near" }),
+        json!({
+            "nonce": 0,
+            "permission": "FullAccess",
+            "block_height": 1,
+            "block_hash": "11111111111111111111111111111111",
+        }),
+    );
+    worker.respond_error(
+        "broadcast_tx_commit",
+        json!({
+            "code": -32000,
+            "message": "Server error",
+            "name": "HANDLER_ERROR",
+            "cause": { "name": "INVALID_TRANSACTION", "info": { "context": "Expired" } },
+        }),
+    );
+
+    // The transaction keeps on expiring, so it gets re-signed until giving up.
+    let result = alice
+        .transfer_near(&"bob.near".parse()?, NearToken::from_near(1))
+        .await;
+    assert!(result.is_err());
+    assert_eq!(worker.rebroadcasted_transactions(), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_record_and_replay() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let cassette = dir.path().join("cassette.json");
+
+    let worker = near_workspaces::mock().record(&cassette).await?;
+    worker.respond("gas_price", json!({ "gas_price": "100" }));
+    assert_eq!(worker.gas_price().await?, NearToken::from_yoctonear(100));
+    worker.respond("gas_price", json!({ "gas_price": "200" }));
+    assert_eq!(worker.gas_price().await?, NearToken::from_yoctonear(200));
+    drop(worker);
+
+    // Nothing is registered on the replaying worker, so responses come from the cassette,
+    // in the same order they were recorded in.
+    let worker = near_workspaces::mock().replay(&cassette).await?;
+    assert_eq!(worker.gas_price().await?, NearToken::from_yoctonear(100));
+    assert_eq!(worker.gas_price().await?, NearToken::from_yoctonear(200));
+    assert!(worker.gas_price().await.is_err());
+
+    Ok(())
+}
+
+/// Counts the requests passing through, and answers `gas_price` requests itself.
+#[derive(Clone, Default)]
+struct FixedGasPrice {
+    requests: Arc<AtomicUsize>,
+}
+
+impl Layer for FixedGasPrice {
+    fn call<'a>(&'a self, request: Value, next: Next<'a>) -> BoxFuture<'a, Value> {
+        Box::pin(async move {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            if request["method"] == "gas_price" {
+                return json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": { "gas_price": "42" },
+                });
+            }
+            next.run(request).await
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_middleware_layer() -> anyhow::Result<()> {
+    let layer = FixedGasPrice::default();
+    let worker = near_workspaces::mock().layer(layer.clone()).await?;
+    worker.respond("gas_price", json!({ "gas_price": "100" }));
+    worker.respond_to(
+        "query",
+        json!({ "request_type": "view_account" }),
+        json!({
+            "amount": "1",
+            "locked": "0",
+            "code_hash": "11111111111111111111111111111111",
+            "storage_usage": 0,
+            "storage_paid_at": 0,
+            "block_height": 1,
+            "block_hash": "11111111111111111111111111111111",
+        }),
+    );
+
+    // Answered by the layer without reaching the network:
+    assert_eq!(worker.gas_price().await?, NearToken::from_yoctonear(42));
+
+    // Passed on to the network:
+    let alice: AccountId = "alice.near".parse()?;
+    assert_eq!(
+        worker.view_account(&alice).await?.balance,
+        NearToken::from_yoctonear(1)
+    );
+    assert_eq!(layer.requests.load(Ordering::SeqCst), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cached_view() -> anyhow::Result<()> {
+    let worker = near_workspaces::mock().await?;
+    let contract: AccountId = "ft.near".parse()?;
+    let respond_symbol = |symbol: &str| {
+        worker.respond_to(
+            "query",
+            json!({ "request_type": "call_function", "method_name": "ft_metadata" }),
+            json!({
+                "result": serde_json::to_vec(&json!({ "symbol": symbol }))?,
+                "logs": [],
+                "block_height": 1,
+                "block_hash": "11111111111111111111111111111111",
+            }),
+        );
+        anyhow::Ok(())
+    };
+    let symbol = || async {
+        let metadata: Value = worker
+            .cached_view(Duration::from_millis(500), &contract, "ft_metadata")
+            .await?
+            .json()?;
+        anyhow::Ok(metadata["symbol"].clone())
+    };
+
+    respond_symbol("OLD")?;
+    assert_eq!(symbol().await?, "OLD");
+
+    // Still answered from the cache:
+    respond_symbol("NEW")?;
+    assert_eq!(symbol().await?, "OLD");
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(symbol().await?, "NEW");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_accounts_in_state() -> anyhow::Result<()> {
+    let worker = near_workspaces::mock().await?;
+    let factory: AccountId = "factory.near".parse()?;
+    let encode = |bytes: &[u8]| general_purpose::STANDARD.encode(bytes);
+
+    // One account is stored as a borsh string in the key, the other as raw bytes in a value.
+    let mut borsh_key = b"p".to_vec();
+    borsh_key.extend(6u32.to_le_bytes());
+    borsh_key.extend(b"a.near");
+    worker.respond_to(
+        "query",
+        json!({ "request_type": "view_state", "account_id": "factory.near", "prefix_base64": encode(b"p") }),
+        json!({
+            "values": [
+                { "key": encode(&borsh_key), "value": encode(b"b.near") },
+                { "key": encode(b"pb.near"), "value": encode(&[0, 1, 2]) },
+                { "key": encode(b"p#"), "value": encode(b"not an account") },
+            ],
+            "block_height": 1,
+            "block_hash": "11111111111111111111111111111111",
+        }),
+    );
+
+    let accounts = worker.accounts_in_state(&factory, b"p").await?;
+    assert_eq!(
+        accounts,
+        vec!["a.near".parse::<AccountId>()?, "b.near".parse()?]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_latency_layer() -> anyhow::Result<()> {
+    use near_workspaces::rpc::middleware::Latency;
+
+    let latency = Latency::new(Duration::from_millis(200)).jitter(Duration::from_millis(100));
+    let worker = near_workspaces::mock().layer(latency).await?;
+    worker.respond("gas_price", json!({ "gas_price": "100" }));
+
+    let start = std::time::Instant::now();
+    assert_eq!(worker.gas_price().await?, NearToken::from_yoctonear(100));
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(200));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_no_retry() -> anyhow::Result<()> {
+    let layer = FixedGasPrice::default();
+    let worker = near_workspaces::mock().layer(layer.clone()).await?;
+    let alice: AccountId = "alice.near".parse()?;
+
+    // No fixture is registered, so viewing the account fails every time.
+    assert!(worker.view_account(&alice).no_retry().await.is_err());
+    assert_eq!(layer.requests.load(Ordering::SeqCst), 1);
+
+    assert!(worker.view_account(&alice).await.is_err());
+    assert!(layer.requests.load(Ordering::SeqCst) > 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_gas_price_at_block() -> anyhow::Result<()> {
+    let worker = near_workspaces::mock().await?;
+    worker.respond("gas_price", json!({ "gas_price": "100" }));
+    worker.respond_to("gas_price", json!([7]), json!({ "gas_price": "70" }));
+
+    assert_eq!(worker.gas_price().await?, NearToken::from_yoctonear(100));
+    assert_eq!(
+        worker.gas_price().block_height(7).await?,
+        NearToken::from_yoctonear(70)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_wait_for_state() -> anyhow::Result<()> {
+    let worker = near_workspaces::mock().await?;
+    let contract: AccountId = "counter.near".parse()?;
+    let respond_state = |value: &[u8]| {
+        worker.respond_to(
+            "query",
+            json!({ "request_type": "view_state", "account_id": "counter.near" }),
+            json!({
+                "values": [{ "key": general_purpose::STANDARD.encode(b"STATE"), "value": general_purpose::STANDARD.encode(value) }],
+                "block_height": 1,
+                "block_hash": "11111111111111111111111111111111",
+            }),
+        );
+    };
+
+    respond_state(b"pending");
+    let err = worker
+        .wait_for_state(
+            &contract,
+            b"STATE",
+            |value| value == b"done",
+            Duration::from_millis(500),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), &near_workspaces::error::ErrorKind::Timeout);
+
+    // The state gets updated in the middle of waiting:
+    let updater = {
+        let worker = worker.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            worker.respond_to(
+                "query",
+                json!({ "request_type": "view_state" }),
+                json!({
+                    "values": [{ "key": general_purpose::STANDARD.encode(b"STATE"), "value": general_purpose::STANDARD.encode(b"done") }],
+                    "block_height": 2,
+                    "block_hash": "11111111111111111111111111111111",
+                }),
+            );
+        })
+    };
+    let value = worker
+        .wait_for_state(
+            &contract,
+            b"STATE",
+            |value| value == b"done",
+            Duration::from_secs(10),
+        )
+        .await?;
+    assert_eq!(value, b"done");
+    updater.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_view_paginated() -> anyhow::Result<()> {
+    let worker = near_workspaces::mock().await?;
+    let contract: AccountId = "list.near".parse()?;
+    let args = |from_index: u64, limit: u64| {
+        general_purpose::STANDARD
+            .encode(json!({ "from_index": from_index, "limit": limit }).to_string())
+    };
+    let respond_page = |from_index: u64, limit: u64, items: Value| {
+        worker.respond_to(
+            "query",
+            json!({ "request_type": "call_function", "args_base64": args(from_index, limit) }),
+            json!({
+                "result": serde_json::to_vec(&items).unwrap(),
+                "logs": [],
+                "block_height": 1,
+                "block_hash": "11111111111111111111111111111111",
+            }),
+        );
+    };
+
+    // Pages of four items are too large for the node, so two items per page are viewed.
+    worker.respond_error(
+        "query",
+        json!({
+            "code": -32000,
+            "message": "Server error",
+            "name": "HANDLER_ERROR",
+            "cause": {
+                "name": "CONTRACT_EXECUTION_ERROR",
+                "info": {
+                    "vm_error": "wasm execution failed with error: HostError(GasLimitExceeded)",
+                    "block_height": 1,
+                    "block_hash": "11111111111111111111111111111111",
+                },
+            },
+        }),
+    );
+    respond_page(0, 2, json!([1, 2]));
+    respond_page(2, 2, json!([3, 4]));
+    respond_page(4, 2, json!([5]));
+
+    let items: Vec<u64> = worker
+        .view_paginated(&contract, "items", Value::Null, 4)
+        .await?;
+    assert_eq!(items, vec![1, 2, 3, 4, 5]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_store_credentials_concurrently() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let keystore = dir.path().join("keystore");
+    let worker = near_workspaces::mock().keystore_path(&keystore).await?;
+    assert_eq!(worker.info().keystore_path, keystore);
+
+    let mut tasks = Vec::new();
+    for seed in 0..16 {
+        let sk = SecretKey::from_seed(KeyType::ED25519, &seed.to_string());
+        let alice = Account::from_secret_key("alice.near".parse()?, sk, &worker);
+        let keystore = keystore.clone();
+        tasks.push(tokio::spawn(async move {
+            alice.store_credentials(keystore).await
+        }));
+    }
+    for task in tasks {
+        task.await??;
+    }
+
+    // Only the file of one of the writers is left, and in full.
+    let entries = std::fs::read_dir(&keystore)?.collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(entries.len(), 1);
+    let creds: Value = serde_json::from_slice(&std::fs::read(keystore.join("alice.near.json"))?)?;
+    assert_eq!(creds["account_id"], "alice.near");
+    let sk: SecretKey = creds["secret_key"].as_str().unwrap().parse()?;
+    assert_eq!(creds["public_key"], sk.public_key().to_string());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unavailable_shard() -> anyhow::Result<()> {
+    let worker = near_workspaces::mock().await?;
+    worker.respond_error(
+        "query",
+        json!({
+            "code": -32000,
+            "message": "Server error",
+            "name": "HANDLER_ERROR",
+            "cause": { "name": "UNAVAILABLE_SHARD", "info": { "requested_shard_id": 3 } },
+        }),
+    );
+
+    let err = worker
+        .view_account(&"alice.near".parse()?)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.kind(),
+        &near_workspaces::error::RpcErrorCode::UnavailableShard.into()
+    );
+
+    Ok(())
+}
+
+/// Never answers transactions, such that sending them is always in flight.
+struct HangingTransactions;
+
+impl Layer for HangingTransactions {
+    fn call<'a>(&'a self, request: Value, next: Next<'a>) -> BoxFuture<'a, Value> {
+        Box::pin(async move {
+            if request["method"] == "broadcast_tx_commit" {
+                std::future::pending::<()>().await;
+            }
+            next.run(request).await
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_detached_transactions() -> anyhow::Result<()> {
+    let worker = near_workspaces::mock().layer(HangingTransactions).await?;
+    let sk = SecretKey::from_seed(KeyType::ED25519, "alice");
+    let alice = Account::from_secret_key("alice.near".parse()?, sk, &worker);
+    worker.respond_to(
+        "query",
+        json!({ "request_type": "view_access_key", "account_id": "alice.near" }),
+        json!({
+            "nonce": 0,
+            "permission": "FullAccess",
+            "block_height": 1,
+            "block_hash": "11111111111111111111111111111111",
+        }),
+    );
+
+    let bob: AccountId = "bob.near".parse()?;
+    let transfer = alice.transfer_near(&bob, NearToken::from_near(1));
+    assert!(tokio::time::timeout(Duration::from_millis(500), transfer)
+        .await
+        .is_err());
+
+    let detached = worker.take_detached_transactions();
+    assert_eq!(detached.len(), 1);
+    assert_eq!(detached[0].sender_id(), alice.id());
+    assert!(worker.take_detached_transactions().is_empty());
+
+    Ok(())
+}
+
+/// Counts the requests going through it.
+#[derive(Clone, Default)]
+struct CountRequests(Arc<AtomicUsize>);
+
+impl Layer for CountRequests {
+    fn call<'a>(&'a self, request: Value, next: Next<'a>) -> BoxFuture<'a, Value> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        Box::pin(next.run(request))
+    }
+}
+
+#[tokio::test]
+async fn test_retry_policy() -> anyhow::Result<()> {
+    use near_workspaces::rpc::retry::{RetryPolicy, RetryableError};
+
+    let server_error = json!({
+        "code": -32000,
+        "message": "Server error",
+        "name": "INTERNAL_ERROR",
+        "cause": { "name": "INTERNAL_ERROR", "info": { "error_message": "node is overloaded" } },
+    });
+
+    let counter = CountRequests::default();
+    let worker = near_workspaces::mock()
+        .layer(counter.clone())
+        .retry_policy(RetryPolicy::none())
+        .await?;
+    worker.respond_error("gas_price", server_error.clone());
+    assert!(worker.gas_price().await.is_err());
+    assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+
+    let counter = CountRequests::default();
+    let worker = near_workspaces::mock()
+        .layer(counter.clone())
+        .retry_policy(
+            RetryPolicy::default()
+                .max_retries(2)
+                .initial_delay(Duration::from_millis(1)),
+        )
+        .await?;
+    worker.respond_error("gas_price", server_error.clone());
+    assert!(worker.gas_price().await.is_err());
+    assert_eq!(counter.0.load(Ordering::SeqCst), 3);
+
+    // Only transport errors are retried, so the server error fails right away.
+    let counter = CountRequests::default();
+    let worker = near_workspaces::mock()
+        .layer(counter.clone())
+        .retry_policy(RetryPolicy::default().retry_on(&[RetryableError::Transport]))
+        .await?;
+    worker.respond_error("gas_price", server_error);
+    assert!(worker.gas_price().await.is_err());
+    assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_timeouts() -> anyhow::Result<()> {
+    use near_workspaces::error::ErrorKind;
+    use near_workspaces::rpc::middleware::Latency;
+    use near_workspaces::rpc::retry::RetryPolicy;
+
+    let alice: AccountId = "alice.near".parse()?;
+
+    let worker = near_workspaces::mock()
+        .layer(Latency::new(Duration::from_secs(60)))
+        .request_timeout(Duration::from_millis(100))
+        .retry_policy(RetryPolicy::none())
+        .await?;
+    let err = tokio::time::timeout(Duration::from_secs(10), worker.view_account(&alice))
+        .await?
+        .unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::Timeout);
+
+    let worker = near_workspaces::mock()
+        .layer(Latency::new(Duration::from_secs(60)))
+        .await?;
+    let err = worker
+        .view_account(&alice)
+        .timeout(Duration::from_millis(100))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::Timeout);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_labels() -> anyhow::Result<()> {
+    let worker = near_workspaces::mock().await?;
+    let buyer: AccountId = "dev-20240101-1234.test.near".parse()?;
+    let market: AccountId = "dev-20240101-5678.test.near".parse()?;
+
+    worker.label(&buyer, "buyer");
+    worker.clone().label(&market, "marketplace");
+    assert_eq!(worker.label_of(&buyer).as_deref(), Some("buyer"));
+    assert_eq!(worker.label_of(&"alice.near".parse()?), None);
+
+    assert_eq!(
+        worker.relabel(format!("{} -> {}.buy: sold to {}.", buyer, market, buyer)),
+        "buyer -> marketplace.buy: sold to buyer."
+    );
+    // Only whole account ids get replaced.
+    assert_eq!(
+        worker.relabel(format!("sub.{} x{}", buyer, market)),
+        format!("sub.{} x{}", buyer, market)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_custom_headers() -> anyhow::Result<()> {
+    use std::future::IntoFuture;
+    use tokio::io::AsyncReadExt;
+
+    // Capture the head of the first request the worker makes, without answering it.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let rpc_url = format!("http://{}", listener.local_addr()?);
+    let captured = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await?;
+        let mut head = Vec::new();
+        let mut buf = [0; 1024];
+        while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+            let read = stream.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            head.extend_from_slice(&buf[..read]);
+        }
+        anyhow::Ok(String::from_utf8_lossy(&head).to_lowercase())
+    });
+
+    let worker = tokio::spawn(
+        near_workspaces::mainnet()
+            .rpc_addr(&rpc_url)
+            .api_key("my-api-key")
+            .header("Authorization", "Bearer my-token")
+            .into_future(),
+    );
+    let head = captured.await??;
+    worker.abort();
+    assert!(head.contains("x-api-key: my-api-key"), "{}", head);
+    assert!(head.contains("authorization: bearer my-token"), "{}", head);
+
+    let err = near_workspaces::mock()
+        .header("invalid header", "value")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.kind(),
+        &near_workspaces::error::ErrorKind::DataConversion
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_runtime_required() -> anyhow::Result<()> {
+    use std::future::IntoFuture;
+
+    let err = futures::executor::block_on(near_workspaces::mock().into_future()).unwrap_err();
+    assert_eq!(err.kind(), &near_workspaces::error::ErrorKind::Other);
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    runtime.block_on(async {
+        let worker = near_workspaces::mock().await?;
+        worker.respond("gas_price", json!({ "gas_price": "100" }));
+        assert_eq!(worker.gas_price().await?, NearToken::from_yoctonear(100));
+        Ok(())
+    })
+}
+
+#[test]
+fn test_blocking_worker() -> anyhow::Result<()> {
+    let worker = near_workspaces::blocking::mock()?;
+    worker
+        .as_async()
+        .respond("gas_price", json!({ "gas_price": "100" }));
+    assert_eq!(worker.gas_price()?, NearToken::from_yoctonear(100));
+
+    let alice: AccountId = "alice.near".parse()?;
+    worker.as_async().respond_to(
+        "query",
+        json!({ "request_type": "view_account", "account_id": "alice.near" }),
+        json!({
+            "amount": NearToken::from_near(10).as_yoctonear().to_string(),
+            "locked": "0",
+            "code_hash": "11111111111111111111111111111111",
+            "storage_usage": 182,
+            "storage_paid_at": 0,
+            "block_height": 1,
+            "block_hash": "11111111111111111111111111111111",
+        }),
+    );
+    assert_eq!(
+        worker.view_account(&alice)?.balance,
+        NearToken::from_near(10)
+    );
+    let details = worker.block_on(worker.as_async().view_account(&alice))?;
+    assert_eq!(details.storage_usage, 182);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rpc_failover() -> anyhow::Result<()> {
+    // A port nothing listens on anymore, standing in for an RPC node having an outage.
+    let dead_rpc = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        format!("http://{}", listener.local_addr()?)
+    };
+
+    let backup = near_workspaces::mock().await?;
+    backup.respond(
+        "status",
+        json!({
+            "version": { "version": "2.0.0", "build": "mock" },
+            "chain_id": "mock",
+            "protocol_version": 73,
+            "latest_protocol_version": 73,
+            "validators": [],
+            "sync_info": {
+                "latest_block_hash": "11111111111111111111111111111111",
+                "latest_block_height": 1,
+                "latest_state_root": "11111111111111111111111111111111",
+                "latest_block_time": "2024-01-01T00:00:00Z",
+                "syncing": false,
+                "earliest_block_hash": null,
+                "earliest_block_height": null,
+                "earliest_block_time": null,
+                "epoch_id": null,
+                "epoch_start_height": null,
+            },
+            "validator_account_id": null,
+            "validator_public_key": null,
+            "node_public_key": "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp",
+            "node_key": null,
+            "uptime_sec": 0,
+            "genesis_hash": "11111111111111111111111111111111",
+        }),
+    );
+    backup.respond("gas_price", json!({ "gas_price": "100" }));
+
+    let worker = near_workspaces::mainnet()
+        .rpc_addrs(&[&dead_rpc, backup.info().rpc_url.as_str()])
+        .await?;
+    assert_eq!(worker.gas_price().await?, NearToken::from_yoctonear(100));
+    assert_eq!(worker.gas_price().await?, NearToken::from_yoctonear(100));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_summarize() -> anyhow::Result<()> {
+    let worker = near_workspaces::mock().await?;
+    let alice: AccountId = "alice.near".parse()?;
+    let bob: AccountId = "bob.near".parse()?;
+
+    worker.respond_error(
+        "query",
+        json!({
+            "code": -32000,
+            "message": "Server error",
+            "name": "HANDLER_ERROR",
+            "cause": {
+                "name": "UNKNOWN_ACCOUNT",
+                "info": {
+                    "requested_account_id": "bob.near",
+                    "block_height": 1,
+                    "block_hash": "11111111111111111111111111111111",
+                },
+            },
+        }),
+    );
+    worker.respond_to(
+        "query",
+        json!({ "request_type": "view_account", "account_id": "alice.near" }),
+        json!({
+            "amount": NearToken::from_near(10).as_yoctonear().to_string(),
+            "locked": "0",
+            "code_hash": "11111111111111111111111111111111",
+            "storage_usage": 182,
+            "storage_paid_at": 0,
+            "block_height": 1,
+            "block_hash": "11111111111111111111111111111111",
+        }),
+    );
+    worker.label(&alice, "buyer");
+    worker.label(&bob, "seller");
+
+    let summary = worker.summarize().await?;
+    assert_eq!(summary.accounts.len(), 2);
+    let details = summary.account(&alice).unwrap().details.as_ref().unwrap();
+    assert_eq!(details.balance, NearToken::from_near(10));
+    assert!(summary.account(&bob).unwrap().details.is_none());
+
+    let table = summary.to_string();
+    assert!(table.contains("buyer (alice.near)"), "{}", table);
+    assert!(
+        table
+            .lines()
+            .any(|line| line.starts_with("seller (bob.near)") && line.ends_with("deleted")),
+        "{}",
+        table
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_contract_registry() -> anyhow::Result<()> {
+    let mainnet = near_workspaces::mock().await?;
+    let respond_code = |code: &[u8]| {
+        mainnet.respond_to(
+            "query",
+            json!({ "request_type": "view_code", "account_id": "wrap.near" }),
+            json!({
+                "code_base64": general_purpose::STANDARD.encode(code),
+                "hash": "11111111111111111111111111111111",
+                "block_height": 1,
+                "block_hash": "11111111111111111111111111111111",
+            }),
+        );
+    };
+
+    let registry = ContractRegistry::new()
+        .register("token", WasmSource::bytes(b"token".to_vec()))
+        .register("wrap", WasmSource::account("wrap.near".parse()?, &mainnet));
+    let mut names = registry.names().collect::<Vec<_>>();
+    names.sort_unstable();
+    assert_eq!(names, ["token", "wrap"]);
+
+    assert_eq!(registry.wasm("token").await?, b"token");
+
+    respond_code(b"wrap v1");
+    assert_eq!(registry.wasm("wrap").await?, b"wrap v1");
+
+    // Resolved once, then answered from the cache shared with clones:
+    respond_code(b"wrap v2");
+    assert_eq!(registry.clone().wasm("wrap").await?, b"wrap v1");
+
+    let err = registry.wasm("unknown").await.unwrap_err();
+    assert!(err.to_string().contains("unknown"), "{}", err);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_declared_signers() -> anyhow::Result<()> {
+    use near_workspaces::rpc::retry::RetryPolicy;
+
+    let worker = near_workspaces::mock()
+        .retry_policy(RetryPolicy::none())
+        .await?;
+    let sk = SecretKey::from_seed(KeyType::ED25519, "test");
+    let alice = Account::from_secret_key("alice.near".parse()?, sk.clone(), &worker);
+    let bob = Account::from_secret_key("bob.near".parse()?, sk, &worker);
+
+    let not_allowed =
+        |err: near_workspaces::error::Error| err.to_string().contains("not allowed to sign");
+
+    // Any account can sign until signers get declared:
+    let err = bob
+        .transfer_near(alice.id(), NearToken::from_yoctonear(1))
+        .await;
+    assert!(!not_allowed(err.unwrap_err()));
+
+    worker.declare_signers([alice.id().clone()]);
+    let err = bob
+        .transfer_near(alice.id(), NearToken::from_yoctonear(1))
+        .await;
+    assert!(not_allowed(err.unwrap_err()));
+    let err = bob.batch(alice.id()).transact_async().await;
+    assert!(not_allowed(err.unwrap_err()));
+
+    // Declared accounts still go through to the network, which has no fixtures to answer:
+    let err = alice
+        .transfer_near(bob.id(), NearToken::from_yoctonear(1))
+        .await;
+    assert!(!not_allowed(err.unwrap_err()));
+
+    worker.declare_signers([bob.id().clone()]);
+    let err = bob
+        .transfer_near(alice.id(), NearToken::from_yoctonear(1))
+        .await;
+    assert!(!not_allowed(err.unwrap_err()));
+
+    Ok(())
+}
+
+/// Counts the transactions sent through it.
+#[derive(Clone, Default)]
+struct CountTransactions(Arc<AtomicUsize>);
+
+impl Layer for CountTransactions {
+    fn call<'a>(&'a self, request: Value, next: Next<'a>) -> BoxFuture<'a, Value> {
+        if request["method"] == "broadcast_tx_commit" {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+        Box::pin(next.run(request))
+    }
+}
+
+#[tokio::test]
+async fn test_chunked_call() -> anyhow::Result<()> {
+    use near_workspaces::rpc::retry::RetryPolicy;
+
+    let counter = CountTransactions::default();
+    let worker = near_workspaces::mock()
+        .retry_policy(RetryPolicy::none())
+        .layer(counter.clone())
+        .await?;
+    let sk = SecretKey::from_seed(KeyType::ED25519, "alice");
+    let alice = Account::from_secret_key("alice.near".parse()?, sk, &worker);
+    worker.respond_to(
+        "query",
+        json!({ "request_type": "view_access_key", "account_id": "alice.near" }),
+        json!({
+            "nonce": 0,
+            "permission": "FullAccess",
+            "block_height": 1,
+            "block_hash": "11111111111111111111111111111111",
+        }),
+    );
+    let oracle: AccountId = "oracle.near".parse()?;
+
+    let call = alice
+        .chunked_call(&oracle, "append", &[0; 10])
+        .chunk_size(3);
+    assert_eq!(call.num_chunks(), 4);
+
+    // Nothing to send for an empty payload:
+    let outcomes = alice
+        .chunked_call(&oracle, "append", &[])
+        .transact()
+        .await?;
+    assert!(outcomes.is_empty());
+    assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+
+    // No fixture answers the transactions, so the first chunk fails and the rest never get sent:
+    assert!(call.transact().await.is_err());
+    assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+
+    Ok(())
+}
+
+/// Outcome of `alice.near` calling `withdraw` on `market.near`, with the receipt of the call
+/// having the given `status`. The transaction, its receipt and the block they got executed in
+/// all have a hash made of zeroes.
+fn call_outcome(status: Value) -> Value {
+    let hash = "11111111111111111111111111111111";
+    let outcome = |executor_id: &str, status: Value| {
+        json!({
+            "proof": [],
+            "block_hash": hash,
+            "id": hash,
+            "outcome": {
+                "logs": [],
+                "receipt_ids": [],
+                "gas_burnt": 2_428_000_000_000u64,
+                "tokens_burnt": "242800000000000000000",
+                "executor_id": executor_id,
+                "status": status,
+                "metadata": { "version": 1, "gas_profile": null },
+            },
+        })
+    };
+    let mut transaction_outcome = outcome("alice.near", json!({ "SuccessReceiptId": hash }));
+    transaction_outcome["outcome"]["receipt_ids"] = json!([hash]);
+    json!({
+        "status": status,
+        "transaction": {
+            "signer_id": "alice.near",
+            "public_key": format!("ed25519:{}", hash),
+            "nonce": 1,
+            "receiver_id": "market.near",
+            "actions": [{ "FunctionCall": {
+                "method_name": "withdraw",
+                "args": "e30=",
+                "gas": 30_000_000_000_000u64,
+                "deposit": "0",
+            } }],
+            "signature": format!("ed25519:{}", "1".repeat(64)),
+            "hash": hash,
+        },
+        "transaction_outcome": transaction_outcome,
+        "receipts_outcome": [outcome("market.near", status)],
+    })
+}
+
+#[tokio::test]
+async fn test_execution_failure() -> anyhow::Result<()> {
+    use near_workspaces::result::{ActionErrorKind, FunctionCallError, TxExecutionError};
+
+    let worker = near_workspaces::mock().await?;
+    // Every account sends a single transaction, as later ones would need a block to sign with:
+    let signer = |name: &str| -> anyhow::Result<Account> {
+        let id: AccountId = format!("{name}.near").parse()?;
+        worker.respond_to(
+            "query",
+            json!({ "request_type": "view_access_key", "account_id": id }),
+            json!({
+                "nonce": 0,
+                "permission": "FullAccess",
+                "block_height": 1,
+                "block_hash": "11111111111111111111111111111111",
+            }),
+        );
+        let sk = SecretKey::from_seed(KeyType::ED25519, name);
+        Ok(Account::from_secret_key(id, sk, &worker))
+    };
+    let market: AccountId = "market.near".parse()?;
+
+    worker.respond(
+        "broadcast_tx_commit",
+        call_outcome(json!({ "Failure": { "ActionError": {
+            "index": 0,
+            "kind": { "FunctionCallError": {
+                "ExecutionError": "Smart contract panicked: ERR_NOT_OWNER",
+            } },
+        } } })),
+    );
+    let outcome = signer("alice")?
+        .call(&market, "withdraw")
+        .transact()
+        .await?;
+    assert!(outcome.failure_message_contains("ERR_NOT_OWNER"));
+    assert!(!outcome.failure_message_contains("ERR_NOT_ENOUGH"));
+    assert!(matches!(
+        outcome.failure(),
+        Some(TxExecutionError::ActionError(_))
+    ));
+
+    let failure = signer("bob")?
+        .call(&market, "withdraw")
+        .expect_failure()
+        .await?;
+    assert_eq!(failure.panic_message(), Some("ERR_NOT_OWNER"));
+    assert_eq!(failure.action_error().unwrap().index, Some(0));
+    assert!(matches!(
+        failure.function_call_error(),
+        Some(FunctionCallError::ExecutionError(_))
+    ));
+    assert!(matches!(
+        failure.into_error(),
+        TxExecutionError::ActionError(err)
+            if matches!(err.kind, ActionErrorKind::FunctionCallError(_))
+    ));
+
+    // Calls that succeed are not what was expected:
+    worker.respond(
+        "broadcast_tx_commit",
+        call_outcome(json!({ "SuccessValue": "" })),
+    );
+    let outcome = signer("carol")?
+        .call(&market, "withdraw")
+        .transact()
+        .await?;
+    assert!(outcome.failure().is_none());
+    let err = signer("dave")?
+        .call(&market, "withdraw")
+        .expect_failure()
+        .await;
+    assert!(err.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_scenario_report() -> anyhow::Result<()> {
+    use near_workspaces::types::Scenario;
+
+    let mut worker = near_workspaces::mock().await?;
+    let scenario = Scenario::now(&mut worker, "withdrawals");
+    let signer = |name: &str| -> anyhow::Result<Account> {
+        let id: AccountId = format!("{name}.near").parse()?;
+        worker.respond_to(
+            "query",
+            json!({ "request_type": "view_access_key", "account_id": id }),
+            json!({
+                "nonce": 0,
+                "permission": "FullAccess",
+                "block_height": 1,
+                "block_hash": "11111111111111111111111111111111",
+            }),
+        );
+        let sk = SecretKey::from_seed(KeyType::ED25519, name);
+        Ok(Account::from_secret_key(id, sk, &worker))
+    };
+    let market: AccountId = "market.near".parse()?;
+    worker.label(&market, "market");
+
+    scenario.note("owner withdraws")?;
+    worker.respond(
+        "broadcast_tx_commit",
+        call_outcome(json!({ "SuccessValue": "" })),
+    );
+    signer("alice")?
+        .call(&market, "withdraw")
+        .transact()
+        .await?
+        .into_result()?;
+
+    scenario.note("stranger withdraws")?;
+    worker.respond(
+        "broadcast_tx_commit",
+        call_outcome(json!({ "Failure": { "ActionError": {
+            "index": 0,
+            "kind": { "FunctionCallError": {
+                "ExecutionError": "Smart contract panicked: ERR_NOT_OWNER",
+            } },
+        } } })),
+    );
+    let outcome = signer("bob")?.call(&market, "withdraw").transact().await?;
+    assert!(outcome.is_failure());
+
+    assert_eq!(scenario.transactions()?.len(), 2);
+    assert!(scenario.actors()?.contains(&market));
+
+    let report = scenario.report()?;
+    let lines = report.lines().collect::<Vec<_>>();
+    assert!(
+        lines[0].starts_with("scenario `withdrawals`: 2 transactions"),
+        "{}",
+        report
+    );
+    assert!(lines[0].ends_with("1 failed"), "{}", report);
+    assert!(lines[1].contains("market (market.near)"), "{}", report);
+    assert!(lines[2].ends_with("note: owner withdraws"), "{}", report);
+    assert!(
+        lines[3].contains("#1 alice.near -> market.withdraw: succeeded"),
+        "{}",
+        report
+    );
+    assert!(
+        report.contains("#2 alice.near -> market.withdraw: failed"),
+        "{}",
+        report
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_storage_report() -> anyhow::Result<()> {
+    let worker = near_workspaces::mock().await?;
+    let sk = SecretKey::from_seed(KeyType::ED25519, "alice");
+    let alice = Account::from_secret_key("alice.near".parse()?, sk, &worker);
+    worker.respond_to(
+        "query",
+        json!({ "request_type": "view_access_key", "account_id": "alice.near" }),
+        json!({
+            "nonce": 0,
+            "permission": "FullAccess",
+            "block_height": 1,
+            "block_hash": "11111111111111111111111111111111",
+        }),
+    );
+    let outcome = call_outcome(json!({ "SuccessValue": "" }));
+    worker.respond("broadcast_tx_commit", outcome.clone());
+    let mut with_receipts = outcome;
+    with_receipts["receipts"] = json!([]);
+    with_receipts["final_execution_status"] = json!("FINAL");
+    worker.respond("EXPERIMENTAL_tx_status", with_receipts);
+
+    let receipt =
+        json!({ "type": "receipt_processing", "receipt_hash": "11111111111111111111111111111111" });
+    let other =
+        json!({ "type": "receipt_processing", "receipt_hash": "11111111111111111111111111111112" });
+    worker.respond_to(
+        "EXPERIMENTAL_changes",
+        json!({ "changes_type": "data_changes" }),
+        json!({
+            "block_hash": "11111111111111111111111111111111",
+            "changes": [
+                // "key" => "value", "k" deleted, and a write of another receipt:
+                { "cause": receipt, "type": "data_update", "change": {
+                    "account_id": "market.near", "key_base64": "a2V5", "value_base64": "dmFsdWU=",
+                } },
+                { "cause": receipt, "type": "data_deletion", "change": {
+                    "account_id": "market.near", "key_base64": "aw==",
+                } },
+                { "cause": other, "type": "data_update", "change": {
+                    "account_id": "market.near", "key_base64": "a2V5", "value_base64": "dmFsdWU=",
+                } },
+            ],
+        }),
+    );
+    let account = |storage_usage: u64| {
+        json!({
+            "account_id": "market.near",
+            "amount": "0",
+            "locked": "0",
+            "code_hash": "11111111111111111111111111111111",
+            "storage_usage": storage_usage,
+            "storage_paid_at": 0,
+        })
+    };
+    worker.respond_to(
+        "EXPERIMENTAL_changes",
+        json!({ "changes_type": "account_changes" }),
+        json!({
+            "block_hash": "11111111111111111111111111111111",
+            "changes": [
+                { "cause": other, "type": "account_update", "change": account(1000) },
+                { "cause": receipt, "type": "account_update", "change": account(1049) },
+            ],
+        }),
+    );
+
+    let market: AccountId = "market.near".parse()?;
+    let result = alice.call(&market, "withdraw").transact().await?;
+    let report = worker.storage_report(&[result.clone(), result]).await?;
+    assert_eq!(report.receipts.len(), 2);
+    assert_eq!(report.receipts[0].account_id, market);
+    assert_eq!(report.receipts[0].bytes_written, 8);
+    assert_eq!(report.receipts[0].bytes_deleted, 1);
+    assert_eq!(report.receipts[0].storage_growth, 49);
+
+    let withdraw = report.by_method()["withdraw"];
+    assert_eq!(withdraw.calls, 2);
+    assert_eq!(withdraw.storage_growth, 98);
+    assert_eq!(withdraw.growth_per_call(), 49.0);
+    assert_eq!(report.over_budget(48).len(), 1);
+    assert!(report.over_budget(49).is_empty());
+    assert!(
+        report.to_string().starts_with("withdraw: 2 calls"),
+        "{}",
+        report
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_receipt_tree() -> anyhow::Result<()> {
+    let worker = near_workspaces::mock().await?;
+    let sk = SecretKey::from_seed(KeyType::ED25519, "alice");
+    let alice = Account::from_secret_key("alice.near".parse()?, sk, &worker);
+    worker.respond_to(
+        "query",
+        json!({ "request_type": "view_access_key", "account_id": "alice.near" }),
+        json!({
+            "nonce": 0,
+            "permission": "FullAccess",
+            "block_height": 1,
+            "block_hash": "11111111111111111111111111111111",
+        }),
+    );
+
+    // `withdraw` on market.near calls into `ft_transfer` on token.near, which fails, followed
+    // by the `on_transfer` callback on market.near:
+    let (withdraw, transfer, callback) = (
+        "11111111111111111111111111111111",
+        "11111111111111111111111111111112",
+        "11111111111111111111111111111113",
+    );
+    let mut outcome = call_outcome(json!({ "SuccessValue": "" }));
+    outcome["receipts_outcome"][0]["outcome"]["receipt_ids"] = json!([transfer, callback]);
+    let mut receipt_outcome = |id: &str, executor_id: &str, status: Value| {
+        let mut receipt = outcome["receipts_outcome"][0].clone();
+        receipt["id"] = json!(id);
+        receipt["outcome"]["executor_id"] = json!(executor_id);
+        receipt["outcome"]["receipt_ids"] = json!([]);
+        receipt["outcome"]["status"] = status;
+        outcome["receipts_outcome"]
+            .as_array_mut()
+            .unwrap()
+            .push(receipt);
+    };
+    receipt_outcome(
+        transfer,
+        "token.near",
+        json!({ "Failure": { "ActionError": {
+            "index": 0,
+            "kind": { "FunctionCallError": {
+                "ExecutionError": "Smart contract panicked: not enough balance",
+            } },
+        } } }),
+    );
+    receipt_outcome(callback, "market.near", json!({ "SuccessValue": "" }));
+    worker.respond("broadcast_tx_commit", outcome.clone());
+
+    let receipt = |id: &str, predecessor_id: &str, receiver_id: &str, method_name: &str| {
+        json!({
+            "predecessor_id": predecessor_id,
+            "receiver_id": receiver_id,
+            "receipt_id": id,
+            "receipt": { "Action": {
+                "signer_id": "alice.near",
+                "signer_public_key": format!("ed25519:{}", withdraw),
+                "gas_price": "100000000",
+                "output_data_receivers": [],
+                "input_data_ids": [],
+                "actions": [{ "FunctionCall": {
+                    "method_name": method_name,
+                    "args": "e30=",
+                    "gas": 10_000_000_000_000u64,
+                    "deposit": "0",
+                } }],
+            } },
+        })
+    };
+    outcome["final_execution_status"] = json!("FINAL");
+    outcome["receipts"] = json!([
+        receipt(withdraw, "alice.near", "market.near", "withdraw"),
+        receipt(transfer, "market.near", "token.near", "ft_transfer"),
+        receipt(callback, "market.near", "market.near", "on_transfer"),
+    ]);
+    worker.respond("EXPERIMENTAL_tx_status", outcome);
+
+    let result = alice
+        .call(&"market.near".parse()?, "withdraw")
+        .transact()
+        .await?;
+    let tree = worker.receipt_tree(&result).await?;
+    assert_eq!(tree.receipts.len(), 1);
+    let root = &tree.receipts[0];
+    assert_eq!(root.method_names(), ["withdraw"]);
+    assert_eq!(root.children.len(), 2);
+    assert_eq!(tree.nodes().len(), 3);
+
+    let path = tree.failure_path();
+    let hops = path
+        .iter()
+        .map(|receipt| receipt.receiver_id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(hops, ["market.near", "token.near"]);
+    assert_eq!(path[1].predecessor_id.as_str(), "market.near");
+    assert_eq!(path[1].method_names(), ["ft_transfer"]);
+    assert_eq!(tree.failures().len(), 1);
+
+    let rendered = tree.to_string();
+    assert!(
+        rendered.contains("\n  market.near -> token.near.ft_transfer: failed: "),
+        "{}",
+        rendered
+    );
+    assert!(
+        rendered.contains("\n  market.near -> market.near.on_transfer: succeeded"),
+        "{}",
+        rendered
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_blocks_until_epoch_end() -> anyhow::Result<()> {
+    let worker = near_workspaces::mock().await?;
+    worker.respond("EXPERIMENTAL_genesis_config", json!({ "epoch_length": 60 }));
+    worker.respond(
+        "status",
+        json!({
+            "version": { "version": "2.0.0", "build": "mock" },
+            "chain_id": "mock",
+            "protocol_version": 73,
+            "latest_protocol_version": 73,
+            "validators": [],
+            "sync_info": {
+                "latest_block_hash": "11111111111111111111111111111111",
+                "latest_block_height": 130,
+                "latest_state_root": "11111111111111111111111111111111",
+                "latest_block_time": "2024-01-01T00:00:00Z",
+                "syncing": false,
+                "earliest_block_hash": null,
+                "earliest_block_height": null,
+                "earliest_block_time": null,
+                "epoch_id": null,
+                "epoch_start_height": 120,
+            },
+            "validator_account_id": null,
+            "validator_public_key": null,
+            "node_public_key": "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp",
+            "node_key": null,
+            "uptime_sec": 0,
+            "genesis_hash": "11111111111111111111111111111111",
+        }),
+    );
+
+    assert_eq!(worker.blocks_until_epoch_end().await?, 50);
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use serde_json::json;
+use test_log::test;
+
+use near_workspaces::types::{NearToken, OutcomeIndex};
+
+const FT_WASM_FILEPATH: &str = "../examples/res/fungible_token.wasm";
+
+#[test(tokio::test)]
+async fn test_outcome_index() -> anyhow::Result<()> {
+    let mut worker = near_workspaces::sandbox().await?;
+    let index = OutcomeIndex::now(&mut worker);
+
+    let contract = worker.dev_deploy(&std::fs::read(FT_WASM_FILEPATH)?).await?;
+    contract
+        .call("new_default_meta")
+        .args_json(json!({
+            "owner_id": contract.id(),
+            "total_supply": NearToken::from_near(1_000).as_yoctonear().to_string(),
+        }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let bob = worker.dev_create_account().await?;
+    bob.call(contract.id(), "storage_deposit")
+        .deposit(NearToken::from_millinear(125))
+        .transact()
+        .await?
+        .into_result()?;
+
+    for _ in 0..3 {
+        contract
+            .call("ft_transfer")
+            .args_json(json!({ "receiver_id": bob.id(), "amount": "100" }))
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await?
+            .into_result()?;
+    }
+
+    // deploy, new_default_meta, create account, storage_deposit, and 3 transfers:
+    assert_eq!(index.transactions()?.len(), 7);
+    assert_eq!(index.calls("ft_transfer")?.len(), 3);
+    assert_eq!(index.events("nep141", "ft_transfer")?.len(), 3);
+    assert!(!index.outcomes_of(bob.id())?.is_empty());
+    assert!(index.failures()?.is_empty());
+
+    index.reset()?;
+    assert!(index.transactions()?.is_empty());
+
+    Ok(())
+}
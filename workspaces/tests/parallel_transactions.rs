@@ -96,3 +96,78 @@ async fn test_parallel_async() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_parallel_join_all() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker.dev_deploy(STATUS_MSG_CONTRACT).await?;
+    let account = worker.dev_create_account().await?;
+
+    let nonce_start = worker
+        .view_access_key(account.id(), &account.secret_key().public_key())
+        .await?
+        .nonce;
+
+    // None of the nonces are cached yet, so every transaction races to fetch the one on chain.
+    let count = 20;
+    let results = futures::future::join_all((0..count).map(|i| {
+        account
+            .call(contract.id(), "set_status")
+            .args_json(json!({ "message": i.to_string() }))
+            .transact()
+    }))
+    .await;
+    for result in results {
+        result?.into_result()?;
+    }
+
+    let nonce_end = worker
+        .view_access_key(account.id(), &account.secret_key().public_key())
+        .await?
+        .nonce;
+    assert_eq!(nonce_end - nonce_start, count);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transaction_status_by_hash() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker.dev_deploy(STATUS_MSG_CONTRACT).await?;
+    let account = worker.dev_create_account().await?;
+
+    let status = account
+        .call(contract.id(), "set_status")
+        .args_json(json!({ "message": "hello" }))
+        .transact_async()
+        .await?;
+    let (hash, sender_id) = (*status.hash(), status.sender_id().clone());
+    drop(status);
+
+    // Look the transaction up again, only knowing its hash and signer.
+    let outcome = worker.transaction_status(hash, &sender_id).await?;
+    assert!(outcome.is_success());
+    assert_eq!(outcome.outcome().transaction_hash, hash);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_parallel_sandboxes() -> anyhow::Result<()> {
+    let workers = futures::future::try_join_all((0..4).map(|_| async {
+        let worker = near_workspaces::sandbox().await?;
+        worker.dev_deploy(STATUS_MSG_CONTRACT).await?;
+        anyhow::Ok(worker)
+    }))
+    .await?;
+
+    let mut ports = std::collections::HashSet::new();
+    let mut home_dirs = std::collections::HashSet::new();
+    for worker in &workers {
+        assert!(ports.insert(worker.rpc_port().unwrap()));
+        assert!(ports.insert(worker.net_port().unwrap()));
+        assert!(home_dirs.insert(worker.home_dir().unwrap().to_path_buf()));
+    }
+
+    Ok(())
+}
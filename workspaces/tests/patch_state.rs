@@ -6,6 +6,7 @@ use near_token::NearToken;
 use serde_json::json;
 use test_log::test;
 
+use near_workspaces::rpc::patch::StateDump;
 use near_workspaces::types::{KeyType, SecretKey};
 use near_workspaces::{AccessKey, AccountDetailsPatch, AccountId, Contract, DevNetwork, Worker};
 
@@ -210,3 +211,228 @@ async fn test_patch_account_from_current() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test(tokio::test)]
+async fn test_import_contracts() -> anyhow::Result<()> {
+    let from = near_workspaces::sandbox().await?;
+    let (first_id, _) = view_status_state(&from).await?;
+    let (second_id, _) = view_status_state(&from).await?;
+
+    let worker = near_workspaces::sandbox().await?;
+    let contracts = worker
+        .import_contracts([first_id.clone(), second_id.clone()], &from)
+        .with_data()
+        .transact()
+        .await?;
+    assert_eq!(contracts.len(), 2);
+    assert_eq!(contracts[0].id(), &first_id);
+    assert_eq!(contracts[1].id(), &second_id);
+
+    for contract in contracts {
+        let msg: String = contract
+            .view("get_status")
+            .args_json(json!({
+                "account_id": contract.id(),
+            }))
+            .await?
+            .json()?;
+        assert_eq!(msg, "hello".to_string());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_patch_account() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let bob = worker.dev_create_account().await?;
+    let before = worker.view_account(bob.id()).await?;
+
+    const NEW_BALANCE: NearToken = NearToken::from_near(42);
+    const NEW_LOCKED: NearToken = NearToken::from_near(7);
+    worker
+        .patch_account(
+            bob.id(),
+            AccountDetailsPatch::default()
+                .balance(NEW_BALANCE)
+                .locked(NEW_LOCKED),
+        )
+        .await?;
+
+    let after = worker.view_account(bob.id()).await?;
+    assert_eq!(after.balance, NEW_BALANCE);
+    assert_eq!(after.locked, NEW_LOCKED);
+
+    // Fields left unset keep their previous values.
+    assert_eq!(after.storage_usage, before.storage_usage);
+    assert_eq!(after.code_hash, before.code_hash);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_patch_access_key() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let bob = worker.dev_create_account().await?;
+
+    // Take over bob's account with a key that was never added through a transaction.
+    let sk = SecretKey::from_seed(KeyType::ED25519, "takeover");
+    worker
+        .patch_access_key(bob.id(), sk.public_key(), AccessKey::full_access())
+        .await?;
+
+    let key = worker.view_access_key(bob.id(), &sk.public_key()).await?;
+    assert!(matches!(
+        key.permission,
+        near_workspaces::types::AccessKeyPermission::FullAccess
+    ));
+
+    let takeover = near_workspaces::Account::from_secret_key(bob.id().clone(), sk, &worker);
+    takeover
+        .transfer_near(worker.root_account()?.id(), NearToken::from_near(1))
+        .await?
+        .into_result()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_patch_code() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let (contract_id, status_msg) = view_status_state(&worker).await?;
+
+    // Swap the code for another contract and back again. The state is left untouched, so
+    // the original code keeps on working with it.
+    let wasm = std::fs::read(STATUS_MSG_WASM_FILEPATH)?;
+    let noop = std::fs::read("../examples/res/noop_contract.wasm")?;
+    worker.patch_code(&contract_id, &noop).await?;
+    assert_eq!(worker.view_code(&contract_id).await?, noop);
+
+    worker.patch_code(&contract_id, &wasm).await?;
+    assert_eq!(worker.view_code(&contract_id).await?, wasm);
+
+    let state = worker
+        .view_state(&contract_id)
+        .await?
+        .remove(b"STATE".as_slice())
+        .ok_or_else(|| anyhow::anyhow!("Could not retrieve STATE"))?;
+    assert_eq!(StatusMessage::try_from_slice(&state)?, status_msg);
+
+    let msg: String = worker
+        .view(&contract_id, "get_status")
+        .args_json(json!({ "account_id": contract_id }))
+        .await?
+        .json()?;
+    assert_eq!(msg, "hello");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_batch_patch() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let (first_id, status_msg) = view_status_state(&worker).await?;
+    let (second_id, _) = view_status_state(&worker).await?;
+    let bob = worker.dev_create_account().await?;
+
+    let mut first_msg = status_msg.clone();
+    first_msg.records.push(Record {
+        k: "alice.near".to_string(),
+        v: "first".to_string(),
+    });
+    let mut second_msg = status_msg;
+    second_msg.records.push(Record {
+        k: "alice.near".to_string(),
+        v: "second".to_string(),
+    });
+
+    const NEW_BALANCE: NearToken = NearToken::from_near(3);
+    worker
+        .batch_patch()
+        .state(&first_id, b"STATE", &borsh::to_vec(&first_msg)?)
+        .state(&second_id, b"STATE", &borsh::to_vec(&second_msg)?)
+        .patch(
+            worker
+                .patch(bob.id())
+                .account(AccountDetailsPatch::default().balance(NEW_BALANCE)),
+        )
+        .transact()
+        .await?;
+
+    for (contract_id, expected) in [(&first_id, "first"), (&second_id, "second")] {
+        let status: String = worker
+            .view(contract_id, "get_status")
+            .args_json(json!({ "account_id": "alice.near" }))
+            .await?
+            .json()?;
+        assert_eq!(status, expected);
+    }
+    assert_eq!(worker.view_account(bob.id()).await?.balance, NEW_BALANCE);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_state() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let (contract_id, _) = view_status_state(&worker).await?;
+    let code = worker.view_code(&contract_id).await?;
+
+    worker
+        .patch(&contract_id)
+        .states([
+            (b"a".as_slice(), b"1".as_slice()),
+            (b"b", b"2"),
+            (b"c", b"3"),
+        ])
+        .transact()
+        .await?;
+    worker.delete_state(&contract_id, b"a").await?;
+    worker
+        .delete_states(&contract_id, [b"b".as_slice(), b"missing"])
+        .await?;
+
+    let state = worker.view_state(&contract_id).await?;
+    let mut keys: Vec<_> = state.keys().map(Vec::as_slice).collect();
+    keys.sort();
+    assert_eq!(keys, vec![b"STATE".as_slice(), b"c"]);
+
+    // The original code is back in place, and keeps on working.
+    assert_eq!(worker.view_code(&contract_id).await?, code);
+    let msg: String = worker
+        .view(&contract_id, "get_status")
+        .args_json(json!({ "account_id": contract_id }))
+        .await?
+        .json()?;
+    assert_eq!(msg, "hello");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dump_and_load_state() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let (contract_id, _) = view_status_state(&worker).await?;
+
+    let dump = worker.dump_state().await?;
+    let account_ids: Vec<_> = dump.account_ids().collect();
+    assert!(account_ids.contains(&&contract_id));
+    assert!(account_ids.contains(&worker.root_account()?.id()));
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("records.json");
+    dump.save(&path)?;
+    assert_eq!(StateDump::load(&path)?, dump);
+
+    // Seed another sandbox with the dump, where the contract works as it did before.
+    let other = near_workspaces::sandbox().await?;
+    other.load_state(&dump).await?;
+    let msg: String = other
+        .view(&contract_id, "get_status")
+        .args_json(json!({ "account_id": contract_id }))
+        .await?
+        .json()?;
+    assert_eq!(msg, "hello");
+
+    Ok(())
+}
@@ -45,3 +45,167 @@ async fn test_invalid_query() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_consistent_view() -> anyhow::Result<()> {
+    let (worker, contract) = init().await?;
+
+    contract
+        .call("set_status")
+        .args_json(("before",))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let (block, status) = worker
+        .consistent_view(|pinned| async move {
+            let block = pinned.view_block().await?;
+
+            // State changes after the pinned block should not be observed.
+            contract
+                .call("set_status")
+                .args_json(("after",))
+                .transact()
+                .await?
+                .into_result()?;
+
+            let status: String = pinned
+                .view(contract.id(), "get_status")
+                .args_json(serde_json::json!({ "account_id": contract.id() }))
+                .await?
+                .json()?;
+            anyhow::Ok((block, status))
+        })
+        .await??;
+
+    assert_eq!(status, "before");
+    assert!(worker.view_block().await?.height() > block.height());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_find_block_where() -> anyhow::Result<()> {
+    let (worker, contract) = init().await?;
+    let start = worker.view_block().await?.height();
+
+    worker.fast_forward(10).await?;
+    contract
+        .call("set_status")
+        .args_json(("hello",))
+        .transact()
+        .await?
+        .into_result()?;
+    let end = worker.view_block().await?.height();
+
+    let block = worker
+        .find_block_where(start, end, |block| {
+            let worker = worker.clone();
+            let contract_id = contract.id().clone();
+            async move {
+                let status: Option<String> = worker
+                    .view(&contract_id, "get_status")
+                    .args_json(serde_json::json!({ "account_id": contract_id }))
+                    .block_hash(*block.hash())
+                    .await?
+                    .json()?;
+                Ok(status.is_some())
+            }
+        })
+        .await?
+        .expect("status should have been set within the range");
+
+    assert!(block.height() > start + 10);
+    assert!(block.height() <= end);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_view_at_block_reference() -> anyhow::Result<()> {
+    use near_workspaces::types::Finality;
+
+    let (worker, contract) = init().await?;
+    let status = |block: near_workspaces::types::BlockReference| {
+        let contract = contract.clone();
+        async move {
+            let status: Option<String> = contract
+                .view("get_status")
+                .args_json(serde_json::json!({ "account_id": contract.id() }))
+                .block_reference(block)
+                .await?
+                .json()?;
+            anyhow::Ok(status)
+        }
+    };
+
+    let before = worker.view_block().block_reference(Finality::Final).await?;
+    contract
+        .call("set_status")
+        .args_json(("hello",))
+        .transact()
+        .await?
+        .into_result()?;
+
+    assert_eq!(status(before.height().into()).await?, None);
+    assert_eq!(status((*before.hash()).into()).await?, None);
+    assert_eq!(
+        status(Finality::Optimistic.into()).await?,
+        Some("hello".to_string())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_call_with_finality() -> anyhow::Result<()> {
+    use near_workspaces::types::Finality;
+
+    let (_worker, contract) = init().await?;
+    contract
+        .call("set_status")
+        .args_json(("final",))
+        .finality(Finality::Final)
+        .no_retry()
+        .transact()
+        .await?
+        .into_result()?;
+
+    // The status is part of the latest final block by the time the call returns.
+    let status: String = contract
+        .as_account()
+        .call(contract.id(), "get_status")
+        .args_json(serde_json::json!({ "account_id": contract.id() }))
+        .finality(Finality::Final)
+        .view()
+        .await?
+        .json()?;
+    assert_eq!(status, "final");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_call_expect_failure() -> anyhow::Result<()> {
+    use near_workspaces::result::FunctionCallError;
+
+    let (_worker, contract) = init().await?;
+    let failure = contract.call("no_such_method").expect_failure().await?;
+    assert!(
+        matches!(
+            failure.function_call_error(),
+            Some(FunctionCallError::MethodResolveError(_))
+        ),
+        "{}",
+        failure
+    );
+
+    let err = contract
+        .call("set_status")
+        .args_json(("hello",))
+        .expect_failure()
+        .await;
+    assert!(err.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use serde_json::json;
+use test_log::test;
+
+const STATUS_MSG_WASM_FILEPATH: &str = "../examples/res/status_message.wasm";
+
+#[test(tokio::test)]
+async fn test_snapshot_and_restore() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let wasm = std::fs::read(STATUS_MSG_WASM_FILEPATH)?;
+    let contract = worker.dev_deploy(&wasm).await?;
+
+    contract
+        .call("set_status")
+        .args_json(json!({ "message": "before" }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let snapshot = worker.snapshot().await?;
+
+    contract
+        .call("set_status")
+        .args_json(json!({ "message": "after" }))
+        .transact()
+        .await?
+        .into_result()?;
+    let status: String = contract
+        .view("get_status")
+        .args_json(json!({ "account_id": contract.id() }))
+        .await?
+        .json()?;
+    assert_eq!(status, "after");
+
+    worker.restore(snapshot).await?;
+    let status: String = contract
+        .view("get_status")
+        .args_json(json!({ "account_id": contract.id() }))
+        .await?
+        .json()?;
+    assert_eq!(status, "before");
+
+    // Transactions keep working against the restored state.
+    contract
+        .call("set_status")
+        .args_json(json!({ "message": "restored" }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    Ok(())
+}
@@ -40,6 +40,96 @@ fn test_keypair_secp256k1() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_secret_key_from_seed_phrase() -> anyhow::Result<()> {
+    let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    let sk_expected = "ed25519:F1kPR175szkGxEL52A9H6Z5ocS2BtaipprK2Hiob9DjGzHTkScrBb1yt44baXPZ3LxyHcsTPdBjHmn6zx147txH";
+    let pk_expected = "ed25519:6j4b6zUaty6fD1awqcGCCU9JYGCWYUgdJhQrzfZhqE25";
+
+    let sk = SecretKey::from_seed_phrase(phrase, SecretKey::DEFAULT_HD_PATH)?;
+    assert_eq!(sk.expose_secret(), sk_expected);
+    assert_eq!(sk.public_key().to_string(), pk_expected);
+
+    // Casing and spacing of the words are not significant:
+    let messy = format!("  {}\n", phrase.to_uppercase().replace(' ', "\t "));
+    assert_eq!(SecretKey::from_seed_phrase(&messy, "m/44'/397'/0'")?, sk);
+
+    // Other paths derive other keys:
+    let other = SecretKey::from_seed_phrase(phrase, "m/44'/397'/1'")?;
+    assert_ne!(other, sk);
+
+    assert!(SecretKey::from_seed_phrase("abandon about", SecretKey::DEFAULT_HD_PATH).is_err());
+    for path in [
+        "44'/397'/0'",
+        "m/44/397'/0'",
+        "m/44'/x'/0'",
+        "m/2147483648'",
+    ] {
+        assert!(
+            SecretKey::from_seed_phrase(phrase, path).is_err(),
+            "{}",
+            path
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_secp256k1_roundtrip() -> anyhow::Result<()> {
+    let sk = SecretKey::from_random(KeyType::SECP256K1);
+    assert_eq!(sk.key_type(), KeyType::SECP256K1);
+    assert_eq!(SecretKey::from_str(&sk.expose_secret())?, sk);
+
+    let pk = sk.public_key();
+    assert_eq!(pk.key_type(), KeyType::SECP256K1);
+    assert_eq!(pk.len(), KeyType::SECP256K1.data_len() + 1);
+    assert_eq!(PublicKey::from_str(&pk.to_string())?, pk);
+    assert_eq!(
+        PublicKey::try_from_parts(KeyType::SECP256K1, pk.key_data())?,
+        pk
+    );
+
+    let signer = near_workspaces::types::InMemorySigner::from_secret_key("alice.near".parse()?, sk);
+    assert_eq!(signer.public_key(), pk);
+
+    Ok(())
+}
+
+#[test]
+fn test_implicit_account_id() -> anyhow::Result<()> {
+    let pk = SecretKey::from_seed(KeyType::ED25519, "test").public_key();
+    let id = pk.implicit_account_id()?;
+    let hex = pk
+        .key_data()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    assert_eq!(id.as_str(), hex);
+    assert_eq!(id.as_str().len(), 64);
+
+    let pk = SecretKey::from_seed(KeyType::SECP256K1, "test").public_key();
+    assert!(pk.implicit_account_id().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_secret_key_redacted() -> anyhow::Result<()> {
+    let sk = SecretKey::from_seed(KeyType::ED25519, "test");
+    let encoded = "ed25519:3KyUuch8pYP47krBq4DosFEVBMR5wDTMQ8AThzM8kAEcBQEpsPdYTZ2FPX5ZnSoLrerjwg66hwwJaW1wHzprd5k3";
+
+    assert_eq!(sk.to_string(), "ed25519:<redacted>");
+    assert_eq!(format!("{sk:?}"), "SecretKey(ed25519:<redacted>)");
+    assert_eq!(sk.expose_secret(), encoded);
+    assert_eq!(SecretKey::from_str(&sk.expose_secret())?, sk);
+
+    let signer = near_workspaces::types::InMemorySigner::from_secret_key("alice.near".parse()?, sk);
+    assert!(!format!("{signer:?}").contains(&encoded[8..]));
+
+    Ok(())
+}
+
 #[test]
 fn test_pubkey_serialization() -> anyhow::Result<()> {
     for key_type in [KeyType::ED25519, KeyType::SECP256K1] {
@@ -106,3 +196,88 @@ fn test_valid_account_id() {
         "Something changed underneath for testnet to not be a valid Account ID"
     );
 }
+
+#[test]
+fn test_args_encoders() -> anyhow::Result<()> {
+    use near_workspaces::operations::{ArgsEncoder, Borsh, Json};
+
+    let json = Json.encode(&serde_json::json!({ "message": "hello" }))?;
+    assert_eq!(json, br#"{"message":"hello"}"#);
+
+    let borsh = Borsh.encode(&("hello".to_string(), 7u32))?;
+    assert_eq!(borsh, borsh::to_vec(&("hello".to_string(), 7u32))?);
+
+    Ok(())
+}
+
+#[test]
+fn test_retry_policy_delays() {
+    use std::time::Duration;
+
+    use near_workspaces::rpc::retry::RetryPolicy;
+
+    let delays = |policy: RetryPolicy| policy.jitter(false).delays().collect::<Vec<_>>();
+    assert_eq!(
+        delays(RetryPolicy::default()),
+        [5, 25, 125, 625].map(Duration::from_millis)
+    );
+    assert_eq!(
+        delays(
+            RetryPolicy::default()
+                .max_retries(5)
+                .initial_delay(Duration::from_millis(100))
+                .multiplier(2)
+                .max_delay(Duration::from_millis(500))
+        ),
+        [100, 200, 400, 500, 500].map(Duration::from_millis)
+    );
+    assert!(delays(RetryPolicy::none()).is_empty());
+}
+
+#[test]
+fn test_signer_from_credentials_dir() -> anyhow::Result<()> {
+    use near_workspaces::InMemorySigner;
+
+    let dir = tempfile::tempdir()?;
+    let alice: AccountId = "alice.testnet".parse()?;
+    let bob: AccountId = "bob.near".parse()?;
+    let (alice_sk, bob_sk) = (
+        SecretKey::from_seed(KeyType::ED25519, "alice"),
+        SecretKey::from_seed(KeyType::ED25519, "bob"),
+    );
+
+    // near-cli writes `<network>/<account_id>.json` files, with a `private_key` field.
+    std::fs::create_dir_all(dir.path().join("testnet"))?;
+    std::fs::write(
+        dir.path().join("testnet").join("alice.testnet.json"),
+        serde_json::to_string(&serde_json::json!({
+            "account_id": alice,
+            "public_key": alice_sk.public_key(),
+            "private_key": alice_sk,
+        }))?,
+    )?;
+    // near-cli-rs writes `<network>/<account_id>/<public_key>.json` files.
+    let bob_dir = dir.path().join("mainnet").join("bob.near");
+    std::fs::create_dir_all(&bob_dir)?;
+    std::fs::write(
+        bob_dir.join(format!("{}.json", bob_sk.public_key())),
+        serde_json::to_string(&serde_json::json!({
+            "account_id": bob,
+            "public_key": bob_sk.public_key(),
+            "private_key": bob_sk,
+            "master_seed_phrase": "unused",
+        }))?,
+    )?;
+
+    let signer = InMemorySigner::from_credentials_in(dir.path(), &alice)?;
+    assert_eq!(signer.account_id(), &alice);
+    assert_eq!(signer.secret_key(), &alice_sk);
+
+    let signer = InMemorySigner::from_credentials_in(dir.path(), &bob)?;
+    assert_eq!(signer.account_id(), &bob);
+    assert_eq!(signer.secret_key(), &bob_sk);
+
+    assert!(InMemorySigner::from_credentials_in(dir.path(), &"carol.near".parse()?).is_err());
+
+    Ok(())
+}